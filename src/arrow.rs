@@ -0,0 +1,518 @@
+// src/arrow.rs
+
+//! Arrow record batches from RESO records (requires the `arrow` feature)
+//!
+//! Converts JSON records into Arrow [`RecordBatch`]es with a schema derived
+//! from a parsed `$metadata` entity type, for loading into columnar engines or
+//! writing Parquet (see [`ParquetSink`](crate::ParquetSink)).
+//!
+//! # Type Mapping
+//!
+//! | EDM type | Arrow type |
+//! |----------|------------|
+//! | `Edm.String`, `Edm.Guid`, `Edm.TimeOfDay`, enum types | `Utf8` |
+//! | `Edm.Boolean` | `Boolean` |
+//! | `Edm.Byte`, `Edm.SByte`, `Edm.Int16`, `Edm.Int32`, `Edm.Int64` | `UInt8`, `Int8`, `Int16`, `Int32`, `Int64` |
+//! | `Edm.Decimal` with `Precision` ≤ 38 and `Scale` | `Decimal128(precision, scale)` |
+//! | `Edm.Decimal` without those facets, `Edm.Double` | `Float64` |
+//! | `Edm.Single` | `Float32` |
+//! | `Edm.Date` | `Date32` |
+//! | `Edm.DateTimeOffset` | `Timestamp(Microsecond, "UTC")` |
+//! | `Collection(T)` | `List(T)` |
+//! | anything else (complex types) | `Utf8` holding JSON text |
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ResoClient, QueryBuilder, RecordBatchConverter};
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let metadata = client.fetch_parsed_metadata().await?;
+//! let property = metadata.entity_type("Property").unwrap();
+//!
+//! let converter = RecordBatchConverter::from_entity_type(property)
+//!     .select(&["ListingKey", "ListPrice", "ModificationTimestamp"])?;
+//!
+//! let query = QueryBuilder::new("Property")
+//!     .select(&["ListingKey", "ListPrice", "ModificationTimestamp"])
+//!     .top(100)
+//!     .build()?;
+//! let response = client.execute(&query).await?;
+//!
+//! let batch = converter.convert(response["value"].as_array().unwrap())?;
+//! println!("{} rows, {} columns", batch.num_rows(), batch.num_columns());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::metadata::{EntityType, Property};
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, Decimal128Array, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, ListArray, StringArray, TimestampMicrosecondArray,
+    UInt8Array,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer, ScalarBuffer};
+use arrow_schema::{DataType, Field, FieldRef, TimeUnit};
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+pub use arrow_array::RecordBatch;
+pub use arrow_schema::{Schema, SchemaRef};
+
+/// Arrow schema with one field per structural property of an entity type
+pub fn entity_schema(entity_type: &EntityType) -> Schema {
+    Schema::new(
+        entity_type
+            .properties
+            .iter()
+            .map(property_field)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Converts JSON records into Arrow record batches with a fixed schema
+///
+/// Fields missing from a record become nulls. A value that does not fit its
+/// column's type fails the conversion with [`ResoError::Parse`].
+#[derive(Debug, Clone)]
+pub struct RecordBatchConverter {
+    schema: SchemaRef,
+}
+
+impl RecordBatchConverter {
+    /// Create a converter for an explicit schema
+    pub fn new(schema: impl Into<SchemaRef>) -> Self {
+        Self {
+            schema: schema.into(),
+        }
+    }
+
+    /// Create a converter for every structural property of an entity type
+    pub fn from_entity_type(entity_type: &EntityType) -> Self {
+        Self::new(entity_schema(entity_type))
+    }
+
+    /// Keep only the given fields, in the given order
+    ///
+    /// Typically the query's `$select` list. Returns [`ResoError::InvalidQuery`]
+    /// if a field is not part of the schema.
+    pub fn select(self, fields: &[&str]) -> Result<Self> {
+        let selected = fields
+            .iter()
+            .map(|name| {
+                self.schema
+                    .field_with_name(name)
+                    .cloned()
+                    .map_err(|_| ResoError::InvalidQuery(format!("Unknown field: {}", name)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(Schema::new(selected)))
+    }
+
+    /// Schema of the produced batches
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Convert a slice of records into one record batch
+    pub fn convert(&self, records: &[JsonValue]) -> Result<RecordBatch> {
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let values: Vec<Option<&JsonValue>> = records
+                    .iter()
+                    .map(|record| record.get(field.name()).filter(|v| !v.is_null()))
+                    .collect();
+                build_array(field, &values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| ResoError::Parse(format!("Invalid record batch: {}", e)))
+    }
+}
+
+fn property_field(property: &Property) -> Field {
+    let element = edm_data_type(property);
+    let data_type = if property.is_collection() {
+        DataType::List(Arc::new(Field::new_list_field(element, true)))
+    } else {
+        element
+    };
+    Field::new(&property.name, data_type, property.nullable)
+}
+
+/// Arrow type of a single (non-collection) value of `property`
+fn edm_data_type(property: &Property) -> DataType {
+    match property.element_type() {
+        "Edm.Boolean" => DataType::Boolean,
+        "Edm.Byte" => DataType::UInt8,
+        "Edm.SByte" => DataType::Int8,
+        "Edm.Int16" => DataType::Int16,
+        "Edm.Int32" => DataType::Int32,
+        "Edm.Int64" => DataType::Int64,
+        "Edm.Decimal" => match (property.precision, property.scale) {
+            (Some(precision), Some(scale))
+                if (1..=38).contains(&precision) && scale <= precision =>
+            {
+                DataType::Decimal128(precision as u8, scale as i8)
+            }
+            _ => DataType::Float64,
+        },
+        "Edm.Double" => DataType::Float64,
+        "Edm.Single" => DataType::Float32,
+        "Edm.Date" => DataType::Date32,
+        "Edm.DateTimeOffset" => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        _ => DataType::Utf8,
+    }
+}
+
+fn build_array(field: &Field, values: &[Option<&JsonValue>]) -> Result<ArrayRef> {
+    let name = field.name();
+    Ok(match field.data_type() {
+        DataType::Utf8 => Arc::new(StringArray::from(
+            values.iter().map(|v| v.map(text)).collect::<Vec<_>>(),
+        )),
+        DataType::Boolean => Arc::new(BooleanArray::from(typed(name, values, JsonValue::as_bool)?)),
+        DataType::UInt8 => Arc::new(UInt8Array::from(typed(name, values, |v| {
+            v.as_u64().and_then(|n| n.try_into().ok())
+        })?)),
+        DataType::Int8 => Arc::new(Int8Array::from(typed(name, values, |v| {
+            v.as_i64().and_then(|n| n.try_into().ok())
+        })?)),
+        DataType::Int16 => Arc::new(Int16Array::from(typed(name, values, |v| {
+            v.as_i64().and_then(|n| n.try_into().ok())
+        })?)),
+        DataType::Int32 => Arc::new(Int32Array::from(typed(name, values, |v| {
+            v.as_i64().and_then(|n| n.try_into().ok())
+        })?)),
+        DataType::Int64 => Arc::new(Int64Array::from(typed(name, values, JsonValue::as_i64)?)),
+        DataType::Float32 => Arc::new(Float32Array::from(typed(name, values, |v| {
+            v.as_f64().map(|n| n as f32)
+        })?)),
+        DataType::Float64 => Arc::new(Float64Array::from(typed(name, values, |v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        })?)),
+        DataType::Decimal128(precision, scale) => Arc::new(
+            Decimal128Array::from(typed(name, values, |v| parse_decimal(v, *scale))?)
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(|e| conversion_error(name, e))?,
+        ),
+        DataType::Date32 => Arc::new(Date32Array::from(typed(name, values, |v| {
+            v.as_str().and_then(parse_date)
+        })?)),
+        DataType::Timestamp(TimeUnit::Microsecond, timezone) => Arc::new(
+            TimestampMicrosecondArray::from(typed(name, values, |v| {
+                v.as_str().and_then(parse_timestamp)
+            })?)
+            .with_timezone_opt(timezone.clone()),
+        ),
+        DataType::List(item) => build_list(name, item, values)?,
+        other => {
+            return Err(ResoError::Parse(format!(
+                "Field {}: unsupported Arrow type {}",
+                name, other
+            )))
+        }
+    })
+}
+
+fn build_list(name: &str, item: &FieldRef, values: &[Option<&JsonValue>]) -> Result<ArrayRef> {
+    let mut offsets = Vec::with_capacity(values.len() + 1);
+    let mut elements = Vec::new();
+    offsets.push(0i32);
+
+    for value in values {
+        match value {
+            Some(JsonValue::Array(items)) => {
+                elements.extend(items.iter().map(|v| Some(v).filter(|v| !v.is_null())));
+            }
+            Some(other) => {
+                return Err(conversion_error(
+                    name,
+                    format!("expected a collection, got {}", other),
+                ))
+            }
+            None => {}
+        }
+        let len = i32::try_from(elements.len())
+            .map_err(|_| conversion_error(name, "collection too large"))?;
+        offsets.push(len);
+    }
+
+    let child = build_array(item, &elements)?;
+    let nulls = NullBuffer::from(values.iter().map(Option::is_some).collect::<Vec<_>>());
+    ListArray::try_new(
+        item.clone(),
+        OffsetBuffer::new(ScalarBuffer::from(offsets)),
+        child,
+        Some(nulls),
+    )
+    .map(|array| Arc::new(array) as ArrayRef)
+    .map_err(|e| conversion_error(name, e))
+}
+
+/// Convert non-null values with `f`, failing on values it rejects
+fn typed<T>(
+    name: &str,
+    values: &[Option<&JsonValue>],
+    f: impl Fn(&JsonValue) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    values
+        .iter()
+        .map(|value| match value {
+            None => Ok(None),
+            Some(v) => f(v)
+                .map(Some)
+                .ok_or_else(|| conversion_error(name, format!("unexpected value {}", v))),
+        })
+        .collect()
+}
+
+fn text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse a JSON number or numeric string into a decimal scaled by `scale`
+///
+/// Extra fractional digits are rounded half away from zero.
+fn parse_decimal(value: &JsonValue, scale: i8) -> Option<i128> {
+    let raw = match value {
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => s.trim().to_string(),
+        _ => return None,
+    };
+    let scale = usize::try_from(scale).ok()?;
+
+    // Exponent notation is only produced for very large or small floats
+    let raw = if raw.contains(['e', 'E']) {
+        format!("{:.*}", scale, raw.parse::<f64>().ok()?)
+    } else {
+        raw
+    };
+
+    let (negative, digits) = match raw.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, raw.strip_prefix('+').unwrap_or(&raw)),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if int_part.is_empty() && frac_part.is_empty()
+        || !int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let mut frac: String = frac_part.chars().take(scale).collect();
+    while frac.len() < scale {
+        frac.push('0');
+    }
+    let all_digits = format!("{}{}", int_part, frac);
+    let significant = all_digits.trim_start_matches('0');
+    let mut unscaled: i128 = if significant.is_empty() {
+        0
+    } else {
+        significant.parse().ok()?
+    };
+    if frac_part.as_bytes().get(scale).is_some_and(|d| *d >= b'5') {
+        unscaled = unscaled.checked_add(1)?;
+    }
+
+    Some(if negative { -unscaled } else { unscaled })
+}
+
+/// Days since the Unix epoch for an `Edm.Date` (`YYYY-MM-DD`)
+fn parse_date(value: &str) -> Option<i32> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    i32::try_from((date - epoch).num_days()).ok()
+}
+
+/// Microseconds since the Unix epoch for an `Edm.DateTimeOffset`
+fn parse_timestamp(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.timestamp_micros())
+}
+
+fn conversion_error(field: &str, error: impl std::fmt::Display) -> ResoError {
+    ResoError::Parse(format!("Field {}: {}", field, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use serde_json::json;
+
+    fn property(name: &str, type_name: &str) -> Property {
+        Property {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            nullable: true,
+            ..Default::default()
+        }
+    }
+
+    fn entity_type() -> EntityType {
+        EntityType {
+            name: "Property".to_string(),
+            key: vec!["ListingKey".to_string()],
+            properties: vec![
+                Property {
+                    nullable: false,
+                    ..property("ListingKey", "Edm.String")
+                },
+                Property {
+                    precision: Some(14),
+                    scale: Some(2),
+                    ..property("ListPrice", "Edm.Decimal")
+                },
+                property("BedroomsTotal", "Edm.Int32"),
+                property("ListingContractDate", "Edm.Date"),
+                property("ModificationTimestamp", "Edm.DateTimeOffset"),
+                property(
+                    "Appliances",
+                    "Collection(org.reso.metadata.enums.Appliances)",
+                ),
+                property("Latitude", "Edm.Decimal"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_entity_schema_types() {
+        let schema = entity_schema(&entity_type());
+
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        assert!(!schema.field(0).is_nullable());
+        assert_eq!(schema.field(1).data_type(), &DataType::Decimal128(14, 2));
+        assert_eq!(schema.field(2).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(3).data_type(), &DataType::Date32);
+        assert_eq!(
+            schema.field(4).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert_eq!(
+            schema.field(5).data_type(),
+            &DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+        );
+        // No facets: fall back to floating point
+        assert_eq!(schema.field(6).data_type(), &DataType::Float64);
+    }
+
+    #[test]
+    fn test_convert_records() {
+        let converter = RecordBatchConverter::from_entity_type(&entity_type());
+        let batch = converter
+            .convert(&[
+                json!({
+                    "ListingKey": "1",
+                    "ListPrice": 450000.5,
+                    "BedroomsTotal": 3,
+                    "ListingContractDate": "1970-01-11",
+                    "ModificationTimestamp": "1970-01-01T00:00:01.5+00:00",
+                    "Appliances": ["Dishwasher", "Range"],
+                    "Latitude": 30.25
+                }),
+                json!({"ListingKey": "2", "Appliances": null}),
+            ])
+            .unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+
+        let price = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(price.value(0), 45_000_050);
+        assert!(price.is_null(1));
+
+        let date = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(date.value(0), 10);
+
+        let timestamp = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(timestamp.value(0), 1_500_000);
+
+        let appliances = batch
+            .column(5)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        assert_eq!(appliances.value_length(0), 2);
+        assert!(appliances.is_null(1));
+    }
+
+    #[test]
+    fn test_select_projects_schema() {
+        let converter = RecordBatchConverter::from_entity_type(&entity_type())
+            .select(&["ListPrice", "ListingKey"])
+            .unwrap();
+
+        let names: Vec<_> = converter
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(names, vec!["ListPrice", "ListingKey"]);
+    }
+
+    #[test]
+    fn test_select_unknown_field() {
+        let result = RecordBatchConverter::from_entity_type(&entity_type()).select(&["Nope"]);
+        assert!(matches!(result, Err(ResoError::InvalidQuery(_))));
+    }
+
+    #[test]
+    fn test_wrong_type_is_an_error() {
+        let converter = RecordBatchConverter::from_entity_type(&entity_type());
+        let result = converter.convert(&[json!({"ListingKey": "1", "BedroomsTotal": "three"})]);
+        assert!(matches!(result, Err(ResoError::Parse(_))));
+    }
+
+    #[test]
+    fn test_missing_required_field_is_an_error() {
+        let converter = RecordBatchConverter::from_entity_type(&entity_type());
+        assert!(converter.convert(&[json!({"ListPrice": 1})]).is_err());
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal(&json!(450000.5), 2), Some(45_000_050));
+        assert_eq!(parse_decimal(&json!("-1.005"), 2), Some(-101));
+        assert_eq!(parse_decimal(&json!(12), 2), Some(1200));
+        assert_eq!(parse_decimal(&json!("0.004"), 2), Some(0));
+        assert_eq!(
+            parse_decimal(&json!(1e20), 0),
+            Some(100_000_000_000_000_000_000)
+        );
+        assert_eq!(parse_decimal(&json!("abc"), 2), None);
+        assert_eq!(parse_decimal(&json!(true), 2), None);
+    }
+
+    #[test]
+    fn test_parse_timestamp_offsets() {
+        assert_eq!(parse_timestamp("1970-01-01T01:00:00+01:00"), Some(0));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("not a date"), None);
+    }
+}
@@ -0,0 +1,197 @@
+// src/auth.rs
+
+//! OAuth 2.0 client-credentials authentication
+//!
+//! Many MLS feeds issue short-lived bearer tokens from a token endpoint
+//! instead of a long-lived token. With [`ClientCredentials`] attached to the
+//! client configuration, the client requests a token on first use, reuses it
+//! until shortly before it expires, and fetches a new one when the server
+//! answers `401 Unauthorized`. Authentication runs as a
+//! [middleware](crate::middleware) after retries and rate limiting.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ClientConfig, ClientCredentials};
+//! let credentials = ClientCredentials::new(
+//!     "https://auth.mls.com/oauth/token",
+//!     "my-client-id",
+//!     std::env::var("MLS_CLIENT_SECRET")?,
+//! )
+//! .with_scope("api");
+//!
+//! let config = ClientConfig::new("https://api.mls.com/odata", "")
+//!     .with_client_credentials(credentials);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::middleware::{set_bearer, Middleware, Next, Request, Response};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Renew tokens this long before they expire
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// OAuth 2.0 client-credentials grant settings
+#[derive(Clone, PartialEq, Eq)]
+pub struct ClientCredentials {
+    /// Token endpoint URL
+    pub token_url: String,
+
+    /// Client identifier
+    pub client_id: String,
+
+    /// Client secret
+    pub client_secret: String,
+
+    /// Optional space-separated scopes
+    pub scope: Option<String>,
+}
+
+impl std::fmt::Debug for ClientCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientCredentials")
+            .field("token_url", &self.token_url)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"<redacted>")
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl ClientCredentials {
+    /// Credentials for `token_url`
+    pub fn new(
+        token_url: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            token_url: token_url.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+        }
+    }
+
+    /// Request these scopes
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// Authenticates requests with client-credentials tokens
+///
+/// A request rejected with `401 Unauthorized` is sent once more with a newly
+/// fetched token.
+pub(crate) struct ClientCredentialsAuth {
+    pub(crate) credentials: ClientCredentials,
+    pub(crate) http_client: reqwest::Client,
+    pub(crate) tokens: TokenCache,
+}
+
+#[async_trait]
+impl Middleware for ClientCredentialsAuth {
+    async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response> {
+        let retry = request.try_clone();
+        let token = self
+            .tokens
+            .get(&self.http_client, &self.credentials)
+            .await?;
+        set_bearer(&mut request, &token)?;
+        let response = next.run(request).await?;
+
+        match retry {
+            Some(mut retry) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                self.tokens.invalidate().await;
+                let token = self
+                    .tokens
+                    .get(&self.http_client, &self.credentials)
+                    .await?;
+                set_bearer(&mut retry, &token)?;
+                next.run(retry).await
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Cached access token
+#[derive(Debug, Default)]
+pub(crate) struct TokenCache {
+    token: Mutex<Option<(String, Option<Instant>)>>,
+}
+
+impl TokenCache {
+    /// A valid access token, fetched if missing or about to expire
+    pub(crate) async fn get(
+        &self,
+        http_client: &reqwest::Client,
+        credentials: &ClientCredentials,
+    ) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some((value, expires)) = token.as_ref() {
+            if expires.is_none_or(|at| Instant::now() < at) {
+                return Ok(value.clone());
+            }
+        }
+
+        let (value, expires_in) = fetch_token(http_client, credentials).await?;
+        let expires = expires_in
+            .map(|secs| Instant::now() + Duration::from_secs(secs).saturating_sub(EXPIRY_MARGIN));
+        *token = Some((value.clone(), expires));
+        Ok(value)
+    }
+
+    /// Forget the cached token so the next request fetches a new one
+    pub(crate) async fn invalidate(&self) {
+        *self.token.lock().await = None;
+    }
+}
+
+async fn fetch_token(
+    http_client: &reqwest::Client,
+    credentials: &ClientCredentials,
+) -> Result<(String, Option<u64>)> {
+    use tracing::debug;
+
+    debug!("Requesting access token from {}", credentials.token_url);
+    let mut form = vec![
+        ("grant_type", "client_credentials"),
+        ("client_id", credentials.client_id.as_str()),
+        ("client_secret", credentials.client_secret.as_str()),
+    ];
+    if let Some(scope) = &credentials.scope {
+        form.push(("scope", scope));
+    }
+
+    let response = http_client
+        .post(&credentials.token_url)
+        .header("Accept", "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| ResoError::Network(format!("Token request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ResoError::from_status(status.as_u16(), &body));
+    }
+
+    let token: TokenResponse = response
+        .json()
+        .await
+        .map_err(|e| ResoError::Parse(format!("Invalid token response: {}", e)))?;
+    Ok((token.access_token, token.expires_in))
+}
@@ -0,0 +1,437 @@
+// src/bin/reso/main.rs
+
+//! `reso` - ad-hoc queries and exports against a RESO Web API server
+//!
+//! Connection settings come from the environment, as in
+//! [`ClientConfig::from_env`]: `RESO_BASE_URL`, `RESO_TOKEN` and the optional
+//! `RESO_DATASET_ID` and `RESO_TIMEOUT`.
+//!
+//! ```text
+//! reso query Property --filter "City eq 'Austin'" --select ListingKey,ListPrice --top 5 --format table
+//! reso get Property 12345 --expand Media
+//! reso count Property --filter "StandardStatus eq 'Active'"
+//! reso metadata --resource Property
+//! reso replicate Property --output ./export --format csv
+//! reso fields Property --sample 500
+//! ```
+
+mod output;
+
+use clap::{Args, Parser, Subcommand};
+use output::{write_records, Format};
+use reso_client::compliance::is_populated;
+use reso_client::{
+    ClientConfig, CsvFileSink, JsonFileSink, MemorySink, QueryBuilder, ReplicationQueryBuilder,
+    ResoClient,
+};
+use serde_json::{json, Value as JsonValue};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Debug, Parser)]
+#[command(name = "reso", version, about = "Query and export RESO Web API data")]
+struct Cli {
+    /// Output format (default: json; ndjson for replicate)
+    #[arg(long, short, value_enum, global = true)]
+    format: Option<Format>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run a query and print the matching records
+    Query(QueryArgs),
+
+    /// Fetch one record by key
+    Get {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Key value
+        key: String,
+        /// Fields to return, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        select: Vec<String>,
+        /// Navigation properties to expand, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        expand: Vec<String>,
+    },
+
+    /// Print the number of matching records
+    Count {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// OData $filter expression
+        #[arg(long)]
+        filter: Option<String>,
+    },
+
+    /// Print $metadata, summarized by default
+    Metadata {
+        /// Print the raw EDMX document
+        #[arg(long, conflicts_with = "resource")]
+        raw: bool,
+        /// List the properties of one resource
+        #[arg(long)]
+        resource: Option<String>,
+    },
+
+    /// Replicate a resource into export files
+    Replicate {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Directory for the part files and manifest
+        #[arg(long, short)]
+        output: PathBuf,
+        /// File name prefix (default: the resource name)
+        #[arg(long)]
+        prefix: Option<String>,
+        /// OData $filter expression
+        #[arg(long)]
+        filter: Option<String>,
+        /// Fields to export, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        select: Vec<String>,
+        /// Records per page
+        #[arg(long)]
+        top: Option<u32>,
+    },
+
+    /// Report how often each field is populated in a sample
+    Fields {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Number of records to sample
+        #[arg(long, default_value_t = 200)]
+        sample: u32,
+        /// OData $filter expression
+        #[arg(long)]
+        filter: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+struct QueryArgs {
+    /// Resource name, e.g. Property
+    resource: String,
+    /// OData $filter expression
+    #[arg(long)]
+    filter: Option<String>,
+    /// Fields to return, comma-separated
+    #[arg(long, value_delimiter = ',')]
+    select: Vec<String>,
+    /// Navigation properties to expand, comma-separated
+    #[arg(long, value_delimiter = ',')]
+    expand: Vec<String>,
+    /// Sort order, e.g. "ListPrice desc" or "City asc, ListPrice desc"
+    #[arg(long)]
+    orderby: Option<String>,
+    /// Maximum number of records
+    #[arg(long)]
+    top: Option<u32>,
+    /// Number of records to skip
+    #[arg(long)]
+    skip: Option<u32>,
+    /// Print @odata.count to stderr
+    #[arg(long)]
+    count: bool,
+    /// OData $apply transformation
+    #[arg(long)]
+    apply: Option<String>,
+    /// Follow @odata.nextLink through every page
+    #[arg(long)]
+    all: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> CliResult<()> {
+    let client = ResoClient::with_config(ClientConfig::from_env()?)?;
+    let format = cli.format;
+    let print = |records: &[JsonValue], leading: &[&str]| -> CliResult<()> {
+        let mut out = BufWriter::new(io::stdout().lock());
+        write_records(&mut out, records, leading, format.unwrap_or(Format::Json))?;
+        out.flush()?;
+        Ok(())
+    };
+
+    match cli.command {
+        Command::Query(args) => {
+            let select = args.select.clone();
+            print(&query(&client, args).await?, &strs(&select))
+        }
+        Command::Get {
+            resource,
+            key,
+            select,
+            expand,
+        } => {
+            let mut builder = QueryBuilder::by_key(resource, key);
+            if !select.is_empty() {
+                builder = builder.select(&strs(&select));
+            }
+            if !expand.is_empty() {
+                builder = builder.expand(&strs(&expand));
+            }
+            let record = client.execute_by_key(&builder.build()?).await?;
+            print(&[record], &strs(&select))
+        }
+        Command::Count { resource, filter } => {
+            let mut builder = QueryBuilder::new(resource).count();
+            if let Some(filter) = filter {
+                builder = builder.filter(filter);
+            }
+            println!("{}", client.execute_count(&builder.build()?).await?);
+            Ok(())
+        }
+        Command::Metadata { raw: true, .. } => {
+            println!("{}", client.fetch_metadata().await?);
+            Ok(())
+        }
+        Command::Metadata { resource, .. } => {
+            let leading: &[&str] = match resource {
+                Some(_) => &["name", "type", "key", "nullable", "lookupName"],
+                None => &["entityType", "key", "properties", "navigationProperties"],
+            };
+            print(&metadata(&client, resource).await?, leading)
+        }
+        Command::Replicate {
+            resource,
+            output,
+            prefix,
+            filter,
+            select,
+            top,
+        } => {
+            replicate(
+                &client,
+                format.unwrap_or(Format::Ndjson),
+                ReplicateArgs {
+                    prefix: prefix.unwrap_or_else(|| resource.clone()),
+                    resource,
+                    output,
+                    filter,
+                    select,
+                    top,
+                },
+            )
+            .await
+        }
+        Command::Fields {
+            resource,
+            sample,
+            filter,
+        } => {
+            let mut builder = QueryBuilder::new(resource).top(sample);
+            if let Some(filter) = filter {
+                builder = builder.filter(filter);
+            }
+            let response = client.execute(&builder.build()?).await?;
+            print(
+                &field_population(&records_of(response)),
+                &["field", "populated", "rate", "example"],
+            )
+        }
+    }
+}
+
+async fn query(client: &ResoClient, args: QueryArgs) -> CliResult<Vec<JsonValue>> {
+    let mut builder = QueryBuilder::new(args.resource);
+    if let Some(filter) = args.filter {
+        builder = builder.filter(filter);
+    }
+    if !args.select.is_empty() {
+        builder = builder.select(&strs(&args.select));
+    }
+    if !args.expand.is_empty() {
+        builder = builder.expand(&strs(&args.expand));
+    }
+    if let Some(orderby) = args.orderby {
+        builder = builder.order_by_expression(orderby);
+    }
+    if let Some(top) = args.top {
+        builder = builder.top(top);
+    }
+    if let Some(skip) = args.skip {
+        builder = builder.skip(skip);
+    }
+    if args.count {
+        builder = builder.with_count();
+    }
+    if let Some(apply) = args.apply {
+        builder = builder.apply(apply);
+    }
+    let query = builder.build()?;
+
+    if args.all {
+        let mut sink = MemorySink::new();
+        client.execute_into(&query, &mut sink).await?;
+        return Ok(sink.into_records());
+    }
+
+    let response = client.execute(&query).await?;
+    if let Some(count) = response.get("@odata.count") {
+        eprintln!("Total: {}", count);
+    }
+    if let Some(next_link) = response.get("@odata.nextLink").and_then(JsonValue::as_str) {
+        eprintln!("More results: {} (use --all to follow)", next_link);
+    }
+    Ok(records_of(response))
+}
+
+/// Entity types, or the properties of one resource
+async fn metadata(client: &ResoClient, resource: Option<String>) -> CliResult<Vec<JsonValue>> {
+    let metadata = client.fetch_parsed_metadata().await?;
+
+    let Some(resource) = resource else {
+        return Ok(metadata
+            .entity_types
+            .iter()
+            .map(|entity| {
+                json!({
+                    "entityType": entity.qualified_name(),
+                    "key": entity.key.join(","),
+                    "properties": entity.properties.len(),
+                    "navigationProperties": entity.navigation_properties.len(),
+                })
+            })
+            .collect());
+    };
+
+    let entity = metadata
+        .entity_type_for_resource(&resource)
+        .ok_or_else(|| format!("$metadata has no entity type for {}", resource))?;
+    Ok(entity
+        .properties
+        .iter()
+        .map(|property| {
+            json!({
+                "name": property.name,
+                "type": property.type_name,
+                "nullable": property.nullable,
+                "key": entity.key.contains(&property.name),
+                "lookupName": property.lookup_name,
+            })
+        })
+        .collect())
+}
+
+struct ReplicateArgs {
+    resource: String,
+    output: PathBuf,
+    prefix: String,
+    filter: Option<String>,
+    select: Vec<String>,
+    top: Option<u32>,
+}
+
+async fn replicate(client: &ResoClient, format: Format, args: ReplicateArgs) -> CliResult<()> {
+    let mut builder = ReplicationQueryBuilder::new(&args.resource);
+    if let Some(filter) = &args.filter {
+        builder = builder.filter(filter);
+    }
+    if !args.select.is_empty() {
+        builder = builder.select(&strs(&args.select));
+    }
+    if let Some(top) = args.top {
+        builder = builder.top(top);
+    }
+    let query = builder.build()?;
+
+    let (progress, manifest) = match format {
+        Format::Ndjson | Format::Json => {
+            let mut sink = match format {
+                Format::Ndjson => JsonFileSink::ndjson(&args.output, &args.prefix),
+                _ => JsonFileSink::json_array(&args.output, &args.prefix),
+            };
+            let progress = client.replicate_into(&query, &mut sink).await?;
+            (progress, sink.finish()?)
+        }
+        Format::Csv => {
+            let mut sink = if args.select.is_empty() {
+                let metadata = client.fetch_parsed_metadata().await?;
+                let entity = metadata
+                    .entity_type_for_resource(&args.resource)
+                    .ok_or_else(|| format!("$metadata has no entity type for {}", args.resource))?;
+                CsvFileSink::for_entity_type(&args.output, &args.prefix, entity)
+            } else {
+                CsvFileSink::for_replication_query(&args.output, &args.prefix, &query)?
+            };
+            let progress = client.replicate_into(&query, &mut sink).await?;
+            (progress, sink.finish()?)
+        }
+        Format::Table => return Err("replicate writes json, ndjson or csv files".into()),
+    };
+
+    eprintln!(
+        "Replicated {} records in {} pages to {} ({} parts)",
+        progress.records_done(),
+        progress.pages_fetched,
+        args.output.display(),
+        manifest.parts.len()
+    );
+    Ok(())
+}
+
+/// Populated count and rate per field, most populated first
+fn field_population(records: &[JsonValue]) -> Vec<JsonValue> {
+    let mut stats: Vec<(String, usize, Option<String>)> = Vec::new();
+    for object in records.iter().filter_map(JsonValue::as_object) {
+        for (field, value) in object.iter().filter(|(f, _)| !f.contains('@')) {
+            let index = match stats.iter().position(|(name, _, _)| name == field) {
+                Some(index) => index,
+                None => {
+                    stats.push((field.clone(), 0, None));
+                    stats.len() - 1
+                }
+            };
+            if is_populated(value) {
+                let entry = &mut stats[index];
+                entry.1 += 1;
+                entry.2.get_or_insert_with(|| match value {
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                });
+            }
+        }
+    }
+    stats.sort_by_key(|(_, populated, _)| std::cmp::Reverse(*populated));
+
+    stats
+        .into_iter()
+        .map(|(field, populated, example)| {
+            let rate = populated as f64 * 100.0 / records.len().max(1) as f64;
+            json!({
+                "field": field,
+                "populated": populated,
+                "rate": (rate * 10.0).round() / 10.0,
+                "example": example,
+            })
+        })
+        .collect()
+}
+
+fn records_of(mut response: JsonValue) -> Vec<JsonValue> {
+    match response.get_mut("value").map(JsonValue::take) {
+        Some(JsonValue::Array(records)) => records,
+        _ => Vec::new(),
+    }
+}
+
+fn strs(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
@@ -0,0 +1,198 @@
+// src/bin/reso/output.rs
+
+//! Rendering records as JSON, NDJSON, CSV or an aligned table
+
+use serde_json::Value as JsonValue;
+use std::io::{self, Write};
+
+/// Widest table cell before truncation
+const MAX_CELL_WIDTH: usize = 40;
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Pretty-printed JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Aligned columns for reading in a terminal
+    Table,
+}
+
+/// Write records in `format`
+///
+/// CSV and table columns start with `leading`, followed by the remaining
+/// fields in the order they first appear.
+pub fn write_records(
+    out: &mut impl Write,
+    records: &[JsonValue],
+    leading: &[&str],
+    format: Format,
+) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)
+        }
+        Format::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        Format::Csv => {
+            let columns = columns(records, leading);
+            let header: Vec<String> = columns.iter().map(|c| csv_escape(c)).collect();
+            writeln!(out, "{}", header.join(","))?;
+            for record in records {
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|c| csv_escape(&cell(record.get(c))))
+                    .collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+            Ok(())
+        }
+        Format::Table => write_table(out, records, leading),
+    }
+}
+
+fn write_table(out: &mut impl Write, records: &[JsonValue], leading: &[&str]) -> io::Result<()> {
+    let columns = columns(records, leading);
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|c| truncate(&cell(record.get(c))))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    writeln!(out, "{}", line(&columns))?;
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    writeln!(out, "{}", line(&rule))?;
+    for row in &rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    writeln!(out, "({} rows)", rows.len())
+}
+
+/// `leading`, then other field names, without OData annotations
+fn columns(records: &[JsonValue], leading: &[&str]) -> Vec<String> {
+    let mut columns: Vec<String> = leading.iter().map(|c| c.to_string()).collect();
+    for object in records.iter().filter_map(JsonValue::as_object) {
+        for name in object.keys() {
+            if !name.contains('@') && !columns.contains(name) {
+                columns.push(name.clone());
+            }
+        }
+    }
+    columns
+}
+
+/// Text of a value: strings unquoted, collections of scalars comma-joined
+fn cell(value: Option<&JsonValue>) -> String {
+    match value {
+        None | Some(JsonValue::Null) => String::new(),
+        Some(JsonValue::String(s)) => s.clone(),
+        Some(JsonValue::Array(items)) if items.iter().all(|v| !v.is_array() && !v.is_object()) => {
+            items
+                .iter()
+                .map(|v| cell(Some(v)))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        Some(other) => other.to_string(),
+    }
+}
+
+fn csv_escape(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn truncate(text: &str) -> String {
+    let single_line = text.replace(['\n', '\r'], " ");
+    if single_line.chars().count() <= MAX_CELL_WIDTH {
+        single_line
+    } else {
+        let kept: String = single_line.chars().take(MAX_CELL_WIDTH - 1).collect();
+        format!("{}…", kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(records: &[JsonValue], leading: &[&str], format: Format) -> String {
+        let mut out = Vec::new();
+        write_records(&mut out, records, leading, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_columns_and_escaping() {
+        let records = vec![
+            json!({"ListingKey": "1", "City": "Austin, TX", "@odata.etag": "W/1"}),
+            json!({"ListingKey": "2", "Appliances": ["Range", "Oven"], "Remarks": "4\" trim"}),
+        ];
+
+        assert_eq!(
+            render(&records, &["ListingKey"], Format::Csv),
+            "ListingKey,City,Appliances,Remarks\n\
+             1,\"Austin, TX\",,\n\
+             2,,\"Range,Oven\",\"4\"\" trim\"\n"
+        );
+    }
+
+    #[test]
+    fn test_table_alignment() {
+        let records = vec![
+            json!({"Key": "1", "Remarks": "x".repeat(50)}),
+            json!({"Key": "22", "Remarks": null}),
+        ];
+        let table = render(&records, &[], Format::Table);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[0], format!("Key  {:<40}", "Remarks").trim_end());
+        assert_eq!(lines[1], format!("---  {}", "-".repeat(40)));
+        assert_eq!(lines[2], format!("1    {}…", "x".repeat(39)));
+        assert_eq!(lines[3], "22");
+        assert_eq!(lines[4], "(2 rows)");
+        assert_eq!(
+            render(&records[1..], &[], Format::Ndjson),
+            "{\"Key\":\"22\",\"Remarks\":null}\n"
+        );
+    }
+}
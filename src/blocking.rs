@@ -0,0 +1,217 @@
+// src/blocking.rs
+
+//! Synchronous client for scripts and batch jobs (`blocking` feature)
+//!
+//! [`blocking::ResoClient`](ResoClient) wraps the async
+//! [`ResoClient`](crate::ResoClient) together with a private single-threaded
+//! tokio runtime and blocks the calling thread on each request. The
+//! configuration, query builders, responses and errors are the same as for
+//! the async client.
+//!
+//! Replication and paginated queries are exposed as iterators that parse
+//! records one at a time as each page arrives.
+//!
+//! The blocking client must not be created, used or dropped inside an async
+//! runtime; use the async client there instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use reso_client::blocking::ResoClient;
+//! use reso_client::{QueryBuilder, ReplicationQueryBuilder};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = ResoClient::from_env()?;
+//!
+//!     let query = QueryBuilder::new("Property").top(10).build()?;
+//!     let results = client.execute(&query)?;
+//!     println!("{}", results["value"]);
+//!
+//!     let query = ReplicationQueryBuilder::new("Property").top(2000).build()?;
+//!     for record in client.replicate(&query) {
+//!         println!("{}", record?["ListingKey"]);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::client::ClientConfig;
+use crate::error::{ResoError, Result};
+use crate::metadata::Metadata;
+use crate::progress::ReplicationProgress;
+use crate::queries::{Query, ReplicationQuery};
+use crate::replication::{ReplicationResponse, ReplicationStream};
+use crate::streaming::RecordStream;
+use serde_json::Value as JsonValue;
+use tokio::runtime::Runtime;
+
+/// Blocking RESO Web API client
+pub struct ResoClient {
+    inner: crate::ResoClient,
+    runtime: Runtime,
+}
+
+impl std::fmt::Debug for ResoClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResoClient")
+            .field("base_url", &self.inner.base_url())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResoClient {
+    /// Create a client from environment variables
+    ///
+    /// See [`ClientConfig::from_env`] for the variables read.
+    pub fn from_env() -> Result<Self> {
+        Self::with_config(ClientConfig::from_env()?)
+    }
+
+    /// Create a client with manual configuration
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ClientConfig;
+    /// # use reso_client::blocking::ResoClient;
+    /// let config = ClientConfig::new("https://api.mls.com/reso/odata", "your-token");
+    /// let client = ResoClient::with_config(config)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_config(config: ClientConfig) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ResoError::Config(format!("Failed to create runtime: {}", e)))?;
+        let inner = crate::ResoClient::with_config(config)?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Get the base URL
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// The async client this one drives
+    pub fn async_client(&self) -> &crate::ResoClient {
+        &self.inner
+    }
+
+    /// Execute a query and return raw JSON
+    ///
+    /// See [`ResoClient::execute`](crate::ResoClient::execute).
+    pub fn execute(&self, query: &Query) -> Result<JsonValue> {
+        self.runtime.block_on(self.inner.execute(query))
+    }
+
+    /// Execute a direct key access query and return a single record
+    ///
+    /// See [`ResoClient::execute_by_key`](crate::ResoClient::execute_by_key).
+    pub fn execute_by_key(&self, query: &Query) -> Result<JsonValue> {
+        self.runtime.block_on(self.inner.execute_by_key(query))
+    }
+
+    /// Execute a count-only query
+    ///
+    /// See [`ResoClient::execute_count`](crate::ResoClient::execute_count).
+    pub fn execute_count(&self, query: &Query) -> Result<u64> {
+        self.runtime.block_on(self.inner.execute_count(query))
+    }
+
+    /// Fetch the `$metadata` document as XML
+    pub fn fetch_metadata(&self) -> Result<String> {
+        self.runtime.block_on(self.inner.fetch_metadata())
+    }
+
+    /// Fetch and parse the `$metadata` document
+    pub fn fetch_parsed_metadata(&self) -> Result<Metadata> {
+        self.runtime.block_on(self.inner.fetch_parsed_metadata())
+    }
+
+    /// Execute a replication query and return its first page
+    ///
+    /// See [`ResoClient::execute_replication`](crate::ResoClient::execute_replication).
+    pub fn execute_replication(&self, query: &ReplicationQuery) -> Result<ReplicationResponse> {
+        self.runtime.block_on(self.inner.execute_replication(query))
+    }
+
+    /// Fetch the page behind a replication `next` link
+    pub fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse> {
+        self.runtime
+            .block_on(self.inner.execute_next_link(next_link))
+    }
+
+    /// Iterate over every record of a replication query
+    ///
+    /// Pages are requested as the iterator reaches them, following `next`
+    /// links. The iterator ends after the first error.
+    pub fn replicate(&self, query: &ReplicationQuery) -> ReplicationRecords<'_> {
+        ReplicationRecords {
+            runtime: &self.runtime,
+            stream: self.inner.replicate(query),
+            failed: false,
+        }
+    }
+
+    /// Iterate over every record of a query, following `@odata.nextLink`
+    ///
+    /// The iterator ends after the first error.
+    pub fn stream_records(&self, query: &Query) -> QueryRecords<'_> {
+        QueryRecords {
+            runtime: &self.runtime,
+            stream: self.inner.stream_records(query),
+            failed: false,
+        }
+    }
+}
+
+/// Records of a replication query
+///
+/// Created by [`ResoClient::replicate`].
+pub struct ReplicationRecords<'a> {
+    runtime: &'a Runtime,
+    stream: ReplicationStream<'a>,
+    failed: bool,
+}
+
+impl ReplicationRecords<'_> {
+    /// Progress of the run so far
+    pub fn progress(&self) -> &ReplicationProgress {
+        self.stream.progress()
+    }
+}
+
+impl Iterator for ReplicationRecords<'_> {
+    type Item = Result<JsonValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.runtime.block_on(self.stream.next_record());
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
+
+/// Records of a query across all pages
+///
+/// Created by [`ResoClient::stream_records`].
+pub struct QueryRecords<'a> {
+    runtime: &'a Runtime,
+    stream: RecordStream<'a>,
+    failed: bool,
+}
+
+impl Iterator for QueryRecords<'_> {
+    type Item = Result<JsonValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.runtime.block_on(self.stream.next_record());
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
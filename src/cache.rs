@@ -0,0 +1,704 @@
+// src/cache.rs
+
+//! HTTP response caching with `ETag` / `If-None-Match` revalidation
+//!
+//! `$metadata`, Lookup tables and reference records change rarely but are
+//! fetched by every job. An [`HttpCache`] attached to the client configuration
+//! stores successful responses keyed by their full request URL, serves them
+//! while they are fresh, and revalidates stale entries with conditional
+//! requests (`If-None-Match` / `If-Modified-Since`) so an unchanged document
+//! costs a `304 Not Modified` instead of a full download.
+//!
+//! # Freshness
+//!
+//! The server's `Cache-Control` header is honoured:
+//!
+//! - `no-store` responses are never cached
+//! - `no-cache` responses are stored but revalidated on every use
+//! - `max-age=N` responses are served without a request for `N` seconds
+//!
+//! Responses without `max-age` are fresh for the cache's default TTL (zero
+//! unless set with [`HttpCache::with_default_ttl`]), then revalidated. A
+//! response with neither a lifetime nor a validator (`ETag` or
+//! `Last-Modified`) is not stored. [`HttpCache::with_metadata_ttl`] overrides
+//! all of this for `$metadata`.
+//!
+//! Bulk transfers (replication and [`execute_into`](crate::ResoClient::execute_into)
+//! pages) are never cached, and the cache can be bypassed for
+//! individual requests with [`ResoClient::uncached`](crate::ResoClient::uncached).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ClientConfig, HttpCache, ResoClient};
+//! # use std::time::Duration;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let cache = HttpCache::on_disk(".reso-cache")?
+//!     .with_metadata_ttl(Duration::from_secs(24 * 60 * 60));
+//!
+//! let config = ClientConfig::from_env()?.with_cache(cache);
+//! let client = ResoClient::with_config(config)?;
+//!
+//! // Served from disk for a day, then revalidated
+//! let metadata = client.fetch_metadata().await?;
+//!
+//! // Always goes to the server
+//! let fresh = client.uncached().fetch_metadata().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A stored HTTP response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Response headers, lower-cased names
+    pub headers: Vec<(String, String)>,
+
+    /// Response body
+    #[serde(skip)]
+    pub body: Vec<u8>,
+
+    /// When the response was stored or last revalidated, in seconds since the Unix epoch
+    pub stored_at: u64,
+}
+
+impl CachedResponse {
+    /// First value of a header
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// `ETag` validator
+    pub fn etag(&self) -> Option<&str> {
+        self.header("etag")
+    }
+
+    /// `Last-Modified` validator
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header("last-modified")
+    }
+
+    fn has_validator(&self) -> bool {
+        self.etag().is_some() || self.last_modified().is_some()
+    }
+
+    /// How long the response may be served without revalidation
+    fn lifetime(&self, ttl_override: Option<Duration>, default_ttl: Duration) -> Duration {
+        if let Some(ttl) = ttl_override {
+            return ttl;
+        }
+        let control = CacheControl::parse(self.header("cache-control"));
+        if control.no_cache {
+            return Duration::ZERO;
+        }
+        control.max_age.unwrap_or(default_ttl)
+    }
+
+    fn is_fresh(&self, ttl_override: Option<Duration>, default_ttl: Duration, now: u64) -> bool {
+        let age = Duration::from_secs(now.saturating_sub(self.stored_at));
+        age < self.lifetime(ttl_override, default_ttl)
+    }
+
+    /// Headers for a conditional request revalidating this response
+    fn conditional_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = self.etag() {
+            headers.push(("If-None-Match", etag.to_string()));
+        }
+        if let Some(modified) = self.last_modified() {
+            headers.push(("If-Modified-Since", modified.to_string()));
+        }
+        headers
+    }
+
+    /// Take the freshness headers from a `304 Not Modified` response
+    fn revalidated(mut self, not_modified: &reqwest::header::HeaderMap, now: u64) -> Self {
+        for name in ["cache-control", "etag", "expires", "last-modified"] {
+            if let Some(value) = not_modified.get(name).and_then(|v| v.to_str().ok()) {
+                self.headers.retain(|(n, _)| n != name);
+                self.headers.push((name.to_string(), value.to_string()));
+            }
+        }
+        self.stored_at = now;
+        self
+    }
+
+    fn into_response(self) -> reqwest::Response {
+        build_response(self.status, &self.headers, self.body)
+            .expect("cached status and headers were valid when stored")
+    }
+}
+
+/// Build a `reqwest::Response` from stored parts
+///
+/// Used to serve cached responses and replayed fixtures as if they had just
+/// arrived from the server.
+pub(crate) fn build_response(
+    status: u16,
+    headers: &[(String, String)],
+    body: Vec<u8>,
+) -> std::result::Result<reqwest::Response, http::Error> {
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(body).map(reqwest::Response::from)
+}
+
+/// Storage backend for [`HttpCache`]
+///
+/// Implementations must be safe to share between tasks. Storage failures
+/// should be logged and treated as misses rather than failing the request.
+pub trait CacheStore: Send + Sync {
+    /// Look up the response stored for `key`
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store (or replace) the response for `key`
+    fn put(&self, key: &str, response: &CachedResponse);
+
+    /// Remove the response stored for `key`
+    fn remove(&self, key: &str);
+}
+
+/// In-memory cache store, shared by clones
+#[derive(Debug, Default, Clone)]
+pub struct MemoryCache {
+    entries: Arc<Mutex<HashMap<String, CachedResponse>>>,
+}
+
+impl MemoryCache {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored responses
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove every stored response
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        // A panic while holding the lock cannot leave a HashMap half-updated
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lock().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: &CachedResponse) {
+        self.lock().insert(key.to_string(), response.clone());
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+}
+
+/// On-disk cache store
+///
+/// Each response is kept as two files named after the SHA-256 of its key:
+/// `{hash}.json` with the status and headers and `{hash}.body` with the body.
+/// Entries survive process restarts and can be shared by jobs on one machine.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    #[serde(flatten)]
+    response: CachedResponse,
+}
+
+impl DiskCache {
+    /// Use `dir` for cache files, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            ResoError::Config(format!(
+                "Failed to create cache directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+        Ok(Self { dir })
+    }
+
+    /// Directory holding the cache files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Remove every cache file
+    pub fn clear(&self) -> Result<()> {
+        let entries = std::fs::read_dir(&self.dir).map_err(|e| {
+            ResoError::Config(format!("Failed to read {}: {}", self.dir.display(), e))
+        })?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("json" | "body")
+            ) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let hash = Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        (
+            self.dir.join(format!("{}.json", hash)),
+            self.dir.join(format!("{}.body", hash)),
+        )
+    }
+
+    fn read(&self, key: &str) -> std::io::Result<Option<CachedResponse>> {
+        let (meta_path, body_path) = self.paths(key);
+        let meta = match std::fs::read(&meta_path) {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let entry: DiskEntry = serde_json::from_slice(&meta)?;
+        // Guard against hash collisions and entries from another key
+        if entry.key != key {
+            return Ok(None);
+        }
+        let mut response = entry.response;
+        response.body = std::fs::read(body_path)?;
+        Ok(Some(response))
+    }
+
+    fn write(&self, key: &str, response: &CachedResponse) -> std::io::Result<()> {
+        let (meta_path, body_path) = self.paths(key);
+        let entry = DiskEntry {
+            key: key.to_string(),
+            response: response.clone(),
+        };
+        // Body first, then the metadata that makes the entry visible
+        write_atomic(&body_path, &response.body)?;
+        write_atomic(&meta_path, &serde_json::to_vec(&entry)?)
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.read(key).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read cache entry for {}: {}", key, e);
+            None
+        })
+    }
+
+    fn put(&self, key: &str, response: &CachedResponse) {
+        if let Err(e) = self.write(key, response) {
+            tracing::warn!("Failed to write cache entry for {}: {}", key, e);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let (meta_path, body_path) = self.paths(key);
+        let _ = std::fs::remove_file(meta_path);
+        let _ = std::fs::remove_file(body_path);
+    }
+}
+
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    // A temp name per write, so concurrent writers sharing the directory never
+    // rename each other's half-written file into place
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = path.with_file_name(name);
+    let written = std::fs::write(&tmp, contents).and_then(|()| std::fs::rename(&tmp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// Opt-in HTTP cache for [`ResoClient`](crate::ResoClient)
+///
+/// See the [module documentation](self) for the caching rules.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::{ClientConfig, HttpCache};
+/// # use std::time::Duration;
+/// let cache = HttpCache::in_memory()
+///     .with_default_ttl(Duration::from_secs(300))
+///     .with_metadata_ttl(Duration::from_secs(3600));
+///
+/// let config = ClientConfig::new("https://api.mls.com/odata", "token").with_cache(cache);
+/// ```
+#[derive(Clone)]
+pub struct HttpCache {
+    store: Arc<dyn CacheStore>,
+    default_ttl: Duration,
+    metadata_ttl: Option<Duration>,
+}
+
+impl std::fmt::Debug for HttpCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpCache")
+            .field("default_ttl", &self.default_ttl)
+            .field("metadata_ttl", &self.metadata_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpCache {
+    /// Cache backed by a custom store
+    pub fn new(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            default_ttl: Duration::ZERO,
+            metadata_ttl: None,
+        }
+    }
+
+    /// Cache held in memory for the life of the client
+    pub fn in_memory() -> Self {
+        Self::new(MemoryCache::new())
+    }
+
+    /// Cache persisted under `dir`
+    pub fn on_disk(dir: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self::new(DiskCache::new(dir)?))
+    }
+
+    /// Lifetime of responses that carry no `Cache-Control: max-age` (default: zero)
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Serve `$metadata` for `ttl` regardless of the server's `Cache-Control`
+    pub fn with_metadata_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata_ttl = Some(ttl);
+        self
+    }
+
+    /// The `$metadata` TTL override, if set
+    pub fn metadata_ttl(&self) -> Option<Duration> {
+        self.metadata_ttl
+    }
+
+    /// Drop the stored response for `url`
+    pub fn invalidate(&self, url: &str) {
+        self.store.remove(url);
+    }
+
+    /// Stored response for `url` if it may be served without a request
+    pub(crate) fn fresh(&self, url: &str, ttl_override: Option<Duration>) -> Lookup {
+        match self.store.get(url) {
+            Some(entry) if entry.is_fresh(ttl_override, self.default_ttl, now()) => {
+                Lookup::Fresh(entry.into_response())
+            }
+            Some(entry) => Lookup::Stale(entry),
+            None => Lookup::Miss,
+        }
+    }
+
+    /// Serve a stale entry after the server answered `304 Not Modified`
+    pub(crate) fn not_modified(
+        &self,
+        url: &str,
+        entry: CachedResponse,
+        response: &reqwest::Response,
+    ) -> reqwest::Response {
+        let entry = entry.revalidated(response.headers(), now());
+        self.store.put(url, &entry);
+        entry.into_response()
+    }
+
+    /// Store a successful response if it is cacheable and hand back an equivalent one
+    pub(crate) async fn store(
+        &self,
+        url: &str,
+        response: reqwest::Response,
+        ttl_override: Option<Duration>,
+    ) -> Result<reqwest::Response> {
+        let status = response.status().as_u16();
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.as_str().to_string(), v.to_string()))
+            })
+            .collect();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ResoError::Parse(format!("Failed to read response: {}", e)))?
+            .to_vec();
+
+        let entry = CachedResponse {
+            status,
+            headers,
+            body,
+            stored_at: now(),
+        };
+
+        let control = CacheControl::parse(entry.header("cache-control"));
+        let cacheable = status == 200
+            && !control.no_store
+            && (entry.has_validator() || !entry.lifetime(ttl_override, self.default_ttl).is_zero());
+        if cacheable {
+            self.store.put(url, &entry);
+        } else if control.no_store {
+            self.store.remove(url);
+        }
+
+        Ok(entry.into_response())
+    }
+}
+
+/// Result of looking up a URL in the cache
+pub(crate) enum Lookup {
+    Fresh(reqwest::Response),
+    Stale(CachedResponse),
+    Miss,
+}
+
+impl Lookup {
+    /// Headers for revalidating a stale entry
+    pub(crate) fn conditional_headers(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Lookup::Stale(entry) => entry.conditional_headers(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The parts of `Cache-Control` the cache acts on
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<Duration>,
+}
+
+impl CacheControl {
+    fn parse(header: Option<&str>) -> Self {
+        let mut control = Self::default();
+        for directive in header.unwrap_or_default().split(',') {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => control.no_store = true,
+                "no-cache" => control.no_cache = true,
+                "max-age" => {
+                    control.max_age = value
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(Duration::from_secs)
+                }
+                _ => {}
+            }
+        }
+        control
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(headers: &[(&str, &str)], stored_at: u64) -> CachedResponse {
+        CachedResponse {
+            status: 200,
+            headers: headers
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect(),
+            body: b"{}".to_vec(),
+            stored_at,
+        }
+    }
+
+    #[test]
+    fn test_cache_control_parse() {
+        assert_eq!(
+            CacheControl::parse(Some("public, max-age=\"60\", no-cache")),
+            CacheControl {
+                no_store: false,
+                no_cache: true,
+                max_age: Some(Duration::from_secs(60)),
+            }
+        );
+        assert!(CacheControl::parse(Some("No-Store")).no_store);
+        assert_eq!(CacheControl::parse(None), CacheControl::default());
+    }
+
+    #[test]
+    fn test_freshness() {
+        let max_age = entry(&[("cache-control", "max-age=60")], 1000);
+        assert!(max_age.is_fresh(None, Duration::ZERO, 1059));
+        assert!(!max_age.is_fresh(None, Duration::ZERO, 1060));
+
+        let no_cache = entry(&[("cache-control", "no-cache, max-age=60")], 1000);
+        assert!(!no_cache.is_fresh(None, Duration::from_secs(60), 1000));
+
+        let plain = entry(&[("etag", "\"v1\"")], 1000);
+        assert!(!plain.is_fresh(None, Duration::ZERO, 1000));
+        assert!(plain.is_fresh(None, Duration::from_secs(10), 1005));
+        // The override wins over the server's headers
+        assert!(no_cache.is_fresh(Some(Duration::from_secs(3600)), Duration::ZERO, 2000));
+    }
+
+    #[test]
+    fn test_conditional_headers() {
+        let validated = entry(
+            &[
+                ("etag", "\"v1\""),
+                ("last-modified", "Wed, 01 Jan 2025 00:00:00 GMT"),
+            ],
+            0,
+        );
+        assert_eq!(
+            validated.conditional_headers(),
+            vec![
+                ("If-None-Match", "\"v1\"".to_string()),
+                (
+                    "If-Modified-Since",
+                    "Wed, 01 Jan 2025 00:00:00 GMT".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_revalidated_refreshes_headers() {
+        let mut not_modified = reqwest::header::HeaderMap::new();
+        not_modified.insert("etag", "\"v2\"".parse().unwrap());
+
+        let refreshed = entry(&[("etag", "\"v1\""), ("content-type", "text/xml")], 0)
+            .revalidated(&not_modified, 500);
+
+        assert_eq!(refreshed.etag(), Some("\"v2\""));
+        assert_eq!(refreshed.header("Content-Type"), Some("text/xml"));
+        assert_eq!(refreshed.stored_at, 500);
+    }
+
+    #[test]
+    fn test_memory_cache() {
+        let cache = MemoryCache::new();
+        let shared = cache.clone();
+        cache.put("https://api.example.com/$metadata", &entry(&[], 1));
+
+        assert_eq!(shared.len(), 1);
+        assert_eq!(
+            shared.get("https://api.example.com/$metadata"),
+            Some(entry(&[], 1))
+        );
+        shared.remove("https://api.example.com/$metadata");
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache")).unwrap();
+        let stored = entry(&[("etag", "\"v1\"")], 42);
+
+        cache.put("https://api.example.com/Lookup", &stored);
+        let reopened = DiskCache::new(dir.path().join("cache")).unwrap();
+
+        assert_eq!(reopened.get("https://api.example.com/Lookup"), Some(stored));
+        assert_eq!(reopened.get("https://api.example.com/Other"), None);
+
+        reopened.clear().unwrap();
+        assert_eq!(reopened.get("https://api.example.com/Lookup"), None);
+    }
+
+    #[test]
+    fn test_disk_cache_concurrent_writes_of_one_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).unwrap();
+        let key = "https://api.example.com/Lookup";
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for _ in 0..25 {
+                        cache
+                            .write(key, &entry(&[("etag", "\"v1\"")], 1000 + i))
+                            .unwrap();
+                        assert!(cache.get(key).is_some());
+                    }
+                });
+            }
+        });
+
+        let mut files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 2, "leftover temp files: {:?}", files);
+        assert!(files[0].ends_with(".body") && files[1].ends_with(".json"));
+    }
+
+    #[tokio::test]
+    async fn test_into_response() {
+        let response = entry(&[("next", "https://api.example.com/next")], 0).into_response();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get("next").unwrap(),
+            "https://api.example.com/next"
+        );
+        assert_eq!(response.text().await.unwrap(), "{}");
+    }
+}
@@ -0,0 +1,818 @@
+// src/certification.rs
+
+//! RESO Web API Core certification harness
+//!
+//! [`CertificationRunner`] runs the RESO Web API Core 2.0 query scenarios
+//! against any configured [`ResoClient`] and reports pass, fail or skip per
+//! scenario, together with the exact request URLs it sent.
+//!
+//! Scenarios are derived from the server's own `$metadata` and a sample of
+//! its records, so no server-specific configuration is needed:
+//!
+//! | Scenario           | Checks                                                   |
+//! |--------------------|----------------------------------------------------------|
+//! | `metadata`         | `$metadata` parses, declares the resource and its key    |
+//! | `top`              | `$top` limits the page size                              |
+//! | `skip`             | `$skip` offsets an ordered result                        |
+//! | `select`           | `$select` limits the returned fields                     |
+//! | `orderby`          | `$orderby` sorts ascending and descending                |
+//! | `key-access`       | `Resource('key')` returns the record                     |
+//! | `filter-<type>`    | `$filter` on String, Boolean, integer, decimal, Date, DateTimeOffset and enum fields |
+//! | `count`            | `$count=true` and `/$count`                              |
+//! | `string-functions` | `contains`, `startswith`, `endswith`, `tolower`          |
+//! | `in`               | the `in` operator                                        |
+//! | `has`              | the `has` operator on multi-valued enumerations          |
+//!
+//! Filter scenarios check that every returned record satisfies the filter.
+//! A scenario is skipped when the server declares no suitable field or the
+//! sample holds no value to filter on.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{CertificationRunner, ResoClient};
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ResoClient::from_env()?;
+//! let report = CertificationRunner::new().resource("Property").run(&client).await;
+//!
+//! println!("{}", report);
+//! if !report.is_passing() {
+//!     std::process::exit(1);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::ResoClient;
+use crate::compliance::is_populated;
+use crate::error::ResoError;
+use crate::filter::{self, Expr};
+use crate::metadata::{EntityType, Metadata, Property};
+use crate::queries::{Query, QueryBuilder};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Scenarios that need `$metadata` and sample records, in run order
+const SCENARIOS: &[(&str, &str)] = &[
+    ("skip", "$skip"),
+    ("select", "$select"),
+    ("orderby", "$orderby"),
+    ("key-access", "Key access"),
+    ("filter-string", "$filter on Edm.String"),
+    ("filter-boolean", "$filter on Edm.Boolean"),
+    ("filter-integer", "$filter on integer types"),
+    ("filter-decimal", "$filter on decimal types"),
+    ("filter-date", "$filter on Edm.Date"),
+    ("filter-datetime", "$filter on Edm.DateTimeOffset"),
+    ("filter-enum", "$filter on enumerations"),
+    ("count", "$count"),
+    ("string-functions", "String functions"),
+    ("in", "in operator"),
+    ("has", "has operator"),
+];
+
+/// Outcome of a scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScenarioStatus {
+    /// Every check succeeded
+    Passed,
+    /// A request failed or a check did not hold
+    Failed,
+    /// The server offers nothing to test the scenario with
+    Skipped,
+}
+
+/// Result of one scenario
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScenarioResult {
+    /// Scenario identifier, e.g. `filter-date`
+    pub id: String,
+    /// Human-readable scenario name
+    pub name: String,
+    /// Outcome
+    pub status: ScenarioStatus,
+    /// Request URLs sent, in order
+    pub requests: Vec<String>,
+    /// Why the scenario failed or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Results of a certification run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificationReport {
+    /// Server base URL
+    pub base_url: String,
+    /// Resource tested
+    pub resource: String,
+    /// Scenario results, in run order
+    pub scenarios: Vec<ScenarioResult>,
+}
+
+impl CertificationReport {
+    /// Number of scenarios with `status`
+    pub fn count(&self, status: ScenarioStatus) -> usize {
+        self.scenarios.iter().filter(|s| s.status == status).count()
+    }
+
+    /// Whether no scenario failed
+    pub fn is_passing(&self) -> bool {
+        self.count(ScenarioStatus::Failed) == 0
+    }
+
+    /// Look up a scenario by identifier
+    pub fn scenario(&self, id: &str) -> Option<&ScenarioResult> {
+        self.scenarios.iter().find(|s| s.id == id)
+    }
+
+    /// The report as JSON
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).unwrap_or(JsonValue::Null)
+    }
+}
+
+impl fmt::Display for CertificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "RESO Web API Core: {} at {}",
+            self.resource, self.base_url
+        )?;
+        for scenario in &self.scenarios {
+            let status = match scenario.status {
+                ScenarioStatus::Passed => "PASS",
+                ScenarioStatus::Failed => "FAIL",
+                ScenarioStatus::Skipped => "SKIP",
+            };
+            write!(f, "{}  {:<18} {}", status, scenario.id, scenario.name)?;
+            match &scenario.message {
+                Some(message) => writeln!(f, ": {}", message)?,
+                None => writeln!(f)?,
+            }
+            for url in &scenario.requests {
+                writeln!(f, "      GET {}", url)?;
+            }
+        }
+        write!(
+            f,
+            "{} passed, {} failed, {} skipped",
+            self.count(ScenarioStatus::Passed),
+            self.count(ScenarioStatus::Failed),
+            self.count(ScenarioStatus::Skipped)
+        )
+    }
+}
+
+/// Runs the Web API Core scenarios against a client
+#[derive(Debug, Clone)]
+pub struct CertificationRunner {
+    resource: String,
+    sample_size: u32,
+}
+
+impl Default for CertificationRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CertificationRunner {
+    /// Runner for the Property resource with a sample of 20 records
+    pub fn new() -> Self {
+        Self {
+            resource: "Property".to_string(),
+            sample_size: 20,
+        }
+    }
+
+    /// Resource to test (default `Property`)
+    pub fn resource(mut self, resource: impl Into<String>) -> Self {
+        self.resource = resource.into();
+        self
+    }
+
+    /// `$top` used for the sample and for filter queries (default 20)
+    pub fn sample_size(mut self, size: u32) -> Self {
+        self.sample_size = size.max(2);
+        self
+    }
+
+    /// Run every scenario
+    ///
+    /// Never fails: request errors are reported as failed scenarios.
+    pub async fn run(&self, client: &ResoClient) -> CertificationReport {
+        let mut session = Session {
+            client,
+            requests: Vec::new(),
+            scenarios: Vec::new(),
+        };
+
+        let metadata = self.metadata(&mut session).await;
+        session.finish("metadata", "Metadata validation", &metadata);
+
+        let sample = self.top(&mut session).await;
+        session.finish("top", "$top", &sample);
+
+        let entity = metadata
+            .as_ref()
+            .ok()
+            .and_then(|m| Some((m, m.entity_type_for_resource(&self.resource)?)));
+        let context = match (entity, sample) {
+            (Some((metadata, entity)), Ok(sample)) if !sample.is_empty() => Some(Context {
+                resource: &self.resource,
+                top: self.sample_size,
+                metadata,
+                entity,
+                key: entity.key[0].as_str(),
+                sample,
+            }),
+            _ => None,
+        };
+
+        for (id, name) in SCENARIOS {
+            let step = match &context {
+                Some(context) => run_scenario(id, &mut session, context).await,
+                None => Err(Verdict::Skip(
+                    "requires valid $metadata and sample records".to_string(),
+                )),
+            };
+            session.finish(id, name, &step);
+        }
+
+        CertificationReport {
+            base_url: client.base_url().to_string(),
+            resource: self.resource.clone(),
+            scenarios: session.scenarios,
+        }
+    }
+
+    async fn metadata(&self, session: &mut Session<'_>) -> Step<Metadata> {
+        session.requests.push(session.client.build_url("$metadata"));
+        let metadata = session.client.fetch_parsed_metadata().await?;
+
+        let entity = metadata
+            .entity_type_for_resource(&self.resource)
+            .ok_or_else(|| fail(format!("no entity type for {}", self.resource)))?;
+        if entity.key.is_empty() {
+            return Err(fail(format!("{} declares no key", entity.name)));
+        }
+
+        let unresolved: Vec<&str> = entity
+            .properties
+            .iter()
+            .map(Property::element_type)
+            .filter(|t| !t.starts_with("Edm.") && metadata.enum_type(t).is_none())
+            .collect();
+        if !unresolved.is_empty() {
+            return Err(fail(format!(
+                "undeclared property types: {}",
+                unresolved.join(", ")
+            )));
+        }
+        Ok(metadata)
+    }
+
+    async fn top(&self, session: &mut Session<'_>) -> Step<Vec<JsonValue>> {
+        let query = QueryBuilder::new(&self.resource)
+            .top(self.sample_size)
+            .build()?;
+        let records = session.records(&query).await?;
+        if records.len() > self.sample_size as usize {
+            return Err(fail(format!(
+                "{} records returned for $top={}",
+                records.len(),
+                self.sample_size
+            )));
+        }
+        if records.is_empty() {
+            return Err(fail("no records returned".to_string()));
+        }
+        Ok(records)
+    }
+}
+
+/// Why a scenario did not pass
+enum Verdict {
+    Fail(String),
+    Skip(String),
+}
+
+impl From<ResoError> for Verdict {
+    fn from(error: ResoError) -> Self {
+        Verdict::Fail(error.to_string())
+    }
+}
+
+type Step<T> = std::result::Result<T, Verdict>;
+
+fn fail(message: String) -> Verdict {
+    Verdict::Fail(message)
+}
+
+/// Sends requests and collects results
+struct Session<'a> {
+    client: &'a ResoClient,
+    requests: Vec<String>,
+    scenarios: Vec<ScenarioResult>,
+}
+
+impl Session<'_> {
+    async fn records(&mut self, query: &Query) -> Step<Vec<JsonValue>> {
+        self.requests
+            .push(self.client.build_url(&query.to_odata_string()));
+        let response = self.client.execute(query).await?;
+        match response.get("value").and_then(JsonValue::as_array) {
+            Some(records) => Ok(records.clone()),
+            None => Err(fail("response has no value array".to_string())),
+        }
+    }
+
+    fn finish<T>(&mut self, id: &str, name: &str, step: &Step<T>) {
+        let (status, message) = match step {
+            Ok(_) => (ScenarioStatus::Passed, None),
+            Err(Verdict::Fail(message)) => (ScenarioStatus::Failed, Some(message.clone())),
+            Err(Verdict::Skip(message)) => (ScenarioStatus::Skipped, Some(message.clone())),
+        };
+        self.scenarios.push(ScenarioResult {
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            requests: std::mem::take(&mut self.requests),
+            message,
+        });
+    }
+}
+
+/// What the scenarios are derived from
+struct Context<'a> {
+    resource: &'a str,
+    top: u32,
+    metadata: &'a Metadata,
+    entity: &'a EntityType,
+    key: &'a str,
+    sample: Vec<JsonValue>,
+}
+
+impl Context<'_> {
+    /// A field matching `wanted` with a sampled value accepted by `usable`,
+    /// preferring fields other than the key
+    fn field(
+        &self,
+        wanted: impl Fn(&Property) -> bool,
+        usable: impl Fn(&JsonValue) -> bool,
+    ) -> Option<(&Property, &JsonValue)> {
+        let mut properties: Vec<&Property> = self
+            .entity
+            .properties
+            .iter()
+            .filter(|p| wanted(p))
+            .collect();
+        properties.sort_by_key(|p| p.name == self.key);
+        properties.into_iter().find_map(|property| {
+            self.sample
+                .iter()
+                .filter_map(|record| record.get(&property.name))
+                .find(|value| is_populated(value) && usable(value))
+                .map(|value| (property, value))
+        })
+    }
+
+    fn query(&self) -> QueryBuilder {
+        QueryBuilder::new(self.resource).top(self.top)
+    }
+
+    /// Run a filter and check every returned record satisfies it
+    async fn check_filter(&self, session: &mut Session<'_>, expression: &str) -> Step<()> {
+        let expr = Expr::parse(expression)
+            .map_err(|e| fail(format!("cannot evaluate {}: {}", expression, e)))?;
+        let records = session
+            .records(&self.query().filter(expression).build()?)
+            .await?;
+        if records.is_empty() {
+            return Err(fail(format!(
+                "no records matched {}, though a sampled record does",
+                expression
+            )));
+        }
+        match records.iter().find(|record| !expr.matches(record)) {
+            Some(record) => Err(fail(format!(
+                "record {} does not satisfy {}",
+                key_of(record, self.key).unwrap_or_default(),
+                expression
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+async fn run_scenario(id: &str, session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    match id {
+        "skip" => skip(session, context).await,
+        "select" => select(session, context).await,
+        "orderby" => orderby(session, context).await,
+        "key-access" => key_access(session, context).await,
+        "count" => count(session, context).await,
+        "string-functions" => string_functions(session, context).await,
+        "in" => in_operator(session, context).await,
+        "has" => has_operator(session, context).await,
+        _ => match id.strip_prefix("filter-").and_then(TypeKind::from_id) {
+            Some(kind) => filter_on(kind, session, context).await,
+            None => Err(Verdict::Skip(format!("unknown scenario {}", id))),
+        },
+    }
+}
+
+async fn skip(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let ordered = || QueryBuilder::new(context.resource).order_by(context.key, "asc");
+    let first = session.records(&ordered().top(2).build()?).await?;
+    if first.len() < 2 {
+        return Err(Verdict::Skip("fewer than two records".to_string()));
+    }
+    let skipped = session.records(&ordered().skip(1).top(1).build()?).await?;
+
+    let expected = key_of(&first[1], context.key);
+    let actual = skipped.first().and_then(|r| key_of(r, context.key));
+    if expected != actual {
+        return Err(fail(format!(
+            "$skip=1 returned {:?}, expected {:?}",
+            actual, expected
+        )));
+    }
+    Ok(())
+}
+
+async fn select(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let (field, _) = context
+        .field(|p| p.name != context.key, |_| true)
+        .ok_or_else(|| Verdict::Skip("no populated field besides the key".to_string()))?;
+    let fields = [context.key, field.name.as_str()];
+
+    let records = session
+        .records(&context.query().select(&fields).build()?)
+        .await?;
+    for record in &records {
+        let object = record.as_object().map(|o| o.keys()).into_iter().flatten();
+        if let Some(extra) = object
+            .filter(|name| !name.contains('@'))
+            .find(|name| !fields.contains(&name.as_str()))
+        {
+            return Err(fail(format!("unselected field {} returned", extra)));
+        }
+        if record.get(context.key).is_none() {
+            return Err(fail(format!("selected key {} missing", context.key)));
+        }
+    }
+    Ok(())
+}
+
+async fn orderby(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let sortable = |p: &Property| {
+        !p.is_collection()
+            && matches!(
+                TypeKind::of(p, context.metadata),
+                Some(TypeKind::Integer | TypeKind::Decimal | TypeKind::Date | TypeKind::DateTime)
+            )
+    };
+    let (field, _) = context
+        .field(sortable, |_| true)
+        .ok_or_else(|| Verdict::Skip("no populated numeric or date field".to_string()))?;
+
+    for (direction, expected) in [("asc", Ordering::Less), ("desc", Ordering::Greater)] {
+        let query = context
+            .query()
+            .filter(format!("{} ne null", field.name))
+            .order_by(&field.name, direction)
+            .build()?;
+        let records = session.records(&query).await?;
+        let values: Vec<&JsonValue> = records
+            .iter()
+            .map(|r| r.get(&field.name).unwrap_or(&JsonValue::Null))
+            .collect();
+        if let Some(pair) = values
+            .windows(2)
+            .find(|pair| compare(pair[0], pair[1]) == expected.reverse())
+        {
+            return Err(fail(format!(
+                "{} {} returned {} before {}",
+                field.name, direction, pair[0], pair[1]
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn key_access(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let key = context
+        .sample
+        .iter()
+        .find_map(|record| key_of(record, context.key))
+        .ok_or_else(|| fail(format!("sample records lack {}", context.key)))?;
+
+    let query = QueryBuilder::by_key(context.resource, &key).build()?;
+    session
+        .requests
+        .push(session.client.build_url(&query.to_odata_string()));
+    let record = session.client.execute_by_key(&query).await?;
+
+    let actual = key_of(&record, context.key);
+    if actual.as_deref() != Some(key.as_str()) {
+        return Err(fail(format!("requested {}, got {:?}", key, actual)));
+    }
+    Ok(())
+}
+
+async fn count(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let query = QueryBuilder::new(context.resource)
+        .with_count()
+        .top(1)
+        .build()?;
+    session
+        .requests
+        .push(session.client.build_url(&query.to_odata_string()));
+    let response = session.client.execute(&query).await?;
+    if response
+        .get("@odata.count")
+        .and_then(JsonValue::as_u64)
+        .is_none()
+    {
+        return Err(fail("$count=true returned no @odata.count".to_string()));
+    }
+
+    let query = QueryBuilder::new(context.resource).count().build()?;
+    session
+        .requests
+        .push(session.client.build_url(&query.to_odata_string()));
+    if session.client.execute_count(&query).await? == 0 {
+        return Err(fail(
+            "/$count returned 0 for a non-empty resource".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn string_functions(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let (field, value) = context
+        .field(
+            |p| TypeKind::of(p, context.metadata) == Some(TypeKind::String),
+            |v| v.as_str().is_some_and(|s| s.chars().count() >= 3),
+        )
+        .ok_or_else(|| Verdict::Skip("no String value of three or more characters".to_string()))?;
+    let chars: Vec<char> = value.as_str().unwrap_or_default().chars().collect();
+    let text = |range: std::ops::Range<usize>| quote(&chars[range].iter().collect::<String>());
+    let n = chars.len();
+
+    for expression in [
+        format!("contains({}, {})", field.name, text(1..n - 1)),
+        format!("startswith({}, {})", field.name, text(0..2)),
+        format!("endswith({}, {})", field.name, text(n - 2..n)),
+        format!(
+            "tolower({}) eq {}",
+            field.name,
+            quote(&chars.iter().collect::<String>().to_lowercase())
+        ),
+    ] {
+        context.check_filter(session, &expression).await?;
+    }
+    Ok(())
+}
+
+async fn in_operator(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let (field, _) = context
+        .field(
+            |p| TypeKind::of(p, context.metadata) == Some(TypeKind::String),
+            |_| true,
+        )
+        .ok_or_else(|| Verdict::Skip("no populated String field".to_string()))?;
+
+    let mut values: Vec<&str> = Vec::new();
+    for value in context
+        .sample
+        .iter()
+        .filter_map(|r| r.get(&field.name)?.as_str())
+    {
+        if !value.is_empty() && !values.contains(&value) && values.len() < 2 {
+            values.push(value);
+        }
+    }
+    let list: Vec<String> = values.into_iter().map(quote).collect();
+    context
+        .check_filter(session, &format!("{} in ({})", field.name, list.join(", ")))
+        .await
+}
+
+async fn has_operator(session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let multi_enum = |p: &Property| {
+        context
+            .metadata
+            .enum_type(p.element_type())
+            .is_some_and(|e| e.is_flags || p.is_collection())
+    };
+    let (field, value) = context
+        .field(multi_enum, |_| true)
+        .ok_or_else(|| Verdict::Skip("no populated multi-valued enumeration".to_string()))?;
+    let member = match value {
+        JsonValue::Array(items) => items.iter().find_map(JsonValue::as_str),
+        JsonValue::String(s) => s.split(',').map(str::trim).find(|m| !m.is_empty()),
+        _ => None,
+    }
+    .ok_or_else(|| fail(format!("cannot read a member from {}", value)))?;
+
+    context
+        .check_filter(
+            session,
+            &format!(
+                "{} has {}'{}'",
+                field.name,
+                field.element_type(),
+                member.replace('\'', "''")
+            ),
+        )
+        .await
+}
+
+async fn filter_on(kind: TypeKind, session: &mut Session<'_>, context: &Context<'_>) -> Step<()> {
+    let (field, value) = context
+        .field(
+            |p| !p.is_collection() && TypeKind::of(p, context.metadata) == Some(kind),
+            |v| !v.is_array() && !v.is_object(),
+        )
+        .ok_or_else(|| Verdict::Skip(format!("no populated {} field", kind.label())))?;
+
+    let raw = match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let expression = match kind {
+        TypeKind::String => format!("{} eq {}", field.name, quote(&raw)),
+        TypeKind::Enum => format!(
+            "{} eq {}'{}'",
+            field.name,
+            field.element_type(),
+            raw.replace('\'', "''")
+        ),
+        TypeKind::Decimal | TypeKind::DateTime => format!("{} ge {}", field.name, raw),
+        TypeKind::Boolean | TypeKind::Integer | TypeKind::Date => {
+            format!("{} eq {}", field.name, raw)
+        }
+    };
+    context.check_filter(session, &expression).await
+}
+
+/// Data type families with a filter scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeKind {
+    String,
+    Boolean,
+    Integer,
+    Decimal,
+    Date,
+    DateTime,
+    Enum,
+}
+
+impl TypeKind {
+    fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "string" => TypeKind::String,
+            "boolean" => TypeKind::Boolean,
+            "integer" => TypeKind::Integer,
+            "decimal" => TypeKind::Decimal,
+            "date" => TypeKind::Date,
+            "datetime" => TypeKind::DateTime,
+            "enum" => TypeKind::Enum,
+            _ => return None,
+        })
+    }
+
+    fn of(property: &Property, metadata: &Metadata) -> Option<Self> {
+        Some(match property.element_type() {
+            "Edm.String" => TypeKind::String,
+            "Edm.Boolean" => TypeKind::Boolean,
+            "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64" => TypeKind::Integer,
+            "Edm.Decimal" | "Edm.Double" | "Edm.Single" => TypeKind::Decimal,
+            "Edm.Date" => TypeKind::Date,
+            "Edm.DateTimeOffset" => TypeKind::DateTime,
+            other if metadata.enum_type(other).is_some_and(|e| !e.is_flags) => TypeKind::Enum,
+            _ => return None,
+        })
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TypeKind::String => "Edm.String",
+            TypeKind::Boolean => "Edm.Boolean",
+            TypeKind::Integer => "integer",
+            TypeKind::Decimal => "decimal",
+            TypeKind::Date => "Edm.Date",
+            TypeKind::DateTime => "Edm.DateTimeOffset",
+            TypeKind::Enum => "enumeration",
+        }
+    }
+}
+
+/// Compare values, dates and date-times as instants
+fn compare(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (
+        a.as_str().and_then(filter::parse_time),
+        b.as_str().and_then(filter::parse_time),
+    ) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => filter::order(a, b),
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn key_of(record: &JsonValue, key: &str) -> Option<String> {
+    match record.get(key)? {
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_type_kinds() {
+        let metadata = Metadata::parse(
+            r#"<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="enums">
+      <EnumType Name="Status"><Member Name="Active"/></EnumType>
+      <EnumType Name="Features" IsFlags="true"><Member Name="Pool"/></EnumType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#,
+        )
+        .unwrap();
+        let kind = |type_name: &str| {
+            let property = Property {
+                name: "Field".to_string(),
+                type_name: type_name.to_string(),
+                ..Default::default()
+            };
+            TypeKind::of(&property, &metadata)
+        };
+
+        assert_eq!(kind("Edm.Int32"), Some(TypeKind::Integer));
+        assert_eq!(kind("Edm.Double"), Some(TypeKind::Decimal));
+        assert_eq!(kind("enums.Status"), Some(TypeKind::Enum));
+        assert_eq!(kind("enums.Features"), None);
+        assert_eq!(kind("Edm.Guid"), None);
+        assert_eq!(TypeKind::from_id("datetime"), Some(TypeKind::DateTime));
+    }
+
+    #[test]
+    fn test_compare_instants() {
+        assert_eq!(
+            compare(
+                &json!("2025-01-15T10:30:00-06:00"),
+                &json!("2025-01-15T16:00:00Z")
+            ),
+            Ordering::Greater
+        );
+        assert_eq!(compare(&json!(2), &json!(10)), Ordering::Less);
+        assert_eq!(quote("O'Hare"), "'O''Hare'");
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = CertificationReport {
+            base_url: "https://api.example.com".to_string(),
+            resource: "Property".to_string(),
+            scenarios: vec![
+                ScenarioResult {
+                    id: "top".to_string(),
+                    name: "$top".to_string(),
+                    status: ScenarioStatus::Passed,
+                    requests: vec!["https://api.example.com/Property?$top=20".to_string()],
+                    message: None,
+                },
+                ScenarioResult {
+                    id: "has".to_string(),
+                    name: "has operator".to_string(),
+                    status: ScenarioStatus::Skipped,
+                    requests: Vec::new(),
+                    message: Some("no populated multi-valued enumeration".to_string()),
+                },
+            ],
+        };
+
+        assert!(report.is_passing());
+        let text = report.to_string();
+        assert!(text.contains("PASS  top"));
+        assert!(text.contains("GET https://api.example.com/Property?$top=20"));
+        assert!(text.ends_with("1 passed, 0 failed, 1 skipped"));
+        assert_eq!(report.to_json()["scenarios"][1]["status"], "skipped");
+    }
+}
@@ -2,8 +2,18 @@
 
 //! Client configuration and connection management
 
+use crate::auth::{ClientCredentials, ClientCredentialsAuth, TokenCache};
+use crate::cache::{HttpCache, Lookup};
 use crate::error::{ResoError, Result};
+use crate::fixtures::FixtureMode;
+use crate::http_client::HttpClientConfig;
+use crate::middleware::{BearerAuth, Middleware, Next, SendVia};
+use crate::options::{ODataResponse, RequestOptions};
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::transport::Transport;
 use reqwest::Client;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Configuration for RESO client
@@ -50,6 +60,36 @@ pub struct ClientConfig {
 
     /// HTTP timeout duration
     pub timeout: Duration,
+
+    /// Optional HTTP response cache
+    pub cache: Option<HttpCache>,
+
+    /// Optional record-and-replay fixture mode
+    pub fixtures: Option<FixtureMode>,
+
+    /// Optional OAuth 2.0 client credentials; replaces `token` when set
+    pub client_credentials: Option<ClientCredentials>,
+
+    /// Optional retry policy for transient failures
+    pub retry: Option<RetryPolicy>,
+
+    /// Optional client-side rate limit
+    pub rate_limiter: Option<RateLimiter>,
+
+    /// Resources this feed serves by default, e.g. from a profile
+    pub resources: Vec<String>,
+
+    /// Custom middlewares, run after the built-in ones in this order
+    pub middlewares: Vec<Arc<dyn Middleware>>,
+
+    /// Proxy, TLS, timeout, pool and protocol settings for the HTTP client
+    pub http: HttpClientConfig,
+
+    /// Pre-built HTTP client; replaces `timeout` and `http` when set
+    pub http_client: Option<Client>,
+
+    /// Custom transport that sends requests instead of the HTTP client
+    pub transport: Option<Arc<dyn Transport>>,
 }
 
 impl std::fmt::Debug for ClientConfig {
@@ -59,6 +99,16 @@ impl std::fmt::Debug for ClientConfig {
             .field("token", &"<redacted>")
             .field("dataset_id", &self.dataset_id)
             .field("timeout", &self.timeout)
+            .field("cache", &self.cache)
+            .field("fixtures", &self.fixtures)
+            .field("client_credentials", &self.client_credentials)
+            .field("retry", &self.retry)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("resources", &self.resources)
+            .field("middlewares", &self.middlewares.len())
+            .field("http", &self.http)
+            .field("http_client", &self.http_client.is_some())
+            .field("transport", &self.transport.is_some())
             .finish()
     }
 }
@@ -99,9 +149,37 @@ impl ClientConfig {
             token,
             dataset_id,
             timeout: Duration::from_secs(timeout_secs),
+            cache: None,
+            fixtures: None,
+            client_credentials: None,
+            retry: None,
+            rate_limiter: None,
+            resources: Vec::new(),
+            middlewares: Vec::new(),
+            http: HttpClientConfig::default(),
+            http_client: None,
+            transport: None,
         })
     }
 
+    /// Load the named feed from the profile file
+    ///
+    /// The file is located with [`Profiles::discover`](crate::Profiles::discover);
+    /// see the [`profile`](crate::profile) module for its format and the
+    /// environment variables that override it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ClientConfig, ResoClient};
+    /// let config = ClientConfig::from_profile("crmls")?;
+    /// let client = ResoClient::with_config(config)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_profile(name: &str) -> Result<Self> {
+        crate::profile::Profiles::discover()?.client_config(name)
+    }
+
     /// Create configuration manually
     ///
     /// # Examples
@@ -119,6 +197,16 @@ impl ClientConfig {
             token: token.into(),
             dataset_id: None,
             timeout: Duration::from_secs(30),
+            cache: None,
+            fixtures: None,
+            client_credentials: None,
+            retry: None,
+            rate_limiter: None,
+            resources: Vec::new(),
+            middlewares: Vec::new(),
+            http: HttpClientConfig::default(),
+            http_client: None,
+            transport: None,
         }
     }
 
@@ -153,12 +241,174 @@ impl ClientConfig {
         self.timeout = timeout;
         self
     }
+
+    /// Enable HTTP response caching
+    ///
+    /// See [`HttpCache`] for which responses are cached and for how long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::{ClientConfig, HttpCache};
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token")
+    ///     .with_cache(HttpCache::in_memory());
+    /// ```
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Record every request and response into fixture files under `dir`
+    ///
+    /// See the [`fixtures`](crate::fixtures) module for the file format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::ClientConfig;
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token")
+    ///     .record_fixtures("tests/fixtures/property");
+    /// ```
+    pub fn record_fixtures(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.fixtures = Some(FixtureMode::Record(dir.into()));
+        self
+    }
+
+    /// Serve responses from fixture files under `dir` without network access
+    ///
+    /// Requests without a recorded fixture fail with
+    /// [`ResoError::FixtureNotFound`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::ClientConfig;
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "unused")
+    ///     .replay_fixtures("tests/fixtures/property");
+    /// ```
+    pub fn replay_fixtures(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.fixtures = Some(FixtureMode::Replay(dir.into()));
+        self
+    }
+
+    /// Authenticate with OAuth 2.0 client credentials instead of `token`
+    ///
+    /// See the [`auth`](crate::auth) module for token caching and renewal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::{ClientConfig, ClientCredentials};
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "")
+    ///     .with_client_credentials(ClientCredentials::new(
+    ///         "https://auth.mls.com/oauth/token",
+    ///         "client-id",
+    ///         "client-secret",
+    ///     ));
+    /// ```
+    pub fn with_client_credentials(mut self, credentials: ClientCredentials) -> Self {
+        self.client_credentials = Some(credentials);
+        self
+    }
+
+    /// Retry transient failures
+    ///
+    /// See [`RetryPolicy`] for which failures are retried.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::{ClientConfig, RetryPolicy};
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token")
+    ///     .with_retry(RetryPolicy::new(5));
+    /// ```
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Limit the request rate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::{ClientConfig, RateLimiter};
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token")
+    ///     .with_rate_limiter(RateLimiter::per_minute(120.0));
+    /// ```
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Configure the underlying HTTP client
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::{ClientConfig, HttpClientConfig};
+    /// # use std::time::Duration;
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token").with_http(
+    ///     HttpClientConfig::new()
+    ///         .with_connect_timeout(Duration::from_secs(5))
+    ///         .with_read_timeout(Duration::from_secs(60)),
+    /// );
+    /// ```
+    pub fn with_http(mut self, http: HttpClientConfig) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Send requests through a pre-built `reqwest::Client`
+    ///
+    /// The client's own settings apply; `timeout` and
+    /// [`with_http`](Self::with_http) are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::ClientConfig;
+    /// let http_client = reqwest::Client::builder()
+    ///     .user_agent("listing-sync/1.0")
+    ///     .build()?;
+    /// let config = ClientConfig::new("https://api.mls.com/odata", "token")
+    ///     .with_http_client(http_client);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Send requests through a custom [`Transport`] instead of reqwest
+    ///
+    /// Requests still pass through the whole middleware chain; the transport
+    /// replaces only the final network call. Responses are read in full by
+    /// the transport, and `timeout`, [`with_http`](Self::with_http) and
+    /// [`with_http_client`](Self::with_http_client) do not apply to it.
+    /// Token requests for OAuth client credentials still use reqwest.
+    ///
+    /// See the [`transport`](crate::transport) module.
+    pub fn with_transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Add a middleware to the end of the request chain
+    ///
+    /// See the [`middleware`](crate::middleware) module for the chain order.
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
 }
 
 /// RESO Web API client
 pub struct ResoClient {
     config: ClientConfig,
     http_client: Client,
+    lookups: Arc<tokio::sync::OnceCell<Arc<crate::lookup::LookupCache>>>,
+    middlewares: Arc<[Arc<dyn Middleware>]>,
 }
 
 impl ResoClient {
@@ -198,14 +448,38 @@ impl ResoClient {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn with_config(config: ClientConfig) -> Result<Self> {
-        let http_client = Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .map_err(|e| ResoError::Config(format!("Failed to create HTTP client: {}", e)))?;
+        let http_client = match &config.http_client {
+            Some(client) => client.clone(),
+            None => config.http.build(config.timeout)?,
+        };
+
+        let mut middlewares: Vec<Arc<dyn Middleware>> = Vec::new();
+        if let Some(policy) = config.retry {
+            middlewares.push(Arc::new(policy));
+        }
+        if let Some(limiter) = &config.rate_limiter {
+            middlewares.push(Arc::new(limiter.clone()));
+        }
+        match &config.client_credentials {
+            Some(credentials) => middlewares.push(Arc::new(ClientCredentialsAuth {
+                credentials: credentials.clone(),
+                http_client: http_client.clone(),
+                tokens: TokenCache::default(),
+            })),
+            None => middlewares.push(Arc::new(BearerAuth {
+                token: config.token.clone(),
+            })),
+        }
+        middlewares.extend(config.middlewares.iter().cloned());
+        if let Some(transport) = &config.transport {
+            middlewares.push(Arc::new(SendVia(transport.clone())));
+        }
 
         Ok(Self {
             config,
             http_client,
+            lookups: Arc::default(),
+            middlewares: middlewares.into(),
         })
     }
 
@@ -224,16 +498,43 @@ impl ResoClient {
         &self.config.base_url
     }
 
+    /// A client sharing this one's configuration that bypasses the response cache
+    ///
+    /// Requests made through the returned client neither read nor update the
+    /// cache. Cheap to create; the underlying connection pool is shared.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ResoClient;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let metadata = client.uncached().fetch_metadata().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn uncached(&self) -> Self {
+        Self {
+            config: ClientConfig {
+                cache: None,
+                ..self.config.clone()
+            },
+            http_client: self.http_client.clone(),
+            lookups: self.lookups.clone(),
+            middlewares: self.middlewares.clone(),
+        }
+    }
+
     /// Build full URL with optional dataset_id
     ///
     /// Some RESO servers require a dataset ID in the URL path between the base URL
     /// and the resource/query path (e.g., `https://api.mls.com/odata/{dataset_id}/Property`).
     /// This method handles both cases transparently.
-    fn build_url(&self, path: &str) -> String {
-        match &self.config.dataset_id {
-            Some(dataset_id) => format!("{}/{}/{}", self.config.base_url, dataset_id, path),
-            None => format!("{}/{}", self.config.base_url, path),
-        }
+    pub(crate) fn build_url(&self, path: &str) -> String {
+        crate::transport::service_url(
+            &self.config.base_url,
+            self.config.dataset_id.as_deref(),
+            path,
+        )
     }
 
     /// Send an authenticated GET request and handle error responses
@@ -242,20 +543,103 @@ impl ResoClient {
     /// 1. Sending a GET request with Authorization header
     /// 2. Checking the response status
     /// 3. Converting error responses to appropriate ResoError variants
-    async fn send_authenticated_request(
+    pub(crate) async fn send_authenticated_request(
         &self,
         url: &str,
         accept: &str,
+        options: &RequestOptions,
     ) -> Result<reqwest::Response> {
-        let response = self
+        let response = self.send_get(url, accept, &[], options).await?;
+        Self::check_status(response).await
+    }
+
+    /// Send an authenticated GET request through the response cache, if enabled
+    ///
+    /// Fresh cached responses are returned without a request. Stale ones are
+    /// revalidated with a conditional request and reused on `304 Not Modified`.
+    /// `ttl_override` replaces the freshness lifetime (used for `$metadata`).
+    /// Requests whose options set headers bypass the cache, since the cache
+    /// keys responses by URL alone.
+    async fn send_cached_request(
+        &self,
+        url: &str,
+        accept: &str,
+        ttl_override: Option<Duration>,
+        options: &RequestOptions,
+    ) -> Result<reqwest::Response> {
+        use tracing::debug;
+
+        let cache = match &self.config.cache {
+            Some(cache) if !options.has_headers() => cache,
+            _ => return self.send_authenticated_request(url, accept, options).await,
+        };
+
+        let lookup = cache.fresh(url, ttl_override);
+        let conditional = lookup.conditional_headers();
+        let stale = match lookup {
+            Lookup::Fresh(response) => {
+                debug!("Cache hit: {}", url);
+                return Ok(response);
+            }
+            Lookup::Stale(entry) => Some(entry),
+            Lookup::Miss => None,
+        };
+
+        let response = self.send_get(url, accept, &conditional, options).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(entry) = stale {
+                debug!("Cache revalidated: {}", url);
+                return Ok(cache.not_modified(url, entry, &response));
+            }
+        }
+
+        let response = Self::check_status(response).await?;
+        cache.store(url, response, ttl_override).await
+    }
+
+    /// Send an authenticated GET request without checking the status
+    ///
+    /// The request passes through the middleware chain, which applies
+    /// retries, rate limiting and authentication.
+    async fn send_get(
+        &self,
+        url: &str,
+        accept: &str,
+        extra_headers: &[(&'static str, String)],
+        options: &RequestOptions,
+    ) -> Result<reqwest::Response> {
+        let mut request = self
             .http_client
             .get(url)
-            .header("Authorization", format!("Bearer {}", self.config.token))
-            .header("Accept", accept)
-            .send()
-            .await
+            .header("Accept", options.accept(accept));
+        for (name, value) in extra_headers {
+            request = request.header(*name, value);
+        }
+        let mut request = request
+            .build()
             .map_err(|e| ResoError::Network(e.to_string()))?;
+        options.apply_headers(request.headers_mut())?;
 
+        if let Some(FixtureMode::Replay(dir)) = &self.config.fixtures {
+            return crate::fixtures::replay(dir, url, request.headers());
+        }
+        let request_headers = request.headers().clone();
+
+        let response = Next::new(&self.http_client, &self.middlewares)
+            .run(request)
+            .await?;
+
+        match &self.config.fixtures {
+            Some(FixtureMode::Record(dir)) => {
+                crate::fixtures::record(dir, url, &request_headers, response).await
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Convert error responses to the appropriate [`ResoError`]
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
         let status = response.status();
 
         // Check for error responses and extract the body for detailed error information
@@ -317,25 +701,202 @@ impl ResoClient {
     /// # }
     /// ```
     pub async fn execute(&self, query: &crate::queries::Query) -> Result<serde_json::Value> {
+        self.execute_with(query, &RequestOptions::default()).await
+    }
+
+    /// Execute a query with per-call options
+    ///
+    /// Like [`execute`](Self::execute), with the limits and headers of
+    /// `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{QueryBuilder, RequestOptions, ResoClient};
+    /// # use std::time::Duration;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = QueryBuilder::new("Property").top(200).build()?;
+    /// let options = RequestOptions::new().with_timeout(Duration::from_secs(120));
+    /// let results = client.execute_with(&query, &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_with(
+        &self,
+        query: &crate::queries::Query,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value> {
+        Ok(self.execute_response(query, options).await?.body)
+    }
+
+    /// Execute a query and return the JSON body with the response headers
+    ///
+    /// Like [`execute_with`](Self::execute_with), but keeps the status and
+    /// headers, e.g. to see which preferences the server applied.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{QueryBuilder, RequestOptions, ResoClient};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = QueryBuilder::new("Property").build()?;
+    /// let options = RequestOptions::new().with_max_page_size(1000);
+    ///
+    /// let response = client.execute_response(&query, &options).await?;
+    /// println!("Applied: {:?}", response.preference_applied());
+    /// println!("{} records", response.body["value"].as_array().map_or(0, |v| v.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_response(
+        &self,
+        query: &crate::queries::Query,
+        options: &RequestOptions,
+    ) -> Result<ODataResponse<serde_json::Value>> {
+        options
+            .run(async {
+                use tracing::{debug, info};
+
+                let url = self.build_url(&query.to_odata_string());
+                info!("Executing query: {}", url);
+
+                let response = self
+                    .send_cached_request(&url, "application/json", None, options)
+                    .await?;
+                let status = response.status().as_u16();
+                let headers = response.headers().clone();
+                let json = Self::parse_json_response(response).await?;
+
+                debug!(
+                    "Query result: {} records",
+                    json.get("value")
+                        .and_then(|v| v.as_array())
+                        .map(|a| a.len())
+                        .unwrap_or(0)
+                );
+
+                Ok(ODataResponse {
+                    body: json,
+                    status,
+                    headers,
+                })
+            })
+            .await
+    }
+
+    /// Execute a query into a sink, following `@odata.nextLink` pages
+    ///
+    /// Servers that page standard query results return an `@odata.nextLink`
+    /// in the response body. This method follows those links, writing each page
+    /// to `sink` and committing a checkpoint after it, exactly like
+    /// [`replicate_into`](Self::replicate_into) does for the replication endpoint.
+    ///
+    /// Returns the number of records committed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, QueryBuilder, MemorySink};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = QueryBuilder::new("Property")
+    ///     .filter("StandardStatus eq 'Active'")
+    ///     .top(200)
+    ///     .build()?;
+    ///
+    /// let mut sink = MemorySink::new();
+    /// let count = client.execute_into(&query, &mut sink).await?;
+    /// println!("Exported {} records", count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_into<S: crate::sink::RecordSink + ?Sized>(
+        &self,
+        query: &crate::queries::Query,
+        sink: &mut S,
+    ) -> Result<u64> {
+        self.execute_into_with(query, sink, &RequestOptions::default())
+            .await
+    }
+
+    /// Execute a query into a sink with per-call options
+    ///
+    /// Like [`execute_into`](Self::execute_into), with the headers of
+    /// `options` on every page request. The timeout applies to each page,
+    /// the deadline and cancellation to the whole run; pages already
+    /// committed stay committed.
+    pub async fn execute_into_with<S: crate::sink::RecordSink + ?Sized>(
+        &self,
+        query: &crate::queries::Query,
+        sink: &mut S,
+        options: &RequestOptions,
+    ) -> Result<u64> {
         use tracing::{debug, info};
 
-        let url = self.build_url(&query.to_odata_string());
-        info!("Executing query: {}", url);
+        let (mut delivery, resume_link) =
+            crate::sink::PageDelivery::resume(sink, query.resource(), &query.to_odata_string())
+                .await?;
+        let mut url = resume_link.unwrap_or_else(|| self.build_url(&query.to_odata_string()));
+
+        loop {
+            info!("Executing query page: {}", url);
+
+            let (records, next_link) = options
+                .run(async {
+                    let response = self
+                        .send_authenticated_request(&url, "application/json", options)
+                        .await?;
+                    // Records are parsed as the body arrives instead of building one
+                    // large JSON tree for the whole page
+                    let mut reader = crate::streaming::RecordReader::new(response);
+                    let records = reader.collect().await?;
+                    Ok((records, reader.odata_next_link()))
+                })
+                .await?;
+
+            debug!(
+                "Retrieved {} records, next link: {:?}",
+                records.len(),
+                next_link
+            );
+
+            delivery.deliver(&records, next_link.clone()).await?;
+
+            match next_link {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
 
-        let response = self
-            .send_authenticated_request(&url, "application/json")
-            .await?;
-        let json = Self::parse_json_response(response).await?;
-
-        debug!(
-            "Query result: {} records",
-            json.get("value")
-                .and_then(|v| v.as_array())
-                .map(|a| a.len())
-                .unwrap_or(0)
-        );
+        Ok(delivery.records_committed())
+    }
 
-        Ok(json)
+    /// Stream the records of a query, following `@odata.nextLink` pages
+    ///
+    /// Records are parsed one at a time as the response body arrives, so
+    /// memory use stays flat regardless of page size. Pages are requested
+    /// lazily and are not cached.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, QueryBuilder};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = QueryBuilder::new("Property")
+    ///     .filter("StandardStatus eq 'Active'")
+    ///     .build()?;
+    ///
+    /// let mut stream = client.stream_records(&query);
+    /// while let Some(record) = stream.next_record().await? {
+    ///     println!("{}", record["ListingKey"]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_records(
+        &self,
+        query: &crate::queries::Query,
+    ) -> crate::streaming::RecordStream<'_> {
+        crate::streaming::RecordStream::new(self, query)
     }
 
     /// Execute a direct key access query and return a single record
@@ -366,15 +927,32 @@ impl ResoClient {
     /// # }
     /// ```
     pub async fn execute_by_key(&self, query: &crate::queries::Query) -> Result<serde_json::Value> {
-        use tracing::info;
-
-        let url = self.build_url(&query.to_odata_string());
-        info!("Executing key access query: {}", url);
+        self.execute_by_key_with(query, &RequestOptions::default())
+            .await
+    }
 
-        let response = self
-            .send_authenticated_request(&url, "application/json")
-            .await?;
-        Self::parse_json_response(response).await
+    /// Execute a direct key access query with per-call options
+    ///
+    /// Like [`execute_by_key`](Self::execute_by_key), with the limits and
+    /// headers of `options`.
+    pub async fn execute_by_key_with(
+        &self,
+        query: &crate::queries::Query,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value> {
+        options
+            .run(async {
+                use tracing::info;
+
+                let url = self.build_url(&query.to_odata_string());
+                info!("Executing key access query: {}", url);
+
+                let response = self
+                    .send_cached_request(&url, "application/json", None, options)
+                    .await?;
+                Self::parse_json_response(response).await
+            })
+            .await
     }
 
     /// Execute a count-only query and return the count as an integer
@@ -399,22 +977,40 @@ impl ResoClient {
     /// # }
     /// ```
     pub async fn execute_count(&self, query: &crate::queries::Query) -> Result<u64> {
-        use tracing::info;
+        self.execute_count_with(query, &RequestOptions::default())
+            .await
+    }
 
-        let url = self.build_url(&query.to_odata_string());
-        info!("Executing count query: {}", url);
+    /// Execute a count-only query with per-call options
+    ///
+    /// Like [`execute_count`](Self::execute_count), with the limits and
+    /// headers of `options`.
+    pub async fn execute_count_with(
+        &self,
+        query: &crate::queries::Query,
+        options: &RequestOptions,
+    ) -> Result<u64> {
+        options
+            .run(async {
+                use tracing::info;
+
+                let url = self.build_url(&query.to_odata_string());
+                info!("Executing count query: {}", url);
 
-        let response = self.send_authenticated_request(&url, "text/plain").await?;
-        let text = Self::parse_text_response(response).await?;
+                let response = self
+                    .send_cached_request(&url, "text/plain", None, options)
+                    .await?;
+                let text = Self::parse_text_response(response).await?;
 
-        let count = text
-            .trim()
-            .parse::<u64>()
-            .map_err(|e| ResoError::Parse(format!("Failed to parse count '{}': {}", text, e)))?;
+                let count = text.trim().parse::<u64>().map_err(|e| {
+                    ResoError::Parse(format!("Failed to parse count '{}': {}", text, e))
+                })?;
 
-        info!("Count result: {}", count);
+                info!("Count result: {}", count);
 
-        Ok(count)
+                Ok(count)
+            })
+            .await
     }
 
     /// Fetch $metadata XML
@@ -440,12 +1036,126 @@ impl ResoClient {
         let url = self.build_url("$metadata");
         info!("Fetching metadata from: {}", url);
 
+        let metadata_ttl = self.config.cache.as_ref().and_then(HttpCache::metadata_ttl);
+
         let response = self
-            .send_authenticated_request(&url, "application/xml")
+            .send_cached_request(
+                &url,
+                "application/xml",
+                metadata_ttl,
+                &RequestOptions::default(),
+            )
             .await?;
         Self::parse_text_response(response).await
     }
 
+    /// Fetch and parse $metadata
+    ///
+    /// Same request as [`fetch_metadata`](Self::fetch_metadata), parsed into
+    /// entity types, properties and enum types.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ResoClient;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let metadata = client.fetch_parsed_metadata().await?;
+    ///
+    /// if let Some(property) = metadata.entity_type("Property") {
+    ///     println!("Property has {} fields", property.properties.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_parsed_metadata(&self) -> Result<crate::metadata::Metadata> {
+        let xml = self.fetch_metadata().await?;
+        crate::metadata::Metadata::parse(&xml)
+    }
+
+    /// Load the `Lookup` resource into a [`LookupCache`](crate::LookupCache)
+    ///
+    /// Follows `@odata.nextLink` pages until every lookup value is loaded.
+    /// The result only ties fields to lookups by name; see
+    /// [`lookups`](Self::lookups) for a cache that also uses `$metadata`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ResoClient;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let lookups = client.fetch_lookups().await?;
+    /// println!("Loaded {} lookup values", lookups.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_lookups(&self) -> Result<crate::lookup::LookupCache> {
+        let query = crate::queries::QueryBuilder::new(crate::lookup::LOOKUP_RESOURCE).build()?;
+        let mut sink = crate::sink::MemorySink::new();
+        self.execute_into(&query, &mut sink).await?;
+        Ok(crate::lookup::LookupCache::from_records(sink.records()))
+    }
+
+    /// The client's lookup cache, loading it on first use
+    ///
+    /// Loads the `Lookup` resource and `$metadata` once; later calls (also
+    /// through [`uncached`](Self::uncached) clients) share the result. If
+    /// loading fails, the next call tries again.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ResoClient;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let lookups = client.lookups().await?;
+    /// let status = lookups.resolve("StandardStatus", "ActiveUnderContract");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn lookups(&self) -> Result<Arc<crate::lookup::LookupCache>> {
+        self.lookups
+            .get_or_try_init(|| async {
+                let metadata = self.fetch_parsed_metadata().await?;
+                let lookups = self.fetch_lookups().await?.with_metadata(&metadata);
+                Ok(Arc::new(lookups))
+            })
+            .await
+            .cloned()
+    }
+
+    /// Fetch the media of a listing, ordered by `Order`
+    ///
+    /// Queries the `Media` resource for records with `ResourceName eq
+    /// 'Property'` and the given `ResourceRecordKey`, following
+    /// `@odata.nextLink` pages. Use
+    /// [`MediaItem::from_expanded`](crate::MediaItem::from_expanded) instead
+    /// when the listing was fetched with `$expand=Media`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::ResoClient;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let items = client.fetch_media("12345").await?;
+    /// let downloads = client.media_downloader().download(&items).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_media(&self, listing_key: &str) -> Result<Vec<crate::media::MediaItem>> {
+        let query = crate::media::media_query("Property", listing_key)?;
+        let mut sink = crate::sink::MemorySink::new();
+        self.execute_into(&query, &mut sink).await?;
+        Ok(crate::media::MediaItem::from_records(sink.records()))
+    }
+
+    /// A media downloader that uses this client's HTTP client
+    ///
+    /// Downloads go through the proxy, root certificates, client identity
+    /// and pool settings of the [`ClientConfig`], or its pre-built client.
+    /// Media URLs usually point at a CDN, so no bearer token is sent.
+    pub fn media_downloader(&self) -> crate::media::MediaDownloader {
+        crate::media::MediaDownloader::new().with_http_client(self.http_client.clone())
+    }
+
     /// Execute a replication query
     ///
     /// The replication endpoint is designed for bulk data transfer and supports
@@ -485,43 +1195,156 @@ impl ResoClient {
         &self,
         query: &crate::queries::ReplicationQuery,
     ) -> Result<crate::replication::ReplicationResponse> {
-        use tracing::{debug, info};
+        self.execute_replication_with(query, &RequestOptions::default())
+            .await
+    }
+
+    /// Execute a replication query with per-call options
+    ///
+    /// Like [`execute_replication`](Self::execute_replication), with the
+    /// limits and headers of `options`.
+    pub async fn execute_replication_with(
+        &self,
+        query: &crate::queries::ReplicationQuery,
+        options: &RequestOptions,
+    ) -> Result<crate::replication::ReplicationResponse> {
+        Ok(self
+            .execute_replication_response(query, options)
+            .await?
+            .body)
+    }
+
+    /// Execute a replication query and return the page with the response headers
+    ///
+    /// Like [`execute_replication_with`](Self::execute_replication_with), but
+    /// keeps the status and headers of the response.
+    pub async fn execute_replication_response(
+        &self,
+        query: &crate::queries::ReplicationQuery,
+        options: &RequestOptions,
+    ) -> Result<ODataResponse<crate::replication::ReplicationResponse>> {
+        use tracing::info;
 
         let url = self.build_url(&query.to_odata_string());
         info!("Executing replication query: {}", url);
 
-        let response = self
-            .send_authenticated_request(&url, "application/json")
-            .await?;
+        options
+            .run(self.fetch_replication_page(&url, options))
+            .await
+    }
 
-        // Extract next link from response headers before consuming response
-        // The replication endpoint uses the "next" header (preferred) or "link" header
-        // to indicate more records are available. This must be extracted before reading
-        // the response body since consuming the response moves ownership.
-        let next_link = response
-            .headers()
-            .get("next")
-            .or_else(|| response.headers().get("link"))
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
+    /// Stream a replication query page by page
+    ///
+    /// Returns a [`ReplicationStream`](crate::replication::ReplicationStream) that
+    /// follows `next` links automatically. Attach a
+    /// [`ProgressObserver`](crate::progress::ProgressObserver) with
+    /// `with_progress()` to track how far along the run is.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Property")
+    ///     .select(&["ListingKey", "ModificationTimestamp"])
+    ///     .top(2000)
+    ///     .build()?;
+    ///
+    /// let mut stream = client.replicate(&query);
+    /// while let Some(page) = stream.next_page().await? {
+    ///     println!("Fetched {} records", page.record_count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replicate(
+        &self,
+        query: &crate::queries::ReplicationQuery,
+    ) -> crate::replication::ReplicationStream<'_> {
+        crate::replication::ReplicationStream::new(self, query.clone())
+    }
 
-        debug!("Next link from headers: {:?}", next_link);
+    /// Replicate a query into a sink
+    ///
+    /// Drives the replication endpoint page by page, writing records to `sink`
+    /// and committing a checkpoint after every page. An unfinished checkpoint
+    /// reported by the sink is resumed, so an interrupted run never skips records
+    /// (records from the last uncommitted page may be delivered again).
+    ///
+    /// See [`ReplicationStream::drain_into`](crate::replication::ReplicationStream::drain_into)
+    /// to combine this with a progress observer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder, MemorySink};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Member")
+    ///     .top(2000)
+    ///     .build()?;
+    ///
+    /// let mut sink = MemorySink::new();
+    /// let progress = client.replicate_into(&query, &mut sink).await?;
+    /// println!("Replicated {} members", progress.records_fetched);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn replicate_into<S: crate::sink::RecordSink + ?Sized>(
+        &self,
+        query: &crate::queries::ReplicationQuery,
+        sink: &mut S,
+    ) -> Result<crate::progress::ReplicationProgress> {
+        self.replicate(query).drain_into(sink).await
+    }
 
-        let json = Self::parse_json_response(response).await?;
+    /// Build the full URL for a replication query
+    pub(crate) fn replication_url(&self, query: &crate::queries::ReplicationQuery) -> String {
+        self.build_url(&query.to_odata_string())
+    }
 
-        // Extract records from OData response envelope
-        // OData wraps result arrays in a "value" field: {"value": [...], "@odata.context": "..."}
-        let records = json
-            .get("value")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
+    /// Fetch one page from the replication endpoint
+    pub(crate) async fn fetch_replication_page(
+        &self,
+        url: &str,
+        options: &RequestOptions,
+    ) -> Result<ODataResponse<crate::replication::ReplicationResponse>> {
+        use tracing::debug;
+
+        let (mut reader, next_link) = self.open_replication_page(url, options).await?;
+        let status = reader.status();
+        let headers = reader.headers().clone();
+        let records = reader.collect().await?;
 
         debug!("Retrieved {} records", records.len());
 
-        Ok(crate::replication::ReplicationResponse::new(
-            records, next_link,
-        ))
+        Ok(ODataResponse {
+            body: crate::replication::ReplicationResponse::new(records, next_link),
+            status,
+            headers,
+        })
+    }
+
+    /// Request one page from the replication endpoint without reading its body
+    ///
+    /// Returns a reader over the page's records and the link to the next page.
+    pub(crate) async fn open_replication_page(
+        &self,
+        url: &str,
+        options: &RequestOptions,
+    ) -> Result<(crate::streaming::RecordReader, Option<String>)> {
+        use tracing::debug;
+
+        let response = self
+            .send_authenticated_request(url, "application/json", options)
+            .await?;
+
+        // The replication endpoint uses the "next" header (preferred) or "link" header
+        // to indicate more records are available, rather than @odata.nextLink
+        let next_link = crate::transport::replication_next_link(response.headers());
+
+        debug!("Next link from headers: {:?}", next_link);
+
+        Ok((crate::streaming::RecordReader::new(response), next_link))
     }
 
     /// Execute a next link from a previous replication response
@@ -555,42 +1378,27 @@ impl ResoClient {
         &self,
         next_link: &str,
     ) -> Result<crate::replication::ReplicationResponse> {
-        use tracing::{debug, info};
+        self.execute_next_link_with(next_link, &RequestOptions::default())
+            .await
+    }
+
+    /// Execute a next link with per-call options
+    ///
+    /// Like [`execute_next_link`](Self::execute_next_link), with the limits
+    /// and headers of `options`.
+    pub async fn execute_next_link_with(
+        &self,
+        next_link: &str,
+        options: &RequestOptions,
+    ) -> Result<crate::replication::ReplicationResponse> {
+        use tracing::info;
 
         info!("Executing next link: {}", next_link);
 
-        let response = self
-            .send_authenticated_request(next_link, "application/json")
+        let response = options
+            .run(self.fetch_replication_page(next_link, options))
             .await?;
-
-        // Extract next link from response headers before consuming response
-        // The replication endpoint uses the "next" header (preferred) or "link" header
-        // to indicate more records are available. This must be extracted before reading
-        // the response body since consuming the response moves ownership.
-        let next_link = response
-            .headers()
-            .get("next")
-            .or_else(|| response.headers().get("link"))
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
-
-        debug!("Next link from headers: {:?}", next_link);
-
-        let json = Self::parse_json_response(response).await?;
-
-        // Extract records from OData response envelope
-        // OData wraps result arrays in a "value" field: {"value": [...], "@odata.context": "..."}
-        let records = json
-            .get("value")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
-
-        debug!("Retrieved {} records", records.len());
-
-        Ok(crate::replication::ReplicationResponse::new(
-            records, next_link,
-        ))
+        Ok(response.body)
     }
 }
 
@@ -0,0 +1,597 @@
+// src/compliance.rs
+
+//! RESO Data Dictionary compliance analysis
+//!
+//! [`ComplianceAnalyzer`] compares a resource in a server's parsed `$metadata`
+//! against Data Dictionary definitions and, given a sample of records,
+//! measures how often each field is populated. The resulting
+//! [`ComplianceReport`] serializes to JSON.
+//!
+//! The report lists:
+//!
+//! - standard fields the server does not declare
+//! - standard fields declared with an incompatible type
+//! - non-standard fields whose names look like misspellings of standard ones
+//!   (case differences or a small edit distance)
+//! - the remaining non-standard (local) fields
+//! - population rates for every declared or sampled field
+//!
+//! # Bundled Definitions
+//!
+//! [`DataDictionary::bundled`] covers the commonly used fields of the
+//! Property, Member, Office, Media, OpenHouse and Lookup resources from Data
+//! Dictionary 2.0. Load the complete dictionary, or a local profile of it,
+//! with [`DataDictionary::from_json`].
+//!
+//! # Type Compatibility
+//!
+//! Lookup fields accept `Edm.String` (Data Dictionary 2.0 string lookups) or
+//! an enum type; multi-valued lookups accept collections of either, or a
+//! flags enum. Integer types are interchangeable, as are `Edm.Decimal`,
+//! `Edm.Double` and `Edm.Single`. Other types must match exactly.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{ComplianceAnalyzer, QueryBuilder, ResoClient};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let metadata = client.fetch_parsed_metadata().await?;
+//! let sample = client
+//!     .execute(&QueryBuilder::new("Property").top(200).build()?)
+//!     .await?;
+//! let records = sample["value"].as_array().cloned().unwrap_or_default();
+//!
+//! let report = ComplianceAnalyzer::new().analyze(&metadata, "Property", &records)?;
+//! println!("Coverage: {:.1}%", report.coverage);
+//! println!("{}", serde_json::to_string_pretty(&report)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::metadata::{Metadata, Property};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+const BUNDLED: &str = include_str!("compliance/data_dictionary.tsv");
+
+/// A field defined by the Data Dictionary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandardField {
+    /// Field name
+    pub name: String,
+
+    /// Expected EDM type; `Edm.String` for lookup fields
+    #[serde(rename = "type")]
+    pub type_name: String,
+
+    /// Lookup name for enumerated fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookup: Option<String>,
+
+    /// Whether the field holds several values (`String List, Multi`)
+    #[serde(default)]
+    pub multi: bool,
+}
+
+/// Data Dictionary field definitions by resource
+///
+/// The JSON form read by [`from_json`](Self::from_json):
+///
+/// ```json
+/// {
+///   "version": "2.0",
+///   "resources": {
+///     "Property": [
+///       { "name": "ListingKey", "type": "Edm.String" },
+///       { "name": "StandardStatus", "type": "Edm.String", "lookup": "StandardStatus" },
+///       { "name": "Appliances", "type": "Edm.String", "lookup": "Appliances", "multi": true }
+///     ]
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataDictionary {
+    /// Data Dictionary version
+    pub version: String,
+
+    /// Field definitions by resource name
+    pub resources: BTreeMap<String, Vec<StandardField>>,
+}
+
+impl DataDictionary {
+    /// The bundled Data Dictionary 2.0 definitions
+    pub fn bundled() -> &'static DataDictionary {
+        static BUNDLED_DICTIONARY: OnceLock<DataDictionary> = OnceLock::new();
+        BUNDLED_DICTIONARY.get_or_init(|| {
+            let mut resources: BTreeMap<String, Vec<StandardField>> = BTreeMap::new();
+            for line in BUNDLED
+                .lines()
+                .filter(|l| !l.starts_with('#') && !l.is_empty())
+            {
+                let columns: Vec<&str> = line.split('\t').collect();
+                if let [resource, name, type_name, lookup, cardinality] = columns[..] {
+                    resources
+                        .entry(resource.to_string())
+                        .or_default()
+                        .push(StandardField {
+                            name: name.to_string(),
+                            type_name: type_name.to_string(),
+                            lookup: (!lookup.is_empty()).then(|| lookup.to_string()),
+                            multi: cardinality == "multi",
+                        });
+                }
+            }
+            DataDictionary {
+                version: "2.0".to_string(),
+                resources,
+            }
+        })
+    }
+
+    /// Parse definitions from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| ResoError::Parse(format!("Invalid Data Dictionary definitions: {}", e)))
+    }
+
+    /// Fields of a resource
+    pub fn fields(&self, resource: &str) -> Option<&[StandardField]> {
+        self.resources.get(resource).map(Vec::as_slice)
+    }
+}
+
+/// A standard field declared with an incompatible type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeMismatch {
+    /// Field name
+    pub field: String,
+    /// Type the Data Dictionary expects
+    pub expected: String,
+    /// Type the server declares
+    pub actual: String,
+}
+
+/// A non-standard field that is probably a misspelled standard field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Misspelling {
+    /// Field name on the server
+    pub field: String,
+    /// Standard field it resembles
+    pub standard_field: String,
+    /// Edit distance between the names, ignoring case
+    pub distance: usize,
+}
+
+/// How often a field is populated in the sample
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldPopulation {
+    /// Field name
+    pub field: String,
+    /// Whether the field is a standard field
+    pub standard: bool,
+    /// Records with a non-empty value
+    pub populated: usize,
+    /// Percentage of sampled records with a non-empty value
+    pub population_rate: f64,
+}
+
+/// Result of a compliance analysis
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplianceReport {
+    /// Resource analyzed
+    pub resource: String,
+    /// Qualified entity type behind the resource
+    pub entity_type: String,
+    /// Version of the definitions used
+    pub dictionary_version: String,
+    /// Number of standard fields defined for the resource
+    pub standard_field_count: usize,
+    /// Number of standard fields the server declares
+    pub standard_fields_present: usize,
+    /// Percentage of standard fields the server declares
+    pub coverage: f64,
+    /// Standard fields the server does not declare
+    pub missing_fields: Vec<String>,
+    /// Standard fields declared with an incompatible type
+    pub type_mismatches: Vec<TypeMismatch>,
+    /// Non-standard fields resembling standard ones
+    pub possible_misspellings: Vec<Misspelling>,
+    /// Other non-standard fields
+    pub local_fields: Vec<String>,
+    /// Number of sampled records
+    pub sample_size: usize,
+    /// Population per field, most populated first
+    pub population: Vec<FieldPopulation>,
+}
+
+impl ComplianceReport {
+    /// The report as JSON
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).unwrap_or(JsonValue::Null)
+    }
+}
+
+/// Compares `$metadata` and sample records against Data Dictionary
+/// definitions
+#[derive(Debug, Clone)]
+pub struct ComplianceAnalyzer {
+    dictionary: DataDictionary,
+    max_distance: usize,
+}
+
+impl Default for ComplianceAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComplianceAnalyzer {
+    /// Analyzer using the bundled Data Dictionary 2.0 definitions
+    pub fn new() -> Self {
+        Self::with_dictionary(DataDictionary::bundled().clone())
+    }
+
+    /// Analyzer using other definitions
+    pub fn with_dictionary(dictionary: DataDictionary) -> Self {
+        Self {
+            dictionary,
+            max_distance: 2,
+        }
+    }
+
+    /// Largest edit distance reported as a misspelling (default 2)
+    ///
+    /// Names shorter than four characters per unit of distance are never
+    /// reported, so short local names are not flagged.
+    pub fn max_edit_distance(mut self, distance: usize) -> Self {
+        self.max_distance = distance;
+        self
+    }
+
+    /// Analyze `resource` in `metadata`, with population statistics from
+    /// `sample` (which may be empty)
+    ///
+    /// # Errors
+    ///
+    /// [`ResoError::InvalidQuery`] if the definitions do not cover the
+    /// resource or the metadata has no entity type for it.
+    pub fn analyze(
+        &self,
+        metadata: &Metadata,
+        resource: &str,
+        sample: &[JsonValue],
+    ) -> Result<ComplianceReport> {
+        let standard = self.dictionary.fields(resource).ok_or_else(|| {
+            ResoError::InvalidQuery(format!(
+                "Data Dictionary {} has no definitions for {}",
+                self.dictionary.version, resource
+            ))
+        })?;
+        let entity_type = metadata.entity_type_for_resource(resource).ok_or_else(|| {
+            ResoError::InvalidQuery(format!("$metadata has no entity type for {}", resource))
+        })?;
+
+        let standard_names: BTreeSet<&str> = standard.iter().map(|f| f.name.as_str()).collect();
+        let mut missing_fields = Vec::new();
+        let mut type_mismatches = Vec::new();
+        for field in standard {
+            match entity_type.property(&field.name) {
+                None => missing_fields.push(field.name.clone()),
+                Some(property) if !compatible(field, property, metadata) => {
+                    type_mismatches.push(TypeMismatch {
+                        field: field.name.clone(),
+                        expected: expected_type(field),
+                        actual: property.type_name.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut possible_misspellings = Vec::new();
+        let mut local_fields = Vec::new();
+        for property in &entity_type.properties {
+            if standard_names.contains(property.name.as_str()) {
+                continue;
+            }
+            match self.closest(&property.name, &missing_fields) {
+                Some((standard_field, distance)) => possible_misspellings.push(Misspelling {
+                    field: property.name.clone(),
+                    standard_field,
+                    distance,
+                }),
+                None => local_fields.push(property.name.clone()),
+            }
+        }
+
+        let present = standard.len() - missing_fields.len();
+        Ok(ComplianceReport {
+            resource: resource.to_string(),
+            entity_type: entity_type.qualified_name(),
+            dictionary_version: self.dictionary.version.clone(),
+            standard_field_count: standard.len(),
+            standard_fields_present: present,
+            coverage: percentage(present, standard.len()),
+            missing_fields,
+            type_mismatches,
+            possible_misspellings,
+            local_fields,
+            sample_size: sample.len(),
+            population: population(
+                entity_type.properties.iter().map(|p| p.name.as_str()),
+                &standard_names,
+                sample,
+            ),
+        })
+    }
+
+    /// Closest missing standard field within the allowed distance
+    fn closest(&self, name: &str, candidates: &[String]) -> Option<(String, usize)> {
+        let lower = name.to_lowercase();
+        candidates
+            .iter()
+            .map(|candidate| (candidate, edit_distance(&lower, &candidate.to_lowercase())))
+            .filter(|(_, distance)| {
+                *distance <= self.max_distance && name.chars().count() >= 4 * distance.max(&1)
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(candidate, distance)| (candidate.clone(), distance))
+    }
+}
+
+fn percentage(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+fn expected_type(field: &StandardField) -> String {
+    match (&field.lookup, field.multi) {
+        (Some(lookup), true) => format!("Collection({}) [lookup {}]", field.type_name, lookup),
+        (Some(lookup), false) => format!("{} [lookup {}]", field.type_name, lookup),
+        (None, _) => field.type_name.clone(),
+    }
+}
+
+fn type_family(type_name: &str) -> &str {
+    match type_name {
+        "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64" => "integer",
+        "Edm.Decimal" | "Edm.Double" | "Edm.Single" => "decimal",
+        other => other,
+    }
+}
+
+fn compatible(field: &StandardField, property: &Property, metadata: &Metadata) -> bool {
+    let element = property.element_type();
+    let enum_type = metadata.enum_type(element);
+
+    if field.lookup.is_some() {
+        let textual = element == "Edm.String" || enum_type.is_some();
+        let multi = property.is_collection() || enum_type.is_some_and(|e| e.is_flags);
+        return textual && multi == field.multi;
+    }
+    !property.is_collection() && type_family(element) == type_family(&field.type_name)
+}
+
+/// Levenshtein distance between two names, counted in characters
+///
+/// Compares characters exactly; `closest` lowercases both names first, so
+/// misspelling detection ignores case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether a field value counts as populated
+///
+/// Null, blank strings and empty collections or objects do not count. Used
+/// for the population rates of a [`ComplianceReport`].
+pub fn is_populated(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::String(s) => !s.trim().is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(object) => !object.is_empty(),
+        _ => true,
+    }
+}
+
+/// Population of declared fields plus any extra fields seen in the sample
+fn population<'a>(
+    declared: impl Iterator<Item = &'a str>,
+    standard: &BTreeSet<&str>,
+    sample: &[JsonValue],
+) -> Vec<FieldPopulation> {
+    let mut counts: BTreeMap<String, usize> = declared.map(|name| (name.to_string(), 0)).collect();
+    for record in sample {
+        if let JsonValue::Object(object) = record {
+            for (field, value) in object {
+                if field.contains('@') {
+                    continue;
+                }
+                let count = counts.entry(field.clone()).or_default();
+                if is_populated(value) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let mut population: Vec<FieldPopulation> = counts
+        .into_iter()
+        .map(|(field, populated)| FieldPopulation {
+            standard: standard.contains(field.as_str()),
+            population_rate: percentage(populated, sample.len()),
+            field,
+            populated,
+        })
+        .collect();
+    population.sort_by(|a, b| b.populated.cmp(&a.populated).then(a.field.cmp(&b.field)));
+    population
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String"/>
+        <Property Name="ListPrice" Type="Edm.Double"/>
+        <Property Name="BedroomsTotal" Type="Edm.String"/>
+        <Property Name="StandardStatus" Type="org.reso.metadata.enums.StandardStatus"/>
+        <Property Name="Appliances" Type="Collection(Edm.String)"/>
+        <Property Name="Cooling" Type="Edm.String"/>
+        <Property Name="ModificationTimestamp" Type="Edm.DateTimeOffset"/>
+        <Property Name="Listprice2" Type="Edm.Decimal"/>
+        <Property Name="YearBuit" Type="Edm.Int32"/>
+        <Property Name="ACT_Tag" Type="Edm.String"/>
+      </EntityType>
+    </Schema>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata.enums">
+      <EnumType Name="StandardStatus"><Member Name="Active"/></EnumType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+    fn dictionary() -> DataDictionary {
+        DataDictionary::from_json(
+            r#"{
+                "version": "2.0",
+                "resources": {
+                    "Property": [
+                        {"name": "ListingKey", "type": "Edm.String"},
+                        {"name": "ListPrice", "type": "Edm.Decimal"},
+                        {"name": "BedroomsTotal", "type": "Edm.Int64"},
+                        {"name": "YearBuilt", "type": "Edm.Int64"},
+                        {"name": "StandardStatus", "type": "Edm.String", "lookup": "StandardStatus"},
+                        {"name": "Appliances", "type": "Edm.String", "lookup": "Appliances", "multi": true},
+                        {"name": "Cooling", "type": "Edm.String", "lookup": "Cooling", "multi": true},
+                        {"name": "ModificationTimestamp", "type": "Edm.DateTimeOffset"},
+                        {"name": "CloseDate", "type": "Edm.Date"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundled_dictionary() {
+        let dictionary = DataDictionary::bundled();
+        let property = dictionary.fields("Property").unwrap();
+
+        assert_eq!(dictionary.version, "2.0");
+        assert!(property.iter().any(|f| f.name == "ListingKey"));
+        let appliances = property.iter().find(|f| f.name == "Appliances").unwrap();
+        assert_eq!(appliances.lookup.as_deref(), Some("Appliances"));
+        assert!(appliances.multi);
+        for resource in ["Member", "Office", "Media", "OpenHouse", "Lookup"] {
+            assert!(dictionary.fields(resource).is_some(), "{}", resource);
+        }
+    }
+
+    #[test]
+    fn test_analyze_metadata() {
+        let metadata = Metadata::parse(METADATA).unwrap();
+        let report = ComplianceAnalyzer::with_dictionary(dictionary())
+            .analyze(&metadata, "Property", &[])
+            .unwrap();
+
+        assert_eq!(report.entity_type, "org.reso.metadata.Property");
+        assert_eq!(report.standard_field_count, 9);
+        assert_eq!(report.standard_fields_present, 7);
+        assert_eq!(report.missing_fields, vec!["YearBuilt", "CloseDate"]);
+
+        let mismatched: Vec<&str> = report
+            .type_mismatches
+            .iter()
+            .map(|m| m.field.as_str())
+            .collect();
+        assert_eq!(mismatched, vec!["BedroomsTotal", "Cooling"]);
+        assert_eq!(report.type_mismatches[0].actual, "Edm.String");
+
+        assert_eq!(
+            report.possible_misspellings,
+            vec![Misspelling {
+                field: "YearBuit".to_string(),
+                standard_field: "YearBuilt".to_string(),
+                distance: 1,
+            }]
+        );
+        assert_eq!(report.local_fields, vec!["Listprice2", "ACT_Tag"]);
+    }
+
+    #[test]
+    fn test_population_statistics() {
+        let metadata = Metadata::parse(METADATA).unwrap();
+        let sample = vec![
+            json!({"ListingKey": "1", "ListPrice": 100, "ACT_Tag": "", "Extra": [1]}),
+            json!({"ListingKey": "2", "ListPrice": null, "@odata.etag": "W/1"}),
+        ];
+        let report = ComplianceAnalyzer::with_dictionary(dictionary())
+            .analyze(&metadata, "Property", &sample)
+            .unwrap();
+
+        let rate = |field: &str| {
+            report
+                .population
+                .iter()
+                .find(|p| p.field == field)
+                .map(|p| (p.population_rate, p.standard))
+        };
+        assert_eq!(report.sample_size, 2);
+        assert_eq!(report.population[0].field, "ListingKey");
+        assert_eq!(rate("ListingKey"), Some((100.0, true)));
+        assert_eq!(rate("ListPrice"), Some((50.0, true)));
+        assert_eq!(rate("ACT_Tag"), Some((0.0, false)));
+        assert_eq!(rate("Extra"), Some((50.0, false)));
+        assert_eq!(rate("@odata.etag"), None);
+
+        let json = report.to_json();
+        assert_eq!(json["resource"], "Property");
+        assert_eq!(json["population"][0]["populationRate"], 100.0);
+    }
+
+    #[test]
+    fn test_unknown_resource() {
+        let metadata = Metadata::parse(METADATA).unwrap();
+        let analyzer = ComplianceAnalyzer::new();
+
+        assert!(analyzer.analyze(&metadata, "Teams", &[]).is_err());
+        assert!(analyzer.analyze(&metadata, "Member", &[]).is_err());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("yearbuit", "yearbuilt"), 1);
+        assert_eq!(edit_distance("listprice", "listprice"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -8,7 +8,9 @@
 //! # Examples
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, QueryBuilder, ResoError};
+//! # #[cfg(feature = "client")]
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let client = ResoClient::from_env()?;
 //! let query = QueryBuilder::new("Property")
@@ -83,7 +85,9 @@ pub struct ODataErrorDetail {
 /// # Examples
 ///
 /// ```no_run
+/// # #[cfg(feature = "client")]
 /// # use reso_client::{ResoClient, ResoError};
+/// # #[cfg(feature = "client")]
 /// # async fn example() {
 /// let result = ResoClient::from_env();
 /// match result {
@@ -139,6 +143,26 @@ pub enum ResoError {
     /// Invalid query
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
+
+    /// Record sink failed to write, flush or commit
+    #[error("Sink error: {0}")]
+    Sink(String),
+
+    /// Replay mode found no recorded fixture for a request
+    #[error("No recorded fixture for {url} (expected {path})")]
+    FixtureNotFound { url: String, path: String },
+
+    /// Media payload rejected or could not be stored
+    #[error("Media error: {0}")]
+    Media(String),
+
+    /// A call ran past its timeout or deadline
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// A call was aborted through its cancellation token
+    #[error("Request cancelled")]
+    Cancelled,
 }
 
 impl ResoError {
@@ -476,6 +500,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_display_sink() {
+        let error = ResoError::Sink("disk full".to_string());
+        assert_eq!(format!("{}", error), "Sink error: disk full");
+    }
+
+    #[test]
+    fn test_error_display_media() {
+        let error = ResoError::Media("photo.jpg is larger than 10 bytes".to_string());
+        assert_eq!(
+            format!("{}", error),
+            "Media error: photo.jpg is larger than 10 bytes"
+        );
+    }
+
     #[test]
     fn test_error_debug_trait() {
         let error = ResoError::Config("test".to_string());
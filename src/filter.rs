@@ -0,0 +1,814 @@
+// src/filter.rs
+
+//! Client-side `$filter` and `$orderby` evaluation
+//!
+//! Used by the mock server to answer queries and by the certification
+//! harness to check that servers return only matching records.
+//!
+//! Supports the subset of OData 4.0 expressions RESO clients use in practice:
+//!
+//! - Comparison: `eq`, `ne`, `gt`, `ge`, `lt`, `le`, `in (...)`, `has`
+//! - Logical: `and`, `or`, `not`, parentheses
+//! - Literals: `'strings'`, numbers, `true`, `false`, `null`, dates,
+//!   date-times and enum literals (`Namespace.Type'Value'`)
+//! - String functions: `contains`, `startswith`, `endswith`, `indexof`,
+//!   `length`, `tolower`, `toupper`, `trim`, `concat`, `substring`
+//! - Date functions: `year`, `month`, `day`, `hour`, `minute`, `second`
+//! - Lambdas over collections: `Appliances/any(a: a eq 'Dishwasher')`, `all`
+//!
+//! Date-time strings in records are compared as instants against date and
+//! date-time literals, so `ModificationTimestamp gt 2025-01-01T00:00:00Z`
+//! works regardless of offsets and fractional seconds.
+
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+/// A parsed `$filter` expression
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Literal(Val),
+    Field(Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(CmpOp, Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Vec<Expr>),
+    Has(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    Lambda {
+        path: Vec<String>,
+        all: bool,
+        var: String,
+        body: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CmpOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// A value during evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Val {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    /// Date or date-time literal, as microseconds since the Unix epoch
+    Time(i128),
+    List(Vec<Val>),
+}
+
+impl Val {
+    fn from_json(value: &JsonValue) -> Self {
+        match value {
+            JsonValue::Null => Val::Null,
+            JsonValue::Bool(b) => Val::Bool(*b),
+            JsonValue::Number(n) => Val::Num(n.as_f64().unwrap_or_default()),
+            JsonValue::String(s) => Val::Str(s.clone()),
+            JsonValue::Array(items) => Val::List(items.iter().map(Val::from_json).collect()),
+            JsonValue::Object(_) => Val::Str(value.to_string()),
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Val::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_time(&self) -> Option<i128> {
+        match self {
+            Val::Time(t) => Some(*t),
+            Val::Str(s) => parse_time(s),
+            _ => None,
+        }
+    }
+}
+
+/// Order two values; `None` if they are not comparable
+fn compare(a: &Val, b: &Val) -> Option<Ordering> {
+    match (a, b) {
+        (Val::Num(x), Val::Num(y)) => x.partial_cmp(y),
+        (Val::Bool(x), Val::Bool(y)) => Some(x.cmp(y)),
+        (Val::Str(x), Val::Str(y)) => Some(x.cmp(y)),
+        (Val::Time(_), _) | (_, Val::Time(_)) => Some(a.as_time()?.cmp(&b.as_time()?)),
+        _ => None,
+    }
+}
+
+fn equals(a: &Val, b: &Val) -> bool {
+    match (a, b) {
+        (Val::Null, Val::Null) => true,
+        (Val::Null, _) | (_, Val::Null) => false,
+        _ => compare(a, b) == Some(Ordering::Equal),
+    }
+}
+
+/// Order two JSON values for `$orderby`; nulls sort first
+pub(crate) fn order(a: &JsonValue, b: &JsonValue) -> Ordering {
+    let (a, b) = (Val::from_json(a), Val::from_json(b));
+    match (&a, &b) {
+        (Val::Null, Val::Null) => Ordering::Equal,
+        (Val::Null, _) => Ordering::Less,
+        (_, Val::Null) => Ordering::Greater,
+        _ => compare(&a, &b).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Look up a `/`-separated path in a record
+pub(crate) fn lookup<'a>(record: &'a JsonValue, path: &[String]) -> Option<&'a JsonValue> {
+    path.iter()
+        .try_fold(record, |value, segment| value.get(segment))
+}
+
+impl Expr {
+    /// Parse a `$filter` expression
+    pub(crate) fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected {:?} in $filter", token)),
+        }
+    }
+
+    /// Whether `record` satisfies the expression
+    pub(crate) fn matches(&self, record: &JsonValue) -> bool {
+        self.eval(record, &[]) == Val::Bool(true)
+    }
+
+    fn eval(&self, record: &JsonValue, scope: &[(&str, &JsonValue)]) -> Val {
+        match self {
+            Expr::Literal(val) => val.clone(),
+            Expr::Field(path) => resolve(record, scope, path)
+                .map(Val::from_json)
+                .unwrap_or(Val::Null),
+            Expr::Not(inner) => match inner.eval(record, scope) {
+                Val::Bool(b) => Val::Bool(!b),
+                _ => Val::Null,
+            },
+            Expr::And(a, b) => Val::Bool(
+                a.eval(record, scope) == Val::Bool(true)
+                    && b.eval(record, scope) == Val::Bool(true),
+            ),
+            Expr::Or(a, b) => Val::Bool(
+                a.eval(record, scope) == Val::Bool(true)
+                    || b.eval(record, scope) == Val::Bool(true),
+            ),
+            Expr::Compare(op, a, b) => {
+                let (a, b) = (a.eval(record, scope), b.eval(record, scope));
+                Val::Bool(match op {
+                    CmpOp::Eq => equals(&a, &b),
+                    CmpOp::Ne => !equals(&a, &b),
+                    CmpOp::Gt => compare(&a, &b) == Some(Ordering::Greater),
+                    CmpOp::Ge => {
+                        matches!(compare(&a, &b), Some(Ordering::Greater | Ordering::Equal))
+                    }
+                    CmpOp::Lt => compare(&a, &b) == Some(Ordering::Less),
+                    CmpOp::Le => {
+                        matches!(compare(&a, &b), Some(Ordering::Less | Ordering::Equal))
+                    }
+                })
+            }
+            Expr::In(value, list) => {
+                let value = value.eval(record, scope);
+                Val::Bool(list.iter().any(|e| equals(&value, &e.eval(record, scope))))
+            }
+            Expr::Has(value, flag) => {
+                let flag = flag.eval(record, scope);
+                Val::Bool(match value.eval(record, scope) {
+                    Val::List(items) => items.iter().any(|item| equals(item, &flag)),
+                    Val::Str(s) => flag
+                        .as_str()
+                        .is_some_and(|f| s.split(',').any(|part| part.trim() == f)),
+                    _ => false,
+                })
+            }
+            Expr::Call(name, args) => {
+                let args: Vec<Val> = args.iter().map(|a| a.eval(record, scope)).collect();
+                call(name, &args)
+            }
+            Expr::Lambda {
+                path,
+                all,
+                var,
+                body,
+            } => {
+                let items = match resolve(record, scope, path) {
+                    Some(JsonValue::Array(items)) => items.as_slice(),
+                    _ => &[],
+                };
+                let test = |item: &JsonValue| {
+                    let mut inner = scope.to_vec();
+                    inner.push((var.as_str(), item));
+                    body.eval(record, &inner) == Val::Bool(true)
+                };
+                Val::Bool(if *all {
+                    items.iter().all(test)
+                } else if var.is_empty() {
+                    !items.is_empty()
+                } else {
+                    items.iter().any(test)
+                })
+            }
+        }
+    }
+}
+
+/// Resolve a path against lambda variables first, then the record
+fn resolve<'a>(
+    record: &'a JsonValue,
+    scope: &[(&str, &'a JsonValue)],
+    path: &[String],
+) -> Option<&'a JsonValue> {
+    if let Some((_, value)) = path
+        .first()
+        .and_then(|first| scope.iter().rev().find(|(name, _)| name == first))
+    {
+        return lookup(value, &path[1..]);
+    }
+    lookup(record, path)
+}
+
+fn call(name: &str, args: &[Val]) -> Val {
+    let text = |i: usize| args.get(i).and_then(Val::as_str);
+    let number = |i: usize| match args.get(i) {
+        Some(Val::Num(n)) => Some(*n as usize),
+        _ => None,
+    };
+    let part = |index: usize| {
+        args.first()
+            .and_then(|v| match v {
+                Val::Str(s) => time_parts(s),
+                _ => None,
+            })
+            .map(|parts| Val::Num(parts[index] as f64))
+            .unwrap_or(Val::Null)
+    };
+
+    match name {
+        "contains" => match (text(0), text(1)) {
+            (Some(s), Some(sub)) => Val::Bool(s.contains(sub)),
+            _ => Val::Bool(false),
+        },
+        "startswith" => match (text(0), text(1)) {
+            (Some(s), Some(prefix)) => Val::Bool(s.starts_with(prefix)),
+            _ => Val::Bool(false),
+        },
+        "endswith" => match (text(0), text(1)) {
+            (Some(s), Some(suffix)) => Val::Bool(s.ends_with(suffix)),
+            _ => Val::Bool(false),
+        },
+        "indexof" => match (text(0), text(1)) {
+            (Some(s), Some(sub)) => Val::Num(
+                s.find(sub)
+                    .map(|i| s[..i].chars().count() as f64)
+                    .unwrap_or(-1.0),
+            ),
+            _ => Val::Null,
+        },
+        "length" => match args.first() {
+            Some(Val::Str(s)) => Val::Num(s.chars().count() as f64),
+            Some(Val::List(items)) => Val::Num(items.len() as f64),
+            _ => Val::Null,
+        },
+        "tolower" => text(0).map_or(Val::Null, |s| Val::Str(s.to_lowercase())),
+        "toupper" => text(0).map_or(Val::Null, |s| Val::Str(s.to_uppercase())),
+        "trim" => text(0).map_or(Val::Null, |s| Val::Str(s.trim().to_string())),
+        "concat" => match (text(0), text(1)) {
+            (Some(a), Some(b)) => Val::Str(format!("{}{}", a, b)),
+            _ => Val::Null,
+        },
+        "substring" => match (text(0), number(1)) {
+            (Some(s), Some(start)) => {
+                let chars = s.chars().skip(start);
+                Val::Str(match number(2) {
+                    Some(len) => chars.take(len).collect(),
+                    None => chars.collect(),
+                })
+            }
+            _ => Val::Null,
+        },
+        "year" => part(0),
+        "month" => part(1),
+        "day" => part(2),
+        "hour" => part(3),
+        "minute" => part(4),
+        "second" => part(5),
+        _ => Val::Null,
+    }
+}
+
+const FUNCTIONS: &[&str] = &[
+    "contains",
+    "startswith",
+    "endswith",
+    "indexof",
+    "length",
+    "tolower",
+    "toupper",
+    "trim",
+    "concat",
+    "substring",
+    "year",
+    "month",
+    "day",
+    "hour",
+    "minute",
+    "second",
+];
+
+/// Parse a `$orderby` list into field paths and descending flags
+#[cfg_attr(not(feature = "mock-server"), allow(dead_code))]
+pub(crate) fn parse_orderby(input: &str) -> Result<Vec<(Vec<String>, bool)>, String> {
+    input
+        .split(',')
+        .map(|item| {
+            let mut words = item.split_whitespace();
+            let field = words
+                .next()
+                .ok_or_else(|| "Empty $orderby item".to_string())?;
+            let descending = match words.next() {
+                None => false,
+                Some(dir) if dir.eq_ignore_ascii_case("asc") => false,
+                Some(dir) if dir.eq_ignore_ascii_case("desc") => true,
+                Some(other) => return Err(format!("Invalid $orderby direction: {}", other)),
+            };
+            Ok((field.split('/').map(String::from).collect(), descending))
+        })
+        .collect()
+}
+
+// --- Tokenizer ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Slash,
+    Str(String),
+    /// `Namespace.Type'Value'`
+    Enum(String),
+    /// Number, date or date-time
+    Scalar(String),
+    Ident(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let quoted = |i: &mut usize| -> Result<String, String> {
+        // Opening quote at chars[*i]; '' is an escaped quote
+        let mut value = String::new();
+        *i += 1;
+        loop {
+            match chars.get(*i) {
+                None => return Err("Unterminated string literal in $filter".to_string()),
+                Some('\'') if chars.get(*i + 1) == Some(&'\'') => {
+                    value.push('\'');
+                    *i += 2;
+                }
+                Some('\'') => {
+                    *i += 1;
+                    return Ok(value);
+                }
+                Some(c) => {
+                    value.push(*c);
+                    *i += 1;
+                }
+            }
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                i += 1;
+            }
+            '\'' => tokens.push(Token::Str(quoted(&mut i)?)),
+            c if c.is_ascii_digit()
+                || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) =>
+            {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric()
+                        || matches!(chars[i], ':' | '.' | '+' | '-'))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Scalar(chars[start..i].iter().collect()));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '_' | '.'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&'\'') {
+                    // Enum literal: the type name is not needed to compare values
+                    tokens.push(Token::Enum(quoted(&mut i)?));
+                } else {
+                    tokens.push(Token::Ident(word));
+                }
+            }
+            other => return Err(format!("Unexpected character '{}' in $filter", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+// --- Parser ---
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(format!(
+                "Expected {:?} in $filter, found {:?}",
+                expected, other
+            )),
+        }
+    }
+
+    fn keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(w)) if w == word)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.keyword("and") {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.primary()?;
+        let op = match self.peek() {
+            Some(Token::Ident(word)) => word.clone(),
+            _ => return Ok(left),
+        };
+
+        let cmp = match op.as_str() {
+            "eq" => CmpOp::Eq,
+            "ne" => CmpOp::Ne,
+            "gt" => CmpOp::Gt,
+            "ge" => CmpOp::Ge,
+            "lt" => CmpOp::Lt,
+            "le" => CmpOp::Le,
+            "in" => {
+                self.pos += 1;
+                self.expect(Token::LParen)?;
+                let list = self.arguments()?;
+                return Ok(Expr::In(Box::new(left), list));
+            }
+            "has" => {
+                self.pos += 1;
+                return Ok(Expr::Has(Box::new(left), Box::new(self.primary()?)));
+            }
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        Ok(Expr::Compare(
+            cmp,
+            Box::new(left),
+            Box::new(self.primary()?),
+        ))
+    }
+
+    /// Comma-separated expressions up to and including `)`
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.or()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => return Ok(args),
+                other => return Err(format!("Expected , or ) in $filter, found {:?}", other)),
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Str(s)) | Some(Token::Enum(s)) => Ok(Expr::Literal(Val::Str(s))),
+            Some(Token::Scalar(s)) => scalar(&s).map(Expr::Literal),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Val::Bool(true))),
+                "false" => Ok(Expr::Literal(Val::Bool(false))),
+                "null" => Ok(Expr::Literal(Val::Null)),
+                _ if self.peek() == Some(&Token::LParen) => {
+                    let name = word.to_ascii_lowercase();
+                    if !FUNCTIONS.contains(&name.as_str()) {
+                        return Err(format!("Unsupported function in $filter: {}", word));
+                    }
+                    self.pos += 1;
+                    Ok(Expr::Call(name, self.arguments()?))
+                }
+                _ => self.path(word),
+            },
+            other => Err(format!("Unexpected {:?} in $filter", other)),
+        }
+    }
+
+    /// Member path, possibly ending in an `any`/`all` lambda
+    fn path(&mut self, first: String) -> Result<Expr, String> {
+        let mut path = vec![first];
+        while self.peek() == Some(&Token::Slash) {
+            self.pos += 1;
+            let segment = match self.next() {
+                Some(Token::Ident(segment)) => segment,
+                other => return Err(format!("Expected a property name, found {:?}", other)),
+            };
+
+            if matches!(segment.as_str(), "any" | "all") && self.peek() == Some(&Token::LParen) {
+                self.pos += 1;
+                let all = segment == "all";
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                    return Ok(Expr::Lambda {
+                        path,
+                        all,
+                        var: String::new(),
+                        body: Box::new(Expr::Literal(Val::Bool(true))),
+                    });
+                }
+                let var = match self.next() {
+                    Some(Token::Ident(var)) => var,
+                    other => return Err(format!("Expected a lambda variable, found {:?}", other)),
+                };
+                self.expect(Token::Colon)?;
+                let body = self.or()?;
+                self.expect(Token::RParen)?;
+                return Ok(Expr::Lambda {
+                    path,
+                    all,
+                    var,
+                    body: Box::new(body),
+                });
+            }
+            path.push(segment);
+        }
+        Ok(Expr::Field(path))
+    }
+}
+
+fn scalar(text: &str) -> Result<Val, String> {
+    let looks_temporal = text[1..].contains('-') || text.contains(':');
+    if !looks_temporal {
+        if let Ok(n) = text.parse::<f64>() {
+            return Ok(Val::Num(n));
+        }
+    }
+    if let Some(t) = parse_time(text) {
+        return Ok(Val::Time(t));
+    }
+    // GUIDs and other unquoted tokens compare as text
+    Ok(Val::Str(text.to_string()))
+}
+
+/// Year, month, day, hour, minute, second of an ISO 8601 date or date-time
+fn time_parts(text: &str) -> Option<[i64; 6]> {
+    let (date, time) = match text.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+    if let Some(time) = time {
+        let clock = time.split(['Z', 'z', '+', '-']).next().unwrap_or_default();
+        let mut clock_parts = clock.split(':');
+        hour = clock_parts.next()?.parse().ok()?;
+        minute = clock_parts.next()?.parse().ok()?;
+        second = clock_parts
+            .next()
+            .map(|s| s.split('.').next().unwrap_or_default().parse())
+            .transpose()
+            .ok()?
+            .unwrap_or(0);
+    }
+    Some([year, month, day, hour, minute, second])
+}
+
+/// Microseconds since the Unix epoch for an ISO 8601 date or date-time
+///
+/// Dates are midnight UTC; date-times without an offset are taken as UTC.
+pub(crate) fn parse_time(text: &str) -> Option<i128> {
+    let [year, month, day, hour, minute, second] = time_parts(text)?;
+
+    let mut micros = 0i128;
+    let mut offset_minutes = 0i64;
+    if let Some((_, time)) = text.split_once('T') {
+        if let Some(fraction) = time.split_once('.').map(|(_, f)| f) {
+            let digits: String = fraction.chars().take_while(char::is_ascii_digit).collect();
+            let padded = format!("{:0<6}", &digits[..digits.len().min(6)]);
+            micros = padded.parse().ok()?;
+        }
+        if let Some(pos) = time.rfind(['+', '-']) {
+            let (hours, minutes) = time[pos + 1..].split_once(':')?;
+            let sign = if &time[pos..pos + 1] == "-" { -1 } else { 1 };
+            offset_minutes =
+                sign * (hours.parse::<i64>().ok()? * 60 + minutes.parse::<i64>().ok()?);
+        }
+    }
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+        - offset_minutes * 60;
+    Some(seconds as i128 * 1_000_000 + micros)
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn listing() -> JsonValue {
+        json!({
+            "ListingKey": "A1",
+            "City": "Austin",
+            "ListPrice": 450000,
+            "BedroomsTotal": 3,
+            "NewConstructionYN": false,
+            "PostalCode": null,
+            "ModificationTimestamp": "2025-01-15T10:30:00.123-06:00",
+            "Appliances": ["Dishwasher", "Range"],
+            "ListOffice": {"OfficeName": "Acme Realty"}
+        })
+    }
+
+    fn matches(filter: &str) -> bool {
+        Expr::parse(filter).unwrap().matches(&listing())
+    }
+
+    #[test]
+    fn test_comparisons_and_logic() {
+        assert!(matches("City eq 'Austin'"));
+        assert!(matches("ListPrice gt 400000 and BedroomsTotal ge 3"));
+        assert!(matches("ListPrice lt 100 or City ne 'Dallas'"));
+        assert!(matches("not (City eq 'Dallas')"));
+        assert!(matches("NewConstructionYN eq false"));
+        assert!(matches("PostalCode eq null"));
+        assert!(!matches("PostalCode gt '0'"));
+        assert!(matches("City in ('Dallas', 'Austin')"));
+        assert!(matches("ListOffice/OfficeName eq 'Acme Realty'"));
+        assert!(!matches("MissingField eq 'x'"));
+    }
+
+    #[test]
+    fn test_dates_compare_as_instants() {
+        // 10:30 at -06:00 is 16:30 UTC
+        assert!(matches("ModificationTimestamp gt 2025-01-15T16:00:00Z"));
+        assert!(matches("ModificationTimestamp lt 2025-01-15T16:31:00Z"));
+        assert!(matches("ModificationTimestamp ge 2025-01-15"));
+        assert!(matches(
+            "year(ModificationTimestamp) eq 2025 and day(ModificationTimestamp) eq 15"
+        ));
+        assert_eq!(parse_time("1970-01-01T00:00:01Z"), Some(1_000_000));
+        assert_eq!(parse_time("2000-03-01"), Some(951_868_800_000_000));
+    }
+
+    #[test]
+    fn test_functions_and_enums() {
+        assert!(matches("contains(City, 'ust')"));
+        assert!(matches("startswith(tolower(City), 'aus')"));
+        assert!(matches("endswith(ListingKey, '1') and length(City) eq 6"));
+        assert!(matches("City eq Org.Reso.Metadata.Enums.City'Austin'"));
+        assert!(matches(
+            "Appliances has Org.Reso.Metadata.Enums.Appliances'Range'"
+        ));
+        assert!(matches("substring(City, 1, 2) eq 'us'"));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        assert!(matches("Appliances/any(a: a eq 'Dishwasher')"));
+        assert!(!matches("Appliances/all(a: a eq 'Dishwasher')"));
+        assert!(matches("Appliances/any()"));
+        assert!(matches("Appliances/all(a: length(a) gt 3)"));
+    }
+
+    #[test]
+    fn test_quoted_strings() {
+        let record = json!({"StreetName": "O'Brien"});
+        assert!(Expr::parse("StreetName eq 'O''Brien'")
+            .unwrap()
+            .matches(&record));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Expr::parse("City eq 'Austin").is_err());
+        assert!(Expr::parse("frobnicate(City)").is_err());
+        assert!(Expr::parse("City eq 'Austin' )").is_err());
+        assert!(Expr::parse("City # 1").is_err());
+    }
+
+    #[test]
+    fn test_orderby() {
+        assert_eq!(
+            parse_orderby("ListPrice desc, City").unwrap(),
+            vec![
+                (vec!["ListPrice".to_string()], true),
+                (vec!["City".to_string()], false)
+            ]
+        );
+        assert!(parse_orderby("ListPrice sideways").is_err());
+        assert_eq!(order(&json!(null), &json!(1)), Ordering::Less);
+        assert_eq!(order(&json!(2), &json!(10)), Ordering::Less);
+    }
+}
@@ -0,0 +1,418 @@
+// src/fixtures.rs
+
+//! Record-and-replay fixtures for offline testing
+//!
+//! In record mode every request the client makes is sent to the server as
+//! usual and the exchange is written to a JSON fixture file. In replay mode
+//! those files are served instead and no network access happens at all; a
+//! request that was never recorded fails with [`ResoError::FixtureNotFound`].
+//! Record once against a live server, commit the fixtures, and replay them in
+//! CI without credentials.
+//!
+//! # Fixture Files
+//!
+//! Each exchange is stored as `{name}-{hash}.json`, where `name` is the last
+//! path segment of the URL and `hash` identifies the request (method, full URL
+//! and request headers such as `Accept`, `Prefer` and conditional headers). The
+//! file holds the request (method, URL, headers without `Authorization`) and
+//! the response (status, headers, body). Text bodies are stored verbatim in
+//! `body` and replayed byte for byte; a body that parses as JSON is also
+//! stored as `json`, a readable copy that replay ignores. Hand-written
+//! fixtures may give only `json`:
+//!
+//! ```json
+//! {
+//!   "request": {
+//!     "method": "GET",
+//!     "url": "https://api.mls.com/odata/Property?$top=1",
+//!     "headers": { "accept": "application/json" }
+//!   },
+//!   "response": {
+//!     "status": 200,
+//!     "headers": { "content-type": "application/json" },
+//!     "body": "{\"value\":[{\"ListingKey\":\"1\"}]}",
+//!     "json": { "value": [{ "ListingKey": "1" }] }
+//!   }
+//! }
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ClientConfig, QueryBuilder, ResoClient};
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Once, with credentials: capture the exchanges
+//! let config = ClientConfig::from_env()?.record_fixtures("tests/fixtures/property");
+//! let client = ResoClient::with_config(config)?;
+//! let query = QueryBuilder::new("Property").top(5).build()?;
+//! client.execute(&query).await?;
+//!
+//! // In CI: same base URL, no network, no real token
+//! let config = ClientConfig::new("https://api.mls.com/odata", "unused")
+//!     .replay_fixtures("tests/fixtures/property");
+//! let client = ResoClient::with_config(config)?;
+//! let results = client.execute(&query).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::cache::build_response;
+use crate::error::{ResoError, Result};
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Whether the client records or replays fixtures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Send requests to the server and write each exchange to this directory
+    Record(PathBuf),
+    /// Serve exchanges from this directory without network access
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    request: FixtureRequest,
+    response: FixtureResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FixtureRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FixtureResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    /// UTF-8 body, exactly as received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// Readable copy of a body that parses as JSON
+    ///
+    /// Only served when there is no `body`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<JsonValue>,
+    /// Binary body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bytes: Option<Vec<u8>>,
+}
+
+impl FixtureResponse {
+    fn new(status: u16, headers: BTreeMap<String, String>, body: Vec<u8>) -> Self {
+        let mut response = Self {
+            status,
+            headers,
+            body: None,
+            json: None,
+            bytes: None,
+        };
+        match String::from_utf8(body) {
+            Ok(text) => {
+                response.json = serde_json::from_str(&text).ok();
+                response.body = Some(text);
+            }
+            Err(e) => response.bytes = Some(e.into_bytes()),
+        }
+        response
+    }
+
+    fn body_bytes(self) -> Vec<u8> {
+        match (self.body, self.json, self.bytes) {
+            (Some(text), _, _) => text.into_bytes(),
+            (None, Some(json), _) => json.to_string().into_bytes(),
+            (None, None, Some(bytes)) => bytes,
+            (None, None, None) => Vec::new(),
+        }
+    }
+}
+
+/// Request headers as stored in a fixture, without `Authorization`
+fn fixture_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| *name != AUTHORIZATION)
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+/// Path of the fixture for a GET of `url` with the given request headers
+fn fixture_path(dir: &Path, url: &str, headers: &BTreeMap<String, String>) -> PathBuf {
+    let mut key = format!("GET {}", url);
+    for (name, value) in headers {
+        key.push_str(&format!("\n{}: {}", name, value));
+    }
+    let hash: String = Sha256::digest(key.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name: String = path
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect();
+    let name = if name.is_empty() { "root" } else { &name };
+
+    dir.join(format!("{}-{}.json", name, hash))
+}
+
+/// Serve a recorded response for a GET of `url` sent with `request_headers`
+pub(crate) fn replay(
+    dir: &Path,
+    url: &str,
+    request_headers: &HeaderMap,
+) -> Result<reqwest::Response> {
+    let path = fixture_path(dir, url, &fixture_headers(request_headers));
+    let contents = match std::fs::read(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ResoError::FixtureNotFound {
+                url: url.to_string(),
+                path: path.display().to_string(),
+            })
+        }
+        Err(e) => {
+            return Err(ResoError::Config(format!(
+                "Failed to read fixture {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    let fixture: Fixture = serde_json::from_slice(&contents)
+        .map_err(|e| ResoError::Parse(format!("Invalid fixture {}: {}", path.display(), e)))?;
+    let status = fixture.response.status;
+    let headers: Vec<(String, String)> = fixture
+        .response
+        .headers
+        .iter()
+        .map(|(n, v)| (n.clone(), v.clone()))
+        .collect();
+
+    build_response(status, &headers, fixture.response.body_bytes())
+        .map_err(|e| ResoError::Parse(format!("Invalid fixture {}: {}", path.display(), e)))
+}
+
+/// Write the exchange for a GET of `url` and hand back an equivalent response
+///
+/// An `Authorization` header in `request_headers` is left out of the fixture.
+pub(crate) async fn record(
+    dir: &Path,
+    url: &str,
+    request_headers: &HeaderMap,
+    response: reqwest::Response,
+) -> Result<reqwest::Response> {
+    let request_headers = fixture_headers(request_headers);
+    let status = response.status().as_u16();
+    // Framing headers describe the wire body, not the stored one
+    let headers: BTreeMap<String, String> = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| {
+            !matches!(
+                name.as_str(),
+                "content-length" | "transfer-encoding" | "connection"
+            )
+        })
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect();
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| ResoError::Network(e.to_string()))?
+        .to_vec();
+
+    let fixture = Fixture {
+        request: FixtureRequest {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: request_headers,
+        },
+        response: FixtureResponse::new(status, headers.clone(), body.clone()),
+    };
+
+    let path = fixture_path(dir, url, &fixture.request.headers);
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_vec_pretty(&fixture)?;
+        std::fs::write(&path, json)
+    };
+    write().map_err(|e| {
+        ResoError::Config(format!("Failed to write fixture {}: {}", path.display(), e))
+    })?;
+    tracing::debug!("Recorded fixture {} for {}", path.display(), url);
+
+    let headers: Vec<(String, String)> = headers.into_iter().collect();
+    build_response(status, &headers, body)
+        .map_err(|e| ResoError::Parse(format!("Invalid response from {}: {}", url, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fixture_path_naming() {
+        let dir = Path::new("fixtures");
+        let path = fixture_path(
+            dir,
+            "https://api.example.com/odata/Property?$top=5",
+            &BTreeMap::new(),
+        );
+        let name = path.file_name().unwrap().to_str().unwrap();
+
+        assert!(name.starts_with("Property-"));
+        assert!(name.ends_with(".json"));
+        assert_eq!(
+            fixture_path(
+                dir,
+                "https://api.example.com/odata/$metadata",
+                &BTreeMap::new()
+            )
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .split('-')
+            .next(),
+            Some("metadata")
+        );
+    }
+
+    #[test]
+    fn test_fixture_path_distinguishes_requests() {
+        let dir = Path::new("fixtures");
+        let url = "https://api.example.com/odata/Property";
+
+        let plain = headers(&[("accept", "application/json")]);
+
+        assert_ne!(
+            fixture_path(dir, url, &plain),
+            fixture_path(dir, &format!("{}?$top=1", url), &plain)
+        );
+        assert_ne!(
+            fixture_path(dir, url, &plain),
+            fixture_path(
+                dir,
+                url,
+                &headers(&[("accept", "application/json"), ("if-none-match", "\"v1\"")])
+            )
+        );
+        assert_ne!(
+            fixture_path(dir, url, &plain),
+            fixture_path(
+                dir,
+                url,
+                &headers(&[("accept", "application/json;odata.metadata=full")])
+            )
+        );
+        assert_ne!(
+            fixture_path(dir, url, &plain),
+            fixture_path(
+                dir,
+                url,
+                &headers(&[
+                    ("accept", "application/json"),
+                    ("prefer", "odata.maxpagesize=10")
+                ])
+            )
+        );
+    }
+
+    #[test]
+    fn test_fixture_headers_leave_out_authorization() {
+        let mut map = HeaderMap::new();
+        map.insert("Accept", "application/json".parse().unwrap());
+        map.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+
+        assert_eq!(
+            fixture_headers(&map),
+            headers(&[("accept", "application/json")])
+        );
+    }
+
+    #[test]
+    fn test_response_body_encoding() {
+        let json = FixtureResponse::new(200, BTreeMap::new(), br#"{"value":[]}"#.to_vec());
+        assert_eq!(json.json, Some(serde_json::json!({"value": []})));
+        assert_eq!(json.body_bytes(), br#"{"value":[]}"#.to_vec());
+
+        // Formatting and number precision survive the round trip
+        let raw = br#"{ "ListPrice": 100000.00, "Big": 12345678901234567890123 }"#;
+        let exact = FixtureResponse::new(200, BTreeMap::new(), raw.to_vec());
+        assert!(exact.json.is_some());
+        assert_eq!(exact.body_bytes(), raw.to_vec());
+
+        let edited = FixtureResponse {
+            status: 200,
+            headers: BTreeMap::new(),
+            body: None,
+            json: Some(serde_json::json!({"value": []})),
+            bytes: None,
+        };
+        assert_eq!(edited.body_bytes(), br#"{"value":[]}"#.to_vec());
+
+        let text = FixtureResponse::new(200, BTreeMap::new(), b"42".to_vec());
+        // A bare number is valid JSON and round-trips unchanged
+        assert_eq!(text.body_bytes(), b"42".to_vec());
+
+        let xml = FixtureResponse::new(200, BTreeMap::new(), b"<edmx/>".to_vec());
+        assert_eq!(xml.body.as_deref(), Some("<edmx/>"));
+
+        let binary = FixtureResponse::new(200, BTreeMap::new(), vec![0xff, 0xd8]);
+        assert_eq!(binary.bytes, Some(vec![0xff, 0xd8]));
+        assert_eq!(binary.body_bytes(), vec![0xff, 0xd8]);
+
+        let empty = FixtureResponse::new(304, BTreeMap::new(), Vec::new());
+        assert_eq!(empty.body.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_replay_missing_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = replay(
+            dir.path(),
+            "https://api.example.com/odata/Member",
+            &HeaderMap::new(),
+        );
+
+        match result {
+            Err(ResoError::FixtureNotFound { url, path }) => {
+                assert_eq!(url, "https://api.example.com/odata/Member");
+                assert!(path.contains("Member-"));
+            }
+            other => panic!("expected FixtureNotFound, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -9,13 +9,32 @@
 //! # Features
 //!
 //! - 🔍 **Fluent Query Builder** - Build complex OData queries with a clean, fluent API
-//! - 🔐 **OAuth Authentication** - Built-in support for bearer token authentication
+//! - 🔐 **OAuth Authentication** - Bearer tokens or OAuth client credentials with automatic renewal
+//! - 🗃️ **Feed Profiles** - Named feeds in a TOML file with secret references and env overrides
+//! - 🌐 **Feed Registry** - Fan one query out to many feeds, with records tagged by source
+//! - 🔀 **Field Mapping** - Normalize feed-specific fields and values into the Data Dictionary shape
+//! - 🧩 **Middleware** - Pluggable request chain for signing, headers, logging, metrics and fault injection
+//! - 🔁 **Retries & Rate Limits** - Exponential backoff for transient failures and client-side request pacing
+//! - ⏱️ **Per-Call Options** - Timeouts, deadlines, cancellation, `Prefer` preferences and custom headers for a single request
 //! - 📊 **Full OData Support** - Filter, sort, paginate, select fields, expand relations
 //! - 🔢 **Count Queries** - Efficient record counting via `/$count` endpoint
 //! - 🗂️ **Dataset ID Support** - Handle RESO servers that use dataset identifiers
 //! - 📖 **Metadata Retrieval** - Fetch and parse OData `$metadata` documents
+//! - 🏅 **Certification Harness** - Run the Web API Core scenarios against any server
+//! - ✅ **Compliance Reports** - Compare `$metadata` and sample records against the RESO Data Dictionary
+//! - 🏷️ **Lookup Resolution** - Resolve enumerated values and build filter literals from the `Lookup` resource
 //! - 🔄 **Replication Endpoint** - Bulk data transfer with up to 2000 records/request
+//! - 🌊 **Streaming Records** - Records parsed one at a time as large pages arrive, optionally into your own types
+//! - 📈 **Progress Reporting** - Record counts, throughput and ETA for replication runs
+//! - 📥 **Record Sinks** - Checkpointed, at-least-once delivery of replicated records
+//! - 📁 **File Exports** - Rotating NDJSON, JSON and CSV files with checksummed manifests
+//! - 🏹 **Arrow & Parquet** - Record batches and Parquet files typed from `$metadata` (`arrow` feature)
+//! - 🖼️ **Media Downloads** - Concurrent, size-limited photo downloads with change detection
+//! - 💾 **Response Cache** - In-memory or on-disk caching with `ETag` revalidation
+//! - 🧪 **Mock Server** - In-process RESO server seeded with fixtures for offline tests (`mock-server` feature)
 //! - ⚡ **Async/Await** - Built on tokio for high-performance concurrent operations
+//! - 🔌 **Pluggable Transport** - Runtime-agnostic core without reqwest or tokio (`default-features = false`), with a `Transport` trait for custom or WASM HTTP backends
+//! - ⏸️ **Blocking Client** - Synchronous API and record iterators for scripts and batch jobs (`blocking` feature)
 //! - 🛡️ **Type-Safe Errors** - Comprehensive error types with detailed context
 //!
 //! ## Stability
@@ -26,8 +45,10 @@
 //! # Quick Start
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! use reso_client::{ResoClient, QueryBuilder};
 //!
+//! # #[cfg(feature = "client")]
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     // Create client from environment variables
@@ -56,6 +77,8 @@
 //!
 //!     Ok(())
 //! }
+//! # #[cfg(not(feature = "client"))]
+//! # fn main() {}
 //! ```
 //!
 //! # Configuration
@@ -74,8 +97,11 @@
 //! ## Manual Configuration
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, ClientConfig};
 //! # use std::time::Duration;
+//! # #[cfg(feature = "client")]
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let config = ClientConfig::new(
 //!     "https://api.mls.com/odata",
 //!     "your-bearer-token"
@@ -84,7 +110,8 @@
 //! .with_timeout(Duration::from_secs(60));
 //!
 //! let client = ResoClient::with_config(config)?;
-//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! # Common Usage Patterns
@@ -92,7 +119,9 @@
 //! ## Filtering with OData Expressions
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, QueryBuilder};
+//! # #[cfg(feature = "client")]
 //! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
 //! // Simple equality
 //! let query = QueryBuilder::new("Property")
@@ -120,7 +149,9 @@
 //! ## Pagination
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, QueryBuilder};
+//! # #[cfg(feature = "client")]
 //! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
 //! // First page
 //! let query = QueryBuilder::new("Property")
@@ -141,7 +172,9 @@
 //! ## Getting Total Counts
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, QueryBuilder};
+//! # #[cfg(feature = "client")]
 //! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
 //! // Include count in response (with records)
 //! let query = QueryBuilder::new("Property")
@@ -170,7 +203,9 @@
 //! ## Bulk Data with Replication
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, ReplicationQueryBuilder};
+//! # #[cfg(feature = "client")]
 //! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
 //! let query = ReplicationQueryBuilder::new("Property")
 //!     .filter("StandardStatus eq 'Active'")
@@ -195,7 +230,9 @@
 //! ## Error Handling
 //!
 //! ```no_run
+//! # #[cfg(feature = "client")]
 //! # use reso_client::{ResoClient, QueryBuilder, ResoError};
+//! # #[cfg(feature = "client")]
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let client = ResoClient::from_env()?;
 //! let query = QueryBuilder::new("Property").top(10).build()?;
@@ -236,16 +273,109 @@
 //! - [OData 4.0 Protocol](https://www.odata.org/documentation/)
 //! - [RESO Data Dictionary](https://www.reso.org/data-dictionary/)
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "client")]
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "client")]
+pub mod cache;
+#[cfg(feature = "client")]
+pub mod certification;
+#[cfg(feature = "client")]
 pub mod client;
+pub mod compliance;
 pub mod error;
+#[cfg(feature = "client")]
+mod filter;
+#[cfg(feature = "client")]
+pub mod fixtures;
+#[cfg(feature = "client")]
+pub mod http_client;
+pub mod lookup;
+pub mod mapping;
+#[cfg(feature = "client")]
+pub mod media;
+pub mod metadata;
+#[cfg(feature = "client")]
+pub mod middleware;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+#[cfg(feature = "client")]
+pub mod options;
+#[cfg(feature = "client")]
+pub mod profile;
+pub mod progress;
 pub mod queries;
+#[cfg(feature = "client")]
+pub mod rate_limit;
+#[cfg(feature = "client")]
+pub mod registry;
 pub mod replication;
+#[cfg(feature = "client")]
+pub mod retry;
+pub mod sink;
+pub mod streaming;
+pub mod transport;
 
 // Re-export main types for convenience
+#[cfg(feature = "arrow")]
+pub use arrow::RecordBatchConverter;
+#[cfg(feature = "client")]
+pub use auth::ClientCredentials;
+#[cfg(feature = "client")]
+pub use cache::{CacheStore, CachedResponse, DiskCache, HttpCache, MemoryCache};
+#[cfg(feature = "client")]
+pub use certification::{CertificationReport, CertificationRunner, ScenarioResult, ScenarioStatus};
+#[cfg(feature = "client")]
 pub use client::{ClientConfig, ResoClient};
+pub use compliance::{ComplianceAnalyzer, ComplianceReport, DataDictionary};
 pub use error::{ResoError, Result};
+#[cfg(feature = "client")]
+pub use fixtures::FixtureMode;
+#[cfg(feature = "client")]
+pub use http_client::HttpClientConfig;
+pub use lookup::{LookupCache, LookupValue};
+pub use mapping::{FieldMapping, MappingReport, NormalizingSink, RecordNormalizer};
+#[cfg(feature = "client")]
+pub use media::{DownloadedMedia, MediaDownload, MediaDownloader, MediaItem, MediaStatus};
+pub use metadata::{EntityType, EnumType, Metadata};
+#[cfg(feature = "client")]
+pub use middleware::{Middleware, Next};
+#[cfg(feature = "mock-server")]
+pub use mock_server::{MockResoServer, MockResoServerBuilder};
+#[cfg(feature = "client")]
+pub use options::{CancellationToken, MetadataLevel, ODataResponse, RequestOptions};
+#[cfg(feature = "client")]
+pub use profile::{FeedProfile, Profiles};
+pub use progress::{ProgressObserver, ReplicationProgress, TracingProgressReporter};
 pub use queries::{Query, QueryBuilder, ReplicationQuery, ReplicationQueryBuilder};
+#[cfg(feature = "client")]
+pub use rate_limit::RateLimiter;
+#[cfg(feature = "client")]
+pub use registry::{FanOutResult, FeedRecord, FeedRegistry};
 pub use replication::ReplicationResponse;
+#[cfg(feature = "client")]
+pub use replication::ReplicationStream;
+#[cfg(feature = "client")]
+pub use retry::RetryPolicy;
+pub use sink::csv::CsvFileSink;
+pub use sink::files::{Compression, ExportManifest, ManifestPart};
+pub use sink::json::{JsonFileSink, JsonFormat};
+#[cfg(feature = "arrow")]
+pub use sink::parquet::ParquetSink;
+#[cfg(feature = "postgres")]
+pub use sink::postgres::PostgresSink;
+#[cfg(feature = "sqlite")]
+pub use sink::sqlite::SqliteSink;
+pub use sink::{MemorySink, RecordSink, ReplicationCheckpoint};
+#[cfg(feature = "client")]
+pub use streaming::RecordStream;
+pub use transport::{ServiceRoot, Transport};
 
 // Re-export serde_json for convenience
 pub use serde_json::Value as JsonValue;
+
+// Re-export async_trait so sinks can be implemented without an extra dependency
+pub use async_trait::async_trait;
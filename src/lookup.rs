@@ -0,0 +1,514 @@
+// src/lookup.rs
+
+//! Lookup resource support for enumerated fields
+//!
+//! RESO Data Dictionary 2.0 servers publish their enumerations through the
+//! `Lookup` resource. Each record maps a `LookupName` and `LookupValue` to the
+//! `StandardLookupValue` and the `LegacyODataValue` (the enum member name used
+//! by servers that still declare fields as OData enum types).
+//!
+//! [`LookupCache`] holds those records and:
+//!
+//! - resolves field values in records to their human-readable standard value
+//!   ([`resolve`](LookupCache::resolve),
+//!   [`resolve_record`](LookupCache::resolve_record))
+//! - turns human-readable values back into the filter literal the server
+//!   expects ([`filter_literal`](LookupCache::filter_literal),
+//!   [`filter_eq`](LookupCache::filter_eq)): a quoted string for string
+//!   lookups, `Namespace.Type'Member'` for enum-typed fields
+//!
+//! Fields are matched to lookups through [`with_metadata`](LookupCache::with_metadata):
+//! the `RESO.OData.Metadata.LookupName` annotation on string fields, or the
+//! enum type of enum-typed fields. Without metadata, a field uses the lookup
+//! with the same name (`StandardStatus` → `StandardStatus`). Lookup names are
+//! compared by their last segment, so `org.reso.metadata.enums.StandardStatus`
+//! and `StandardStatus` are the same lookup. Field names are matched across
+//! all resources, following the Data Dictionary's use of one name per field.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{QueryBuilder, ResoClient};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let lookups = client.lookups().await?;
+//!
+//! let query = QueryBuilder::new("Property")
+//!     .filter(lookups.filter_eq("StandardStatus", "Active Under Contract")?)
+//!     .build()?;
+//!
+//! let mut results = client.execute(&query).await?;
+//! for record in results["value"].as_array_mut().into_iter().flatten() {
+//!     lookups.resolve_record(record);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::metadata::Metadata;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// Name of the RESO lookup resource
+pub const LOOKUP_RESOURCE: &str = "Lookup";
+
+/// A record of the `Lookup` resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupValue {
+    /// `LookupKey`
+    pub lookup_key: Option<String>,
+
+    /// `LookupName`, e.g. `org.reso.metadata.enums.StandardStatus`
+    pub lookup_name: String,
+
+    /// `LookupValue`, the human-friendly value used by string lookups
+    pub lookup_value: String,
+
+    /// `StandardLookupValue`, when the value maps to a Data Dictionary value
+    pub standard_lookup_value: Option<String>,
+
+    /// `LegacyODataValue`, the enum member name on enum-typed servers
+    pub legacy_odata_value: Option<String>,
+}
+
+impl LookupValue {
+    /// Read a `Lookup` record; `None` without `LookupName` and `LookupValue`
+    pub fn from_record(record: &JsonValue) -> Option<Self> {
+        let text = |field: &str| match record.get(field) {
+            Some(JsonValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        };
+        Some(Self {
+            lookup_key: text("LookupKey"),
+            lookup_name: text("LookupName")?,
+            lookup_value: text("LookupValue")?,
+            standard_lookup_value: text("StandardLookupValue"),
+            legacy_odata_value: text("LegacyODataValue"),
+        })
+    }
+
+    /// Human-readable value: the standard value when there is one
+    pub fn display_value(&self) -> &str {
+        self.standard_lookup_value
+            .as_deref()
+            .unwrap_or(&self.lookup_value)
+    }
+
+    fn matches_raw(&self, raw: &str) -> bool {
+        self.lookup_value == raw
+            || self.legacy_odata_value.as_deref() == Some(raw)
+            || self.standard_lookup_value.as_deref() == Some(raw)
+    }
+
+    fn matches_display(&self, value: &str, ignore_case: bool) -> bool {
+        let same = |candidate: &str| {
+            if ignore_case {
+                candidate.eq_ignore_ascii_case(value)
+            } else {
+                candidate == value
+            }
+        };
+        same(&self.lookup_value) || self.standard_lookup_value.as_deref().is_some_and(same)
+    }
+}
+
+/// How a field is tied to a lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FieldLookup {
+    /// Short lookup name
+    lookup: String,
+    /// Qualified enum type for enum-typed fields
+    enum_type: Option<String>,
+    /// Collection or flags field
+    multi: bool,
+}
+
+/// Cached `Lookup` resource records
+///
+/// Build one with [`ResoClient::lookups`](crate::ResoClient::lookups), which
+/// loads the resource and `$metadata` once per client, or from records with
+/// [`from_records`](Self::from_records).
+#[derive(Debug, Clone, Default)]
+pub struct LookupCache {
+    lookups: HashMap<String, Vec<LookupValue>>,
+    fields: HashMap<String, FieldLookup>,
+}
+
+/// Last segment of a dotted lookup or type name
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+impl LookupCache {
+    /// Build a cache from `Lookup` resource records
+    pub fn from_records(records: &[JsonValue]) -> Self {
+        let mut lookups: HashMap<String, Vec<LookupValue>> = HashMap::new();
+        for value in records.iter().filter_map(LookupValue::from_record) {
+            lookups
+                .entry(short_name(&value.lookup_name).to_string())
+                .or_default()
+                .push(value);
+        }
+        Self {
+            lookups,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Tie fields to lookups using a parsed `$metadata` document
+    ///
+    /// String fields use their `RESO.OData.Metadata.LookupName` annotation;
+    /// enum-typed fields use their enum type, and filter literals for them
+    /// are written in enum syntax.
+    pub fn with_metadata(mut self, metadata: &Metadata) -> Self {
+        for entity_type in &metadata.entity_types {
+            for property in &entity_type.properties {
+                let element = property.element_type();
+                let field = if let Some(lookup) = &property.lookup_name {
+                    FieldLookup {
+                        lookup: short_name(lookup).to_string(),
+                        enum_type: None,
+                        multi: property.is_collection(),
+                    }
+                } else if let Some(enum_type) = metadata
+                    .enum_type(element)
+                    .filter(|_| !element.starts_with("Edm."))
+                {
+                    FieldLookup {
+                        lookup: enum_type.name.clone(),
+                        enum_type: Some(enum_type.qualified_name()),
+                        multi: property.is_collection() || enum_type.is_flags,
+                    }
+                } else {
+                    continue;
+                };
+                self.fields.entry(property.name.clone()).or_insert(field);
+            }
+        }
+        self
+    }
+
+    /// Number of lookup values held
+    pub fn len(&self) -> usize {
+        self.lookups.values().map(Vec::len).sum()
+    }
+
+    /// Whether the cache holds no lookup values
+    pub fn is_empty(&self) -> bool {
+        self.lookups.is_empty()
+    }
+
+    /// Names of the lookups held, in no particular order
+    pub fn lookup_names(&self) -> impl Iterator<Item = &str> {
+        self.lookups.keys().map(String::as_str)
+    }
+
+    /// Values of a lookup, by full or short name
+    pub fn values(&self, lookup_name: &str) -> &[LookupValue] {
+        self.lookups
+            .get(short_name(lookup_name))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    fn field(&self, field: &str) -> Option<FieldLookup> {
+        self.fields.get(field).cloned().or_else(|| {
+            self.lookups.contains_key(field).then(|| FieldLookup {
+                lookup: field.to_string(),
+                enum_type: None,
+                multi: false,
+            })
+        })
+    }
+
+    /// Human-readable value of `raw` in `field`, if `raw` is a known value
+    ///
+    /// `raw` may be a `LookupValue`, `LegacyODataValue` or
+    /// `StandardLookupValue`.
+    pub fn resolve(&self, field: &str, raw: &str) -> Option<&str> {
+        let lookup = self.field(field)?;
+        self.values(&lookup.lookup)
+            .iter()
+            .find(|value| value.matches_raw(raw))
+            .map(LookupValue::display_value)
+    }
+
+    /// Replace lookup field values in a record with their human-readable
+    /// values, returning how many values were replaced
+    ///
+    /// Handles single values, collections and comma-separated flags values.
+    /// Unknown values are left as they are.
+    pub fn resolve_record(&self, record: &mut JsonValue) -> usize {
+        let Some(object) = record.as_object_mut() else {
+            return 0;
+        };
+
+        let mut replaced = 0;
+        for (field, value) in object.iter_mut() {
+            if self.field(field).is_none() {
+                continue;
+            }
+            let mut resolve = |raw: &str| match self.resolve(field, raw) {
+                Some(display) if display != raw => {
+                    replaced += 1;
+                    display.to_string()
+                }
+                _ => raw.to_string(),
+            };
+            match value {
+                JsonValue::String(raw)
+                    if raw.contains(',') && self.resolve(field, raw).is_none() =>
+                {
+                    let parts: Vec<String> =
+                        raw.split(',').map(|part| resolve(part.trim())).collect();
+                    *raw = parts.join(",");
+                }
+                JsonValue::String(raw) => *raw = resolve(raw),
+                JsonValue::Array(items) => {
+                    for item in items {
+                        if let JsonValue::String(raw) = item {
+                            *raw = resolve(raw);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        replaced
+    }
+
+    /// Filter literal for a human-readable value of `field`
+    ///
+    /// Returns `'Active Under Contract'` for string lookups and
+    /// `org.reso.metadata.enums.StandardStatus'ActiveUnderContract'` for
+    /// enum-typed fields. Exact matches win over case-insensitive ones.
+    ///
+    /// # Errors
+    ///
+    /// [`ResoError::InvalidQuery`] if the field has no lookup or the value is
+    /// not one of its values.
+    pub fn filter_literal(&self, field: &str, value: &str) -> Result<String> {
+        let lookup = self
+            .field(field)
+            .ok_or_else(|| ResoError::InvalidQuery(format!("{} has no lookup", field)))?;
+        let values = self.values(&lookup.lookup);
+        let entry = values
+            .iter()
+            .find(|v| v.matches_display(value, false))
+            .or_else(|| values.iter().find(|v| v.matches_display(value, true)))
+            .or_else(|| values.iter().find(|v| v.matches_raw(value)))
+            .ok_or_else(|| {
+                ResoError::InvalidQuery(format!(
+                    "'{}' is not a value of the {} lookup",
+                    value, lookup.lookup
+                ))
+            })?;
+
+        Ok(match &lookup.enum_type {
+            Some(enum_type) => format!(
+                "{}'{}'",
+                enum_type,
+                entry
+                    .legacy_odata_value
+                    .as_deref()
+                    .unwrap_or(&entry.lookup_value)
+            ),
+            None => format!("'{}'", entry.lookup_value.replace('\'', "''")),
+        })
+    }
+
+    /// Filter expression matching records where `field` has `value`
+    ///
+    /// Single-valued fields compare with `eq`; collection and flags fields
+    /// use `has` for enum types and `any()` for string lookups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::LookupCache;
+    /// # use serde_json::json;
+    /// let lookups = LookupCache::from_records(&[json!({
+    ///     "LookupName": "StandardStatus",
+    ///     "LookupValue": "Active Under Contract",
+    ///     "LegacyODataValue": "ActiveUnderContract"
+    /// })]);
+    /// assert_eq!(
+    ///     lookups.filter_eq("StandardStatus", "active under contract")?,
+    ///     "StandardStatus eq 'Active Under Contract'"
+    /// );
+    /// # Ok::<(), reso_client::ResoError>(())
+    /// ```
+    pub fn filter_eq(&self, field: &str, value: &str) -> Result<String> {
+        let literal = self.filter_literal(field, value)?;
+        let lookup = self.field(field);
+        Ok(match lookup {
+            Some(FieldLookup {
+                multi: true,
+                enum_type: Some(_),
+                ..
+            }) => format!("{} has {}", field, literal),
+            Some(FieldLookup {
+                multi: true,
+                enum_type: None,
+                ..
+            }) => format!("{}/any(v: v eq {})", field, literal),
+            _ => format!("{} eq {}", field, literal),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Vec<JsonValue> {
+        vec![
+            json!({
+                "LookupKey": "1",
+                "LookupName": "org.reso.metadata.enums.StandardStatus",
+                "LookupValue": "Active Under Contract",
+                "StandardLookupValue": "Active Under Contract",
+                "LegacyODataValue": "ActiveUnderContract"
+            }),
+            json!({
+                "LookupKey": "2",
+                "LookupName": "org.reso.metadata.enums.StandardStatus",
+                "LookupValue": "Coming Soon",
+                "LegacyODataValue": "ComingSoon"
+            }),
+            json!({
+                "LookupKey": "3",
+                "LookupName": "org.reso.metadata.enums.Appliances",
+                "LookupValue": "Dishwasher",
+                "LegacyODataValue": "Dishwasher"
+            }),
+            json!({
+                "LookupKey": "4",
+                "LookupName": "org.reso.metadata.enums.Appliances",
+                "LookupValue": "Wine Cooler",
+                "StandardLookupValue": "Wine Refrigerator",
+                "LegacyODataValue": "WineCooler"
+            }),
+            json!({"LookupKey": "5", "LookupName": "Incomplete"}),
+        ]
+    }
+
+    const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String"/>
+        <Property Name="MlsStatus" Type="Edm.String">
+          <Annotation Term="RESO.OData.Metadata.LookupName" String="org.reso.metadata.enums.StandardStatus"/>
+        </Property>
+        <Property Name="StandardStatus" Type="org.reso.metadata.enums.StandardStatus"/>
+        <Property Name="Appliances" Type="org.reso.metadata.enums.Appliances"/>
+      </EntityType>
+    </Schema>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata.enums">
+      <EnumType Name="StandardStatus">
+        <Member Name="ActiveUnderContract"/>
+        <Member Name="ComingSoon"/>
+      </EnumType>
+      <EnumType Name="Appliances" IsFlags="true">
+        <Member Name="Dishwasher" Value="1"/>
+        <Member Name="WineCooler" Value="2"/>
+      </EnumType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+    #[test]
+    fn test_from_records() {
+        let cache = LookupCache::from_records(&records());
+
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.values("StandardStatus").len(), 2);
+        assert_eq!(
+            cache.values("org.reso.metadata.enums.Appliances")[1].display_value(),
+            "Wine Refrigerator"
+        );
+        assert!(cache.values("Missing").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_by_field_name() {
+        let cache = LookupCache::from_records(&records());
+
+        assert_eq!(
+            cache.resolve("StandardStatus", "ActiveUnderContract"),
+            Some("Active Under Contract")
+        );
+        assert_eq!(
+            cache.resolve("Appliances", "Wine Cooler"),
+            Some("Wine Refrigerator")
+        );
+        assert_eq!(cache.resolve("StandardStatus", "Unknown"), None);
+        assert_eq!(cache.resolve("City", "Austin"), None);
+    }
+
+    #[test]
+    fn test_resolve_record() {
+        let cache = LookupCache::from_records(&records())
+            .with_metadata(&Metadata::parse(METADATA).unwrap());
+        let mut record = json!({
+            "ListingKey": "L1",
+            "StandardStatus": "ComingSoon",
+            "MlsStatus": "ActiveUnderContract",
+            "Appliances": "Dishwasher,WineCooler",
+            "City": "ComingSoon"
+        });
+
+        assert_eq!(cache.resolve_record(&mut record), 3);
+        assert_eq!(record["StandardStatus"], "Coming Soon");
+        assert_eq!(record["MlsStatus"], "Active Under Contract");
+        assert_eq!(record["Appliances"], "Dishwasher,Wine Refrigerator");
+        assert_eq!(record["City"], "ComingSoon");
+
+        let mut collection = json!({"Appliances": ["WineCooler", "Other"]});
+        assert_eq!(cache.resolve_record(&mut collection), 1);
+        assert_eq!(
+            collection["Appliances"],
+            json!(["Wine Refrigerator", "Other"])
+        );
+    }
+
+    #[test]
+    fn test_filter_literals() {
+        let strings = LookupCache::from_records(&records());
+        assert_eq!(
+            strings
+                .filter_literal("StandardStatus", "coming soon")
+                .unwrap(),
+            "'Coming Soon'"
+        );
+
+        let enums = strings.with_metadata(&Metadata::parse(METADATA).unwrap());
+        assert_eq!(
+            enums.filter_eq("StandardStatus", "Coming Soon").unwrap(),
+            "StandardStatus eq org.reso.metadata.enums.StandardStatus'ComingSoon'"
+        );
+        assert_eq!(
+            enums.filter_eq("Appliances", "Wine Refrigerator").unwrap(),
+            "Appliances has org.reso.metadata.enums.Appliances'WineCooler'"
+        );
+        assert_eq!(
+            enums.filter_eq("MlsStatus", "Coming Soon").unwrap(),
+            "MlsStatus eq 'Coming Soon'"
+        );
+
+        assert!(matches!(
+            enums.filter_literal("StandardStatus", "Sold"),
+            Err(ResoError::InvalidQuery(_))
+        ));
+        assert!(matches!(
+            enums.filter_literal("City", "Austin"),
+            Err(ResoError::InvalidQuery(_))
+        ));
+    }
+}
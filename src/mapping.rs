@@ -0,0 +1,809 @@
+// src/mapping.rs
+
+//! Per-feed field mapping into the RESO Data Dictionary shape
+//!
+//! MLS feeds name the same concept differently: vendor-prefixed fields,
+//! legacy names, local enumeration values. A [`FieldMapping`] declares, for
+//! one feed, how its records translate into Data Dictionary fields:
+//!
+//! - **rename** a source field to its standard name
+//! - **cast** the value to a string, integer, decimal, boolean, date or list
+//! - **map values** such as `"A"` to `"Active"`
+//! - **compute** fields from constants or other fields
+//!
+//! A [`RecordNormalizer`] applies the mappings of several feeds for one
+//! resource. Standard fields pass through unchanged, mapped fields are
+//! translated, and the remaining fields are counted in a [`MappingReport`]
+//! and kept. With `keep_unmapped = false` they are dropped instead; the
+//! bundled Data Dictionary only lists common fields, so do that only with
+//! complete definitions (see [`RecordNormalizer::with_dictionary`]).
+//!
+//! Mappings are usually written in TOML:
+//!
+//! ```toml
+//! # Fields already in the Data Dictionary need no entry
+//! ignore = ["X_InternalId"]
+//!
+//! [fields]
+//! L_ListingID = "ListingId"               # plain rename
+//! L_AskingPrice = { rename = "ListPrice", type = "decimal" }
+//! L_Status = { rename = "StandardStatus", values = { A = "Active", P = "Pending", S = "Closed" } }
+//! L_Features = { rename = "Appliances", type = "list" }
+//!
+//! [computed]
+//! OriginatingSystemName = { constant = "Example MLS" }
+//! UnparsedAddress = { concat = { fields = ["StreetNumber", "StreetName", "City"], separator = " " } }
+//! ```
+//!
+//! Computed fields read the record after renames, so they refer to standard
+//! names.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{FieldMapping, QueryBuilder, RecordNormalizer, ResoClient};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let normalizer = RecordNormalizer::new("Property")?
+//!     .feed("example", FieldMapping::from_toml(&std::fs::read_to_string("example.toml")?)?);
+//!
+//! let page = client.execute(&QueryBuilder::new("Property").top(100).build()?).await?;
+//! let records = page["value"].as_array().cloned().unwrap_or_default();
+//!
+//! let normalized = normalizer.normalize("example", &records);
+//! for (field, count) in &normalized.report.unmapped_fields {
+//!     println!("unmapped: {} ({} records)", field, count);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Wrap a sink in a [`NormalizingSink`] to normalize replicated records
+//! before they are written.
+
+use crate::compliance::DataDictionary;
+use crate::error::{ResoError, Result};
+#[cfg(feature = "client")]
+use crate::registry::{FanOutResult, FeedRecord};
+use crate::sink::{RecordSink, ReplicationCheckpoint};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Declarative mapping of one feed's records
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldMapping {
+    /// Rules by source field name
+    pub fields: BTreeMap<String, FieldRule>,
+
+    /// Computed fields by target name
+    pub computed: BTreeMap<String, Computed>,
+
+    /// Source fields to drop without reporting them
+    pub ignore: Vec<String>,
+
+    /// Keep fields that are neither mapped nor in the dictionary (default)
+    ///
+    /// Set to `false` to drop them. Fields the dictionary does not know are
+    /// treated as unmapped, including real Data Dictionary fields missing
+    /// from an incomplete dictionary.
+    pub keep_unmapped: bool,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            fields: BTreeMap::new(),
+            computed: BTreeMap::new(),
+            ignore: Vec::new(),
+            keep_unmapped: true,
+        }
+    }
+}
+
+/// How to translate one source field
+///
+/// A plain string is shorthand for a rename.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RuleRepr")]
+pub struct FieldRule {
+    /// Target field name; the source name if unset
+    pub rename: Option<String>,
+
+    /// Type to cast the value to
+    #[serde(rename = "type")]
+    pub cast: Option<Cast>,
+
+    /// Replacement values by source value
+    pub values: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RuleRepr {
+    Rename(String),
+    Rule {
+        #[serde(default)]
+        rename: Option<String>,
+        #[serde(default, rename = "type")]
+        cast: Option<Cast>,
+        #[serde(default)]
+        values: BTreeMap<String, JsonValue>,
+    },
+}
+
+impl From<RuleRepr> for FieldRule {
+    fn from(repr: RuleRepr) -> Self {
+        match repr {
+            RuleRepr::Rename(rename) => FieldRule {
+                rename: Some(rename),
+                ..FieldRule::default()
+            },
+            RuleRepr::Rule {
+                rename,
+                cast,
+                values,
+            } => FieldRule {
+                rename,
+                cast,
+                values,
+            },
+        }
+    }
+}
+
+/// Target type of a cast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cast {
+    /// Any scalar as a string
+    String,
+    /// Whole number; numeric strings are parsed
+    Integer,
+    /// Number; `$` and thousands separators are stripped from strings
+    Decimal,
+    /// `true`/`false`, `yes`/`no`, `y`/`n` or `1`/`0`
+    Boolean,
+    /// `YYYY-MM-DD` from an ISO date or timestamp, or `MM/DD/YYYY`
+    Date,
+    /// Array; strings are split on commas
+    List,
+}
+
+/// A field computed from constants or other fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Computed {
+    /// A fixed value
+    Constant(JsonValue),
+
+    /// The first of these fields that is not null
+    Coalesce(Vec<String>),
+
+    /// The non-empty values of these fields joined by `separator`
+    Concat {
+        /// Fields to join
+        fields: Vec<String>,
+        /// Separator; a single space if unset
+        #[serde(default)]
+        separator: Option<String>,
+    },
+}
+
+impl FieldMapping {
+    /// Parse a mapping from TOML
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| ResoError::Config(format!("Invalid field mapping: {}", e)))
+    }
+
+    /// Parse a mapping from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| ResoError::Config(format!("Invalid field mapping: {}", e)))
+    }
+}
+
+/// What a normalization run could not translate
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingReport {
+    /// Feed the records came from
+    pub feed: String,
+
+    /// Resource the records belong to
+    pub resource: String,
+
+    /// Records normalized
+    pub records: u64,
+
+    /// Non-standard fields without a rule, with the number of records
+    /// containing each
+    pub unmapped_fields: BTreeMap<String, u64>,
+
+    /// Source values missing from a field's value map, by source field
+    pub unmapped_values: BTreeMap<String, BTreeSet<String>>,
+
+    /// Values that could not be cast and became null, counted by source field
+    pub cast_failures: BTreeMap<String, u64>,
+}
+
+impl MappingReport {
+    /// Whether every field and value was translated
+    pub fn is_clean(&self) -> bool {
+        self.unmapped_fields.is_empty()
+            && self.unmapped_values.is_empty()
+            && self.cast_failures.is_empty()
+    }
+
+    /// Add the counts of another run over the same feed
+    pub fn merge(&mut self, other: MappingReport) {
+        self.records += other.records;
+        for (field, count) in other.unmapped_fields {
+            *self.unmapped_fields.entry(field).or_default() += count;
+        }
+        for (field, values) in other.unmapped_values {
+            self.unmapped_values
+                .entry(field)
+                .or_default()
+                .extend(values);
+        }
+        for (field, count) in other.cast_failures {
+            *self.cast_failures.entry(field).or_default() += count;
+        }
+    }
+
+    /// The report as JSON
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).unwrap_or(JsonValue::Null)
+    }
+}
+
+/// Normalized records and the report for them
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedRecords {
+    /// Records in the Data Dictionary shape
+    pub records: Vec<JsonValue>,
+
+    /// What could not be translated
+    pub report: MappingReport,
+}
+
+/// Applies per-feed mappings for one resource
+#[derive(Debug, Clone)]
+pub struct RecordNormalizer {
+    resource: String,
+    standard: BTreeSet<String>,
+    feeds: BTreeMap<String, FieldMapping>,
+    empty: FieldMapping,
+}
+
+impl RecordNormalizer {
+    /// Normalizer for `resource` using the bundled Data Dictionary
+    ///
+    /// # Errors
+    ///
+    /// [`ResoError::InvalidQuery`] if the bundled definitions do not cover
+    /// the resource.
+    pub fn new(resource: &str) -> Result<Self> {
+        Self::with_dictionary(DataDictionary::bundled(), resource)
+    }
+
+    /// Normalizer for `resource` using other Data Dictionary definitions
+    pub fn with_dictionary(dictionary: &DataDictionary, resource: &str) -> Result<Self> {
+        let fields = dictionary.fields(resource).ok_or_else(|| {
+            ResoError::InvalidQuery(format!(
+                "Data Dictionary {} has no definitions for {}",
+                dictionary.version, resource
+            ))
+        })?;
+        Ok(Self {
+            resource: resource.to_string(),
+            standard: fields.iter().map(|f| f.name.clone()).collect(),
+            feeds: BTreeMap::new(),
+            empty: FieldMapping::default(),
+        })
+    }
+
+    /// Set the mapping for a feed
+    ///
+    /// Feeds without a mapping pass through unchanged, with their
+    /// non-standard fields reported.
+    pub fn feed(mut self, name: impl Into<String>, mapping: FieldMapping) -> Self {
+        self.feeds.insert(name.into(), mapping);
+        self
+    }
+
+    /// Mapping for a feed, if one was set
+    pub fn mapping(&self, feed: &str) -> Option<&FieldMapping> {
+        self.feeds.get(feed)
+    }
+
+    /// Normalize records from `feed`
+    pub fn normalize(&self, feed: &str, records: &[JsonValue]) -> NormalizedRecords {
+        let mapping = self.feeds.get(feed).unwrap_or(&self.empty);
+        let mut report = MappingReport {
+            feed: feed.to_string(),
+            resource: self.resource.clone(),
+            ..MappingReport::default()
+        };
+        let records = records
+            .iter()
+            .map(|record| self.normalize_record(mapping, record, &mut report))
+            .collect();
+        NormalizedRecords { records, report }
+    }
+
+    /// Normalize every record of a fan-out query by its source feed
+    ///
+    /// Feed errors are kept; reports are returned by feed name.
+    #[cfg(feature = "client")]
+    pub fn normalize_fan_out(
+        &self,
+        result: FanOutResult,
+    ) -> (FanOutResult, BTreeMap<String, MappingReport>) {
+        let mut reports: BTreeMap<String, MappingReport> = BTreeMap::new();
+        let records = result
+            .records
+            .into_iter()
+            .map(|FeedRecord { feed, record }| {
+                let mut normalized = self.normalize(&feed, std::slice::from_ref(&record));
+                let record = normalized.records.pop().unwrap_or(JsonValue::Null);
+                match reports.get_mut(&feed) {
+                    Some(report) => report.merge(normalized.report),
+                    None => {
+                        reports.insert(feed.clone(), normalized.report);
+                    }
+                }
+                FeedRecord { feed, record }
+            })
+            .collect();
+        let result = FanOutResult {
+            records,
+            errors: result.errors,
+        };
+        (result, reports)
+    }
+
+    fn normalize_record(
+        &self,
+        mapping: &FieldMapping,
+        record: &JsonValue,
+        report: &mut MappingReport,
+    ) -> JsonValue {
+        report.records += 1;
+        let Some(source) = record.as_object() else {
+            return record.clone();
+        };
+
+        let mut output = Map::new();
+        let mut mapped = Vec::new();
+        for (field, value) in source {
+            if let Some(rule) = mapping.fields.get(field) {
+                let target = rule.rename.as_deref().unwrap_or(field);
+                mapped.push((target.to_string(), convert(field, rule, value, report)));
+            } else if field.starts_with('@') || self.standard.contains(field) {
+                output.insert(field.clone(), value.clone());
+            } else if mapping.ignore.contains(field) {
+                continue;
+            } else {
+                *report.unmapped_fields.entry(field.clone()).or_default() += 1;
+                if mapping.keep_unmapped {
+                    output.insert(field.clone(), value.clone());
+                }
+            }
+        }
+        // Mapped fields win over source fields already using the target name
+        output.extend(mapped);
+
+        for (target, computed) in &mapping.computed {
+            let value = compute(computed, &output);
+            output.insert(target.clone(), value);
+        }
+        JsonValue::Object(output)
+    }
+}
+
+/// Apply a rule's value map and cast to one value
+fn convert(
+    field: &str,
+    rule: &FieldRule,
+    value: &JsonValue,
+    report: &mut MappingReport,
+) -> JsonValue {
+    if value.is_null() {
+        return JsonValue::Null;
+    }
+    let value = match rule.cast {
+        Some(Cast::List) => to_list(value),
+        _ => value.clone(),
+    };
+    let value = match value {
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .into_iter()
+                .map(|item| map_value(field, rule, item, report))
+                .collect(),
+        ),
+        value => map_value(field, rule, value, report),
+    };
+    match rule.cast {
+        None | Some(Cast::List) => value,
+        Some(cast) => cast_value(cast, &value).unwrap_or_else(|| {
+            *report.cast_failures.entry(field.to_string()).or_default() += 1;
+            JsonValue::Null
+        }),
+    }
+}
+
+fn map_value(
+    field: &str,
+    rule: &FieldRule,
+    value: JsonValue,
+    report: &mut MappingReport,
+) -> JsonValue {
+    if rule.values.is_empty() || value.is_null() {
+        return value;
+    }
+    let key = match &value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match rule.values.get(&key) {
+        Some(mapped) => mapped.clone(),
+        None => {
+            report
+                .unmapped_values
+                .entry(field.to_string())
+                .or_default()
+                .insert(key);
+            value
+        }
+    }
+}
+
+fn to_list(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Array(_) => value.clone(),
+        JsonValue::String(s) => JsonValue::Array(
+            s.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| JsonValue::String(item.to_string()))
+                .collect(),
+        ),
+        other => JsonValue::Array(vec![other.clone()]),
+    }
+}
+
+fn cast_value(cast: Cast, value: &JsonValue) -> Option<JsonValue> {
+    match (cast, value) {
+        (_, JsonValue::Null) => Some(JsonValue::Null),
+        (Cast::String, JsonValue::String(_)) => Some(value.clone()),
+        (Cast::String, JsonValue::Number(n)) => Some(JsonValue::String(n.to_string())),
+        (Cast::String, JsonValue::Bool(b)) => Some(JsonValue::String(b.to_string())),
+        (Cast::Integer, JsonValue::Number(n)) => n
+            .as_i64()
+            .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+            .map(JsonValue::from),
+        (Cast::Integer, JsonValue::String(s)) => {
+            let s = s.trim().replace(',', "");
+            s.parse::<i64>()
+                .ok()
+                .or_else(|| {
+                    s.parse::<f64>()
+                        .ok()
+                        .filter(|f| f.fract() == 0.0)
+                        .map(|f| f as i64)
+                })
+                .map(JsonValue::from)
+        }
+        (Cast::Decimal, JsonValue::Number(_)) => Some(value.clone()),
+        (Cast::Decimal, JsonValue::String(s)) => s
+            .trim()
+            .trim_start_matches('$')
+            .replace(',', "")
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(JsonValue::Number),
+        (Cast::Boolean, JsonValue::Bool(_)) => Some(value.clone()),
+        (Cast::Boolean, JsonValue::Number(n)) => match n.as_i64() {
+            Some(0) => Some(JsonValue::Bool(false)),
+            Some(1) => Some(JsonValue::Bool(true)),
+            _ => None,
+        },
+        (Cast::Boolean, JsonValue::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" => Some(JsonValue::Bool(true)),
+            "false" | "no" | "n" | "0" => Some(JsonValue::Bool(false)),
+            _ => None,
+        },
+        (Cast::Date, JsonValue::String(s)) => parse_date(s.trim()).map(JsonValue::String),
+        _ => None,
+    }
+}
+
+/// `YYYY-MM-DD` from `YYYY-MM-DD[T...]` or `MM/DD/YYYY`
+fn parse_date(s: &str) -> Option<String> {
+    let digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    if let Some(date) = s.get(..10) {
+        let parts: Vec<&str> = date.split('-').collect();
+        if let [year, month, day] = parts[..] {
+            if digits(year, 4) && digits(month, 2) && digits(day, 2) {
+                return Some(date.to_string());
+            }
+        }
+    }
+    let parts: Vec<&str> = s.split('/').collect();
+    match parts[..] {
+        [month, day, year]
+            if digits(year, 4)
+                && (1..=2).contains(&month.len())
+                && (1..=2).contains(&day.len()) =>
+        {
+            let month: u32 = month.parse().ok()?;
+            let day: u32 = day.parse().ok()?;
+            Some(format!("{}-{:02}-{:02}", year, month, day))
+        }
+        _ => None,
+    }
+}
+
+fn compute(computed: &Computed, record: &Map<String, JsonValue>) -> JsonValue {
+    let present = |field: &String| record.get(field).filter(|v| !v.is_null());
+    match computed {
+        Computed::Constant(value) => value.clone(),
+        Computed::Coalesce(fields) => fields
+            .iter()
+            .find_map(present)
+            .cloned()
+            .unwrap_or(JsonValue::Null),
+        Computed::Concat { fields, separator } => {
+            let parts: Vec<String> = fields
+                .iter()
+                .filter_map(present)
+                .map(|value| match value {
+                    JsonValue::String(s) => s.trim().to_string(),
+                    other => other.to_string(),
+                })
+                .filter(|s| !s.is_empty())
+                .collect();
+            if parts.is_empty() {
+                JsonValue::Null
+            } else {
+                JsonValue::String(parts.join(separator.as_deref().unwrap_or(" ")))
+            }
+        }
+    }
+}
+
+/// Sink wrapper that normalizes records before writing them
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "client")]
+/// # use reso_client::{FieldMapping, JsonFileSink, JsonFormat, NormalizingSink, RecordNormalizer, ReplicationQueryBuilder, ResoClient};
+/// # #[cfg(feature = "client")]
+/// # async fn example(client: &ResoClient, mapping: FieldMapping) -> Result<(), Box<dyn std::error::Error>> {
+/// let normalizer = RecordNormalizer::new("Property")?.feed("example", mapping);
+/// let files = JsonFileSink::new("export", "Property", JsonFormat::Ndjson);
+/// let mut sink = NormalizingSink::new(files, normalizer, "example");
+///
+/// let query = ReplicationQueryBuilder::new("Property").build()?;
+/// client.replicate_into(&query, &mut sink).await?;
+/// println!("{}", sink.report().to_json());
+/// # Ok(())
+/// # }
+/// ```
+pub struct NormalizingSink<S> {
+    inner: S,
+    normalizer: RecordNormalizer,
+    feed: String,
+    report: MappingReport,
+}
+
+impl<S: RecordSink> NormalizingSink<S> {
+    /// Normalize records from `feed` before passing them to `inner`
+    pub fn new(inner: S, normalizer: RecordNormalizer, feed: impl Into<String>) -> Self {
+        let feed = feed.into();
+        let report = MappingReport {
+            feed: feed.clone(),
+            resource: normalizer.resource.clone(),
+            ..MappingReport::default()
+        };
+        Self {
+            inner,
+            normalizer,
+            feed,
+            report,
+        }
+    }
+
+    /// Report covering every record written so far
+    pub fn report(&self) -> &MappingReport {
+        &self.report
+    }
+
+    /// The wrapped sink
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for NormalizingSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        let normalized = self.normalizer.normalize(&self.feed, records);
+        self.inner.write_batch(&normalized.records).await?;
+        self.report.merge(normalized.report);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.inner.commit(checkpoint).await
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        self.inner.last_checkpoint().await
+    }
+
+    async fn resume(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.inner.resume(checkpoint).await
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.inner.reset().await
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        self.inner.max_batch_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MAPPING: &str = r#"
+        ignore = ["X_RowVersion"]
+        keep_unmapped = false
+
+        [fields]
+        L_ListingID = "ListingId"
+        L_AskingPrice = { rename = "ListPrice", type = "decimal" }
+        L_Status = { rename = "StandardStatus", values = { A = "Active", P = "Pending" } }
+        L_Features = { rename = "Appliances", type = "list", values = { DW = "Dishwasher", RF = "Refrigerator" } }
+        L_Beds = { rename = "BedroomsTotal", type = "integer" }
+        L_Listed = { rename = "ListingContractDate", type = "date" }
+
+        [computed]
+        OriginatingSystemName = { constant = "Example MLS" }
+        UnparsedAddress = { concat = { fields = ["StreetNumber", "StreetName", "City"] } }
+    "#;
+
+    fn normalizer() -> RecordNormalizer {
+        RecordNormalizer::new("Property")
+            .unwrap()
+            .feed("example", FieldMapping::from_toml(MAPPING).unwrap())
+    }
+
+    #[test]
+    fn test_normalize_record() {
+        let records = [json!({
+            "@odata.id": "Property('1')",
+            "ListingKey": "1",
+            "L_ListingID": "ML-1",
+            "L_AskingPrice": "$1,250,000",
+            "L_Status": "A",
+            "L_Features": "DW, RF, XX",
+            "L_Beds": "3",
+            "L_Listed": "04/07/2024",
+            "StreetNumber": "12",
+            "StreetName": "Main St",
+            "City": "Austin",
+            "X_RowVersion": 7,
+            "X_Agent_Notes": "local"
+        })];
+
+        let normalized = normalizer().normalize("example", &records);
+        assert_eq!(
+            normalized.records[0],
+            json!({
+                "@odata.id": "Property('1')",
+                "ListingKey": "1",
+                "ListingId": "ML-1",
+                "ListPrice": 1250000.0,
+                "StandardStatus": "Active",
+                "Appliances": ["Dishwasher", "Refrigerator", "XX"],
+                "BedroomsTotal": 3,
+                "ListingContractDate": "2024-04-07",
+                "StreetNumber": "12",
+                "StreetName": "Main St",
+                "City": "Austin",
+                "OriginatingSystemName": "Example MLS",
+                "UnparsedAddress": "12 Main St Austin"
+            })
+        );
+
+        let report = normalized.report;
+        assert_eq!(report.records, 1);
+        assert_eq!(
+            report.unmapped_fields,
+            BTreeMap::from([("X_Agent_Notes".to_string(), 1)])
+        );
+        assert_eq!(
+            report.unmapped_values["L_Features"],
+            BTreeSet::from(["XX".to_string()])
+        );
+        assert!(report.cast_failures.is_empty());
+        assert_eq!(report.to_json()["unmappedFields"]["X_Agent_Notes"], 1);
+    }
+
+    #[test]
+    fn test_cast_failures_and_unmapped_feeds() {
+        let records = [
+            json!({"ListingKey": "1", "L_Beds": "three", "L_Listed": "2024-13", "Local": 1}),
+            json!({"ListingKey": "2", "L_Beds": 4.0, "L_Listed": "2024-04-07T10:00:00Z", "Local": 2}),
+        ];
+
+        let normalized = normalizer().normalize("example", &records);
+        assert_eq!(normalized.records[0]["BedroomsTotal"], JsonValue::Null);
+        assert_eq!(normalized.records[1]["BedroomsTotal"], 4);
+        assert_eq!(normalized.records[1]["ListingContractDate"], "2024-04-07");
+        assert_eq!(normalized.report.cast_failures["L_Beds"], 1);
+        assert_eq!(normalized.report.cast_failures["L_Listed"], 1);
+        assert_eq!(normalized.report.unmapped_fields["Local"], 2);
+        assert!(!normalized.report.is_clean());
+
+        // A feed without a mapping keeps its records whole
+        let normalized = normalizer().normalize("other", &records);
+        assert_eq!(normalized.records[0], records[0]);
+        assert_eq!(normalized.report.unmapped_fields.len(), 3);
+    }
+
+    #[test]
+    fn test_fields_missing_from_dictionary_are_kept_by_default() {
+        // Data Dictionary fields the bundled definitions do not list
+        let records = [json!({
+            "ListingKey": "1",
+            "ExpirationDate": "2024-12-31",
+            "ContingentDate": "2024-06-01"
+        })];
+        let normalizer = RecordNormalizer::new("Property")
+            .unwrap()
+            .feed("example", FieldMapping::default());
+
+        let normalized = normalizer.normalize("example", &records);
+        assert_eq!(normalized.records[0], records[0]);
+        assert_eq!(normalized.report.unmapped_fields["ExpirationDate"], 1);
+
+        let dropping = FieldMapping::from_toml("keep_unmapped = false").unwrap();
+        let normalized = normalizer
+            .feed("example", dropping)
+            .normalize("example", &records);
+        assert_eq!(normalized.records[0], json!({"ListingKey": "1"}));
+    }
+
+    #[test]
+    fn test_mapping_formats() {
+        let toml = FieldMapping::from_toml(MAPPING).unwrap();
+        let json = FieldMapping::from_json(&serde_json::to_string(&toml).unwrap()).unwrap();
+        assert_eq!(toml, json);
+        assert_eq!(
+            toml.fields["L_ListingID"].rename.as_deref(),
+            Some("ListingId")
+        );
+
+        assert!(FieldMapping::from_toml("[fields]\nX = { rename = 1 }").is_err());
+        assert!(RecordNormalizer::new("NotAResource").is_err());
+    }
+}
@@ -0,0 +1,773 @@
+// src/media.rs
+
+//! Listing media: query the `Media` resource and download payloads
+//!
+//! [`MediaItem`] is the typed view of a RESO `Media` record. Items come from
+//! [`ResoClient::fetch_media`](crate::ResoClient::fetch_media), which queries
+//! the `Media` resource for a listing, or from a Property record fetched with
+//! `$expand=Media` via [`MediaItem::from_expanded`]. Both are sorted by the
+//! `Order` field.
+//!
+//! [`MediaDownloader`] fetches the `MediaURL` payloads concurrently with a
+//! size limit, a content-type allow list and retries for transient failures.
+//! Media URLs usually point at a CDN rather than the RESO server, so downloads
+//! are sent without the bearer token.
+//!
+//! # Local Storage and Change Detection
+//!
+//! With [`store_in`](MediaDownloader::store_in), payloads are written to
+//! `{dir}/{ResourceRecordKey}/{MediaKey}.{ext}` and recorded in
+//! `{dir}/media-manifest.json`. On later runs an item whose
+//! `MediaModificationTimestamp` matches the manifest (and whose file is still
+//! there) is reported as [`MediaStatus::Unchanged`] without being fetched.
+//! Items without a timestamp are compared by `MediaURL` instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::ResoClient;
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let items = client.fetch_media("3yd-ACTRIS-12345").await?;
+//!
+//! // Downloads go through the client's proxy and TLS settings
+//! let downloads = client
+//!     .media_downloader()
+//!     .concurrency(8)
+//!     .max_bytes(10 * 1024 * 1024)
+//!     .store_in("media")
+//!     .download(&items)
+//!     .await?;
+//!
+//! for download in &downloads {
+//!     match &download.result {
+//!         Ok(media) => println!("{}: {:?} ({} bytes)", download.item.media_key, media.status, media.size),
+//!         Err(e) => eprintln!("{}: {}", download.item.media_key, e),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::http_client::HttpClientConfig;
+use crate::queries::{Query, QueryBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name of the RESO media resource
+pub const MEDIA_RESOURCE: &str = "Media";
+
+/// Manifest file written by [`MediaDownloader::store_in`]
+pub const MEDIA_MANIFEST: &str = "media-manifest.json";
+
+/// A RESO `Media` record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaItem {
+    /// `MediaKey`, or the URL when the server omits it
+    pub media_key: String,
+
+    /// `MediaURL`
+    pub media_url: String,
+
+    /// `ResourceRecordKey` (the `ListingKey` for Property media)
+    pub resource_record_key: Option<String>,
+
+    /// `Order`, the display position
+    pub order: Option<i64>,
+
+    /// `MediaCategory`, e.g. `Photo`, `Floor Plan`
+    pub media_category: Option<String>,
+
+    /// `MimeType` reported by the server
+    pub mime_type: Option<String>,
+
+    /// `MediaModificationTimestamp`
+    pub modification_timestamp: Option<String>,
+
+    /// `ShortDescription`
+    pub short_description: Option<String>,
+}
+
+impl MediaItem {
+    /// Read an item from a `Media` record; `None` if it has no `MediaURL`
+    pub fn from_record(record: &JsonValue) -> Option<Self> {
+        let text = |field: &str| match record.get(field) {
+            Some(JsonValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            Some(JsonValue::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let media_url = text("MediaURL")?;
+
+        Some(Self {
+            media_key: text("MediaKey").unwrap_or_else(|| media_url.clone()),
+            resource_record_key: text("ResourceRecordKey"),
+            order: record.get("Order").and_then(|order| match order {
+                JsonValue::String(s) => s.parse().ok(),
+                other => other.as_i64(),
+            }),
+            media_category: text("MediaCategory"),
+            mime_type: text("MimeType"),
+            modification_timestamp: text("MediaModificationTimestamp"),
+            short_description: text("ShortDescription"),
+            media_url,
+        })
+    }
+
+    /// Read items from `Media` records, sorted by `Order`
+    ///
+    /// Records without a `MediaURL` are skipped; items without an `Order`
+    /// sort last, keeping their relative position.
+    pub fn from_records(records: &[JsonValue]) -> Vec<Self> {
+        let mut items: Vec<Self> = records.iter().filter_map(Self::from_record).collect();
+        items.sort_by_key(|item| (item.order.is_none(), item.order));
+        items
+    }
+
+    /// Read items from a Property record fetched with `$expand=Media`
+    ///
+    /// Items without a `ResourceRecordKey` take the record's `ListingKey`.
+    pub fn from_expanded(record: &JsonValue) -> Vec<Self> {
+        let records = match record.get(MEDIA_RESOURCE) {
+            Some(JsonValue::Array(records)) => records.as_slice(),
+            _ => &[],
+        };
+        let listing_key = record
+            .get("ListingKey")
+            .and_then(JsonValue::as_str)
+            .map(String::from);
+
+        let mut items = Self::from_records(records);
+        for item in &mut items {
+            if item.resource_record_key.is_none() {
+                item.resource_record_key = listing_key.clone();
+            }
+        }
+        items
+    }
+}
+
+/// Query for the media of one record, ordered by `Order`
+///
+/// `resource_name` is the resource the media belongs to (`Property`,
+/// `Member`, `Office`, ...) and `record_key` that record's key.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::media::media_query;
+/// let query = media_query("Property", "12345")?;
+/// assert_eq!(
+///     query.to_odata_string(),
+///     "Media?$filter=ResourceName%20eq%20%27Property%27%20and%20ResourceRecordKey%20eq%20%2712345%27&$orderby=Order%20asc"
+/// );
+/// # Ok::<(), reso_client::ResoError>(())
+/// ```
+pub fn media_query(resource_name: &str, record_key: &str) -> Result<Query> {
+    let quote = |value: &str| value.replace('\'', "''");
+    QueryBuilder::new(MEDIA_RESOURCE)
+        .filter(format!(
+            "ResourceName eq '{}' and ResourceRecordKey eq '{}'",
+            quote(resource_name),
+            quote(record_key)
+        ))
+        .order_by("Order", "asc")
+        .build()
+}
+
+/// Whether a payload was fetched or reused from the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaStatus {
+    /// Fetched from `MediaURL`
+    Downloaded,
+    /// Matched the store manifest; not fetched
+    Unchanged,
+}
+
+/// A successfully downloaded (or reused) media payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadedMedia {
+    /// Whether the payload was fetched
+    pub status: MediaStatus,
+
+    /// Content type of the payload, without parameters
+    pub content_type: String,
+
+    /// Payload size in bytes
+    pub size: u64,
+
+    /// Hex SHA-256 of the payload
+    pub sha256: String,
+
+    /// Payload bytes; only kept in memory when there is no store
+    pub data: Option<Vec<u8>>,
+
+    /// Stored file, when downloading into a store
+    pub path: Option<PathBuf>,
+}
+
+/// Outcome for one [`MediaItem`]
+#[derive(Debug)]
+pub struct MediaDownload {
+    /// The requested item
+    pub item: MediaItem,
+
+    /// The payload, or why it could not be downloaded
+    pub result: Result<DownloadedMedia>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ManifestEntry {
+    media_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modification_timestamp: Option<String>,
+    /// Relative to the store directory
+    path: String,
+    content_type: String,
+    size: u64,
+    sha256: String,
+}
+
+impl ManifestEntry {
+    fn is_current(&self, item: &MediaItem) -> bool {
+        match (&item.modification_timestamp, &self.modification_timestamp) {
+            (Some(current), Some(stored)) => current == stored,
+            (None, None) => item.media_url == self.media_url,
+            _ => false,
+        }
+    }
+}
+
+/// Concurrent media downloader
+///
+/// Defaults: 4 concurrent downloads, 25 MiB per payload, `image/*` content
+/// types only, 2 retries starting at 500 ms, 60 second timeout, kept in
+/// memory, sent with a default HTTP client. Use
+/// [`ResoClient::media_downloader`](crate::ResoClient::media_downloader) to
+/// send downloads with the client's HTTP settings instead.
+#[derive(Debug, Clone)]
+pub struct MediaDownloader {
+    concurrency: usize,
+    max_bytes: u64,
+    content_types: Vec<String>,
+    retries: u32,
+    retry_delay: Duration,
+    timeout: Duration,
+    store: Option<PathBuf>,
+    http_client: Option<reqwest::Client>,
+}
+
+impl Default for MediaDownloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaDownloader {
+    /// Create a downloader with default limits
+    pub fn new() -> Self {
+        Self {
+            concurrency: 4,
+            max_bytes: 25 * 1024 * 1024,
+            content_types: vec!["image/*".to_string()],
+            retries: 2,
+            retry_delay: Duration::from_millis(500),
+            timeout: Duration::from_secs(60),
+            store: None,
+            http_client: None,
+        }
+    }
+
+    /// Maximum number of downloads in flight
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Reject payloads larger than `max_bytes`
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Accept only these content types
+    ///
+    /// Entries are exact types (`application/pdf`) or wildcards
+    /// (`image/*`, `*/*`). An empty list accepts anything. When the response
+    /// has no `Content-Type`, the item's `MimeType` is checked instead.
+    pub fn allow_content_types(mut self, content_types: &[&str]) -> Self {
+        self.content_types = content_types.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// Retry network errors, 429 and 5xx responses up to `retries` times
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Delay before the first retry; doubled for each further attempt
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Timeout for each download attempt
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send downloads with `client`, e.g. one configured for a proxy or mTLS
+    ///
+    /// The download [`timeout`](Self::timeout) still applies to each attempt.
+    /// No `Authorization` header is added.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Write payloads under `dir` and skip unchanged media on later runs
+    ///
+    /// See the [module docs](self) for the layout.
+    pub fn store_in(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.store = Some(dir.into());
+        self
+    }
+
+    /// Download `items`, returning one outcome per item in the same order
+    ///
+    /// Individual failures are reported per item. The call itself only fails
+    /// when the HTTP client cannot be created or the store manifest cannot
+    /// be read or written.
+    pub async fn download(&self, items: &[MediaItem]) -> Result<Vec<MediaDownload>> {
+        let http = match &self.http_client {
+            Some(client) => client.clone(),
+            None => HttpClientConfig::default().build(self.timeout)?,
+        };
+        let mut manifest = match &self.store {
+            Some(dir) => read_manifest(dir)?,
+            None => BTreeMap::new(),
+        };
+
+        let mut results: Vec<Option<Result<DownloadedMedia>>> = Vec::with_capacity(items.len());
+        let mut pending = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            match (&self.store, manifest.get(&item.media_key)) {
+                (Some(dir), Some(entry))
+                    if entry.is_current(item) && dir.join(&entry.path).is_file() =>
+                {
+                    results.push(Some(Ok(DownloadedMedia {
+                        status: MediaStatus::Unchanged,
+                        content_type: entry.content_type.clone(),
+                        size: entry.size,
+                        sha256: entry.sha256.clone(),
+                        data: None,
+                        path: Some(dir.join(&entry.path)),
+                    })));
+                }
+                _ => {
+                    results.push(None);
+                    pending.push(index);
+                }
+            }
+        }
+
+        let this = Arc::new(self.clone());
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut queue = pending.into_iter();
+        loop {
+            while tasks.len() < self.concurrency {
+                let Some(index) = queue.next() else { break };
+                let (this, http, item) = (this.clone(), http.clone(), items[index].clone());
+                tasks.spawn(async move { (index, this.fetch(&http, &item).await) });
+            }
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            // A panicked task loses its index; its item is reported below as
+            // not completed, and the downloads saved so far still reach the
+            // manifest
+            let (index, result) = match joined {
+                Ok(joined) => joined,
+                Err(e) => {
+                    tracing::warn!("Download task failed: {}", e);
+                    continue;
+                }
+            };
+
+            let result = match (&self.store, result) {
+                (Some(dir), Ok((content_type, body))) => {
+                    self.save(dir, &items[index], &content_type, body, &mut manifest)
+                }
+                (None, Ok((content_type, body))) => Ok(DownloadedMedia {
+                    status: MediaStatus::Downloaded,
+                    content_type,
+                    size: body.len() as u64,
+                    sha256: sha256_hex(&body),
+                    data: Some(body),
+                    path: None,
+                }),
+                (_, Err(e)) => Err(e),
+            };
+            results[index] = Some(result);
+        }
+
+        if let Some(dir) = &self.store {
+            write_manifest(dir, &manifest)?;
+        }
+
+        Ok(items
+            .iter()
+            .cloned()
+            .zip(results)
+            .map(|(item, result)| MediaDownload {
+                item,
+                result: result.unwrap_or_else(|| {
+                    Err(ResoError::Network("Download did not complete".to_string()))
+                }),
+            })
+            .collect())
+    }
+
+    /// Fetch one payload with retries, returning its content type and bytes
+    async fn fetch(&self, http: &reqwest::Client, item: &MediaItem) -> Result<(String, Vec<u8>)> {
+        let mut attempt = 0;
+        loop {
+            match self.fetch_once(http, item).await {
+                Err((e, true)) if attempt < self.retries => {
+                    let delay = self.retry_delay * 2u32.saturating_pow(attempt);
+                    tracing::debug!(
+                        "Retrying {} in {:?} after error: {}",
+                        item.media_url,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err((e, _)) => return Err(e),
+                Ok(payload) => return Ok(payload),
+            }
+        }
+    }
+
+    /// One attempt; errors carry whether they are worth retrying
+    async fn fetch_once(
+        &self,
+        http: &reqwest::Client,
+        item: &MediaItem,
+    ) -> std::result::Result<(String, Vec<u8>), (ResoError, bool)> {
+        let network = |e: reqwest::Error| {
+            (
+                ResoError::Network(format!("Failed to download {}: {}", item.media_url, e)),
+                true,
+            )
+        };
+
+        let mut response = http
+            .get(&item.media_url)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(network)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let retry = status.as_u16() == 429 || status.is_server_error();
+            return Err((ResoError::from_status(status.as_u16(), &body), retry));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .or(item.mime_type.as_deref())
+            .map(|t| {
+                t.split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .unwrap_or_default();
+        if !self.allows(&content_type) {
+            return Err((
+                ResoError::Media(format!(
+                    "{} has content type '{}', which is not allowed",
+                    item.media_url, content_type
+                )),
+                false,
+            ));
+        }
+
+        let too_large = || {
+            (
+                ResoError::Media(format!(
+                    "{} is larger than {} bytes",
+                    item.media_url, self.max_bytes
+                )),
+                false,
+            )
+        };
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_bytes)
+        {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(network)? {
+            if (body.len() + chunk.len()) as u64 > self.max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok((content_type, body))
+    }
+
+    fn allows(&self, content_type: &str) -> bool {
+        self.content_types.is_empty()
+            || self.content_types.iter().any(|allowed| {
+                allowed == "*/*"
+                    || allowed.eq_ignore_ascii_case(content_type)
+                    || allowed.strip_suffix("/*").is_some_and(|prefix| {
+                        content_type
+                            .split_once('/')
+                            .is_some_and(|(kind, _)| kind.eq_ignore_ascii_case(prefix))
+                    })
+            })
+    }
+
+    /// Write a payload into the store and record it in the manifest
+    fn save(
+        &self,
+        dir: &Path,
+        item: &MediaItem,
+        content_type: &str,
+        body: Vec<u8>,
+        manifest: &mut BTreeMap<String, ManifestEntry>,
+    ) -> Result<DownloadedMedia> {
+        let folder = sanitize(item.resource_record_key.as_deref().unwrap_or("media"));
+        let relative = format!(
+            "{}/{}.{}",
+            folder,
+            sanitize(&item.media_key),
+            extension(content_type)
+        );
+        let path = dir.join(&relative);
+
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(dir.join(&folder))?;
+            let temp = path.with_extension("part");
+            std::fs::write(&temp, &body)?;
+            std::fs::rename(&temp, &path)
+        };
+        write()
+            .map_err(|e| ResoError::Media(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        let entry = ManifestEntry {
+            media_url: item.media_url.clone(),
+            modification_timestamp: item.modification_timestamp.clone(),
+            path: relative,
+            content_type: content_type.to_string(),
+            size: body.len() as u64,
+            sha256: sha256_hex(&body),
+        };
+        if let Some(previous) = manifest.get(&item.media_key) {
+            // A new content type means a new file name; drop the old file
+            if previous.path != entry.path {
+                let _ = std::fs::remove_file(dir.join(&previous.path));
+            }
+        }
+        let downloaded = DownloadedMedia {
+            status: MediaStatus::Downloaded,
+            content_type: entry.content_type.clone(),
+            size: entry.size,
+            sha256: entry.sha256.clone(),
+            data: None,
+            path: Some(path),
+        };
+        manifest.insert(item.media_key.clone(), entry);
+        Ok(downloaded)
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Keep a key usable as a file name
+fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match cleaned.trim_matches('.') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// File extension for a content type
+fn extension(content_type: &str) -> &'static str {
+    match content_type {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/tiff" => "tif",
+        "image/heic" => "heic",
+        "image/svg+xml" => "svg",
+        "video/mp4" => "mp4",
+        "video/quicktime" => "mov",
+        "application/pdf" => "pdf",
+        _ => "bin",
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<BTreeMap<String, ManifestEntry>> {
+    let path = dir.join(MEDIA_MANIFEST);
+    match std::fs::read(&path) {
+        Ok(contents) => serde_json::from_slice(&contents).map_err(|e| {
+            ResoError::Parse(format!("Invalid media manifest {}: {}", path.display(), e))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(ResoError::Media(format!(
+            "Failed to read {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+fn write_manifest(dir: &Path, manifest: &BTreeMap<String, ManifestEntry>) -> Result<()> {
+    let path = dir.join(MEDIA_MANIFEST);
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_vec_pretty(manifest)?)?;
+        std::fs::rename(&temp, &path)
+    };
+    write().map_err(|e| ResoError::Media(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_records_sorts_by_order() {
+        let records = vec![
+            json!({"MediaKey": "c", "MediaURL": "https://cdn/c.jpg"}),
+            json!({"MediaKey": "b", "MediaURL": "https://cdn/b.jpg", "Order": 2}),
+            json!({"MediaKey": "x", "Order": 0}),
+            json!({"MediaKey": "a", "MediaURL": "https://cdn/a.jpg", "Order": "1"}),
+        ];
+        let keys: Vec<String> = MediaItem::from_records(&records)
+            .into_iter()
+            .map(|item| item.media_key)
+            .collect();
+
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_from_expanded_fills_record_key() {
+        let property = json!({
+            "ListingKey": "L1",
+            "Media": [
+                {"MediaKey": "m1", "MediaURL": "https://cdn/1.jpg", "Order": 1,
+                 "MediaModificationTimestamp": "2025-01-01T00:00:00Z"}
+            ]
+        });
+        let items = MediaItem::from_expanded(&property);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].resource_record_key.as_deref(), Some("L1"));
+        assert_eq!(
+            items[0].modification_timestamp.as_deref(),
+            Some("2025-01-01T00:00:00Z")
+        );
+        assert!(MediaItem::from_expanded(&json!({"ListingKey": "L2"})).is_empty());
+    }
+
+    #[test]
+    fn test_media_query_escapes_quotes() {
+        let query = media_query("Property", "O'Neil").unwrap();
+        assert!(query.to_odata_string().contains("O%27%27Neil"));
+    }
+
+    #[test]
+    fn test_content_type_allow_list() {
+        let downloader = MediaDownloader::new();
+        assert!(downloader.allows("image/jpeg"));
+        assert!(!downloader.allows("text/html"));
+
+        let downloader = downloader.allow_content_types(&["image/*", "application/pdf"]);
+        assert!(downloader.allows("application/pdf"));
+        assert!(!downloader.allows("application/json"));
+        assert!(MediaDownloader::new()
+            .allow_content_types(&[])
+            .allows("text/html"));
+    }
+
+    #[test]
+    fn test_manifest_entry_is_current() {
+        let item = MediaItem::from_record(&json!({
+            "MediaKey": "m1",
+            "MediaURL": "https://cdn/1.jpg",
+            "MediaModificationTimestamp": "2025-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        let entry = ManifestEntry {
+            media_url: "https://cdn/old.jpg".to_string(),
+            modification_timestamp: Some("2025-01-01T00:00:00Z".to_string()),
+            path: "L1/m1.jpg".to_string(),
+            content_type: "image/jpeg".to_string(),
+            size: 1,
+            sha256: String::new(),
+        };
+        assert!(entry.is_current(&item));
+
+        let changed = MediaItem {
+            modification_timestamp: Some("2025-02-01T00:00:00Z".to_string()),
+            ..item.clone()
+        };
+        assert!(!entry.is_current(&changed));
+
+        let untimed = MediaItem {
+            modification_timestamp: None,
+            ..item
+        };
+        let untimed_entry = ManifestEntry {
+            modification_timestamp: None,
+            media_url: "https://cdn/1.jpg".to_string(),
+            ..entry
+        };
+        assert!(untimed_entry.is_current(&untimed));
+    }
+
+    #[test]
+    fn test_sanitize_and_extension() {
+        assert_eq!(sanitize("a/b\\c"), "a_b_c");
+        assert_eq!(sanitize(".."), "_");
+        assert_eq!(extension("image/jpeg"), "jpg");
+        assert_eq!(extension("application/octet-stream"), "bin");
+    }
+}
@@ -0,0 +1,482 @@
+// src/metadata.rs
+
+//! Parsed OData `$metadata` (EDMX) documents
+//!
+//! [`ResoClient::fetch_metadata`](crate::ResoClient::fetch_metadata) returns the
+//! raw XML. [`Metadata::parse`] turns it into entity types, properties, enum
+//! types and entity sets that other parts of the crate (and applications) can
+//! use to drive schema-aware behaviour, such as picking CSV columns.
+//!
+//! # Examples
+//!
+//! ```
+//! # use reso_client::Metadata;
+//! let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+//!   <edmx:DataServices>
+//!     <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+//!       <EntityType Name="Property">
+//!         <Key><PropertyRef Name="ListingKey"/></Key>
+//!         <Property Name="ListingKey" Type="Edm.String" Nullable="false" MaxLength="255"/>
+//!         <Property Name="ListPrice" Type="Edm.Decimal" Precision="14" Scale="2"/>
+//!       </EntityType>
+//!     </Schema>
+//!   </edmx:DataServices>
+//! </edmx:Edmx>"#;
+//!
+//! let metadata = Metadata::parse(xml)?;
+//! let property = metadata.entity_type("Property").unwrap();
+//!
+//! assert_eq!(property.key, vec!["ListingKey"]);
+//! assert_eq!(property.property("ListPrice").unwrap().scale, Some(2));
+//! # Ok::<(), reso_client::ResoError>(())
+//! ```
+
+use crate::error::{ResoError, Result};
+
+/// Parsed `$metadata` document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// Entity types across all schemas
+    pub entity_types: Vec<EntityType>,
+
+    /// Enumeration types across all schemas
+    pub enum_types: Vec<EnumType>,
+
+    /// Entity sets exposed by the entity container
+    pub entity_sets: Vec<EntitySet>,
+}
+
+/// An `EntityType` element (e.g., `Property`, `Member`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityType {
+    /// Namespace of the schema declaring the type
+    pub namespace: String,
+
+    /// Unqualified type name
+    pub name: String,
+
+    /// Names of the key properties
+    pub key: Vec<String>,
+
+    /// Structural properties in document order
+    pub properties: Vec<Property>,
+
+    /// Navigation properties (expandable relationships)
+    pub navigation_properties: Vec<NavigationProperty>,
+}
+
+/// A structural `Property` of an entity type
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Property {
+    /// Field name
+    pub name: String,
+
+    /// Declared type, e.g. `Edm.String` or `Collection(org.reso.metadata.enums.Appliances)`
+    pub type_name: String,
+
+    /// Whether the field may be null (defaults to `true` per OData CSDL)
+    pub nullable: bool,
+
+    /// `MaxLength` facet
+    pub max_length: Option<u32>,
+
+    /// `Precision` facet
+    pub precision: Option<u32>,
+
+    /// `Scale` facet
+    pub scale: Option<u32>,
+
+    /// Lookup from the `RESO.OData.Metadata.LookupName` annotation (Data
+    /// Dictionary 2.0 string lookups)
+    pub lookup_name: Option<String>,
+}
+
+/// A `NavigationProperty` of an entity type
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NavigationProperty {
+    /// Navigation property name
+    pub name: String,
+
+    /// Declared type, e.g. `org.reso.metadata.Member` or `Collection(org.reso.metadata.Media)`
+    pub type_name: String,
+}
+
+/// An `EnumType` element
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnumType {
+    /// Namespace of the schema declaring the type
+    pub namespace: String,
+
+    /// Unqualified type name
+    pub name: String,
+
+    /// Whether members can be combined as flags
+    pub is_flags: bool,
+
+    /// Members in document order
+    pub members: Vec<EnumMember>,
+}
+
+/// A member of an [`EnumType`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnumMember {
+    /// Member name
+    pub name: String,
+
+    /// Explicit member value, if declared
+    pub value: Option<i64>,
+}
+
+/// An `EntitySet` in the entity container
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntitySet {
+    /// Entity set name (the resource name used in URLs)
+    pub name: String,
+
+    /// Qualified entity type name
+    pub entity_type: String,
+}
+
+impl Metadata {
+    /// Parse an EDMX `$metadata` document
+    ///
+    /// Returns a [`ResoError::Parse`] if the document is not well-formed XML.
+    /// Unknown elements are ignored.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|e| ResoError::Parse(format!("Invalid $metadata document: {}", e)))?;
+
+        let mut metadata = Metadata::default();
+        for schema in document.descendants().filter(|n| is_local(n, "Schema")) {
+            let namespace = schema.attribute("Namespace").unwrap_or_default();
+
+            for node in schema.children().filter(|n| n.is_element()) {
+                match node.tag_name().name() {
+                    "EntityType" => metadata
+                        .entity_types
+                        .push(parse_entity_type(namespace, node)),
+                    "EnumType" => metadata.enum_types.push(parse_enum_type(namespace, node)),
+                    "EntityContainer" => {
+                        metadata.entity_sets.extend(
+                            node.children()
+                                .filter(|n| is_local(n, "EntitySet"))
+                                .map(|n| EntitySet {
+                                    name: attr(n, "Name"),
+                                    entity_type: attr(n, "EntityType"),
+                                }),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Find an entity type by unqualified or qualified name
+    pub fn entity_type(&self, name: &str) -> Option<&EntityType> {
+        self.entity_types
+            .iter()
+            .find(|t| t.name == name || t.qualified_name() == name)
+    }
+
+    /// Find an enum type by unqualified or qualified name
+    pub fn enum_type(&self, name: &str) -> Option<&EnumType> {
+        self.enum_types
+            .iter()
+            .find(|t| t.name == name || t.qualified_name() == name)
+    }
+
+    /// Find the entity type behind a resource (entity set) name
+    ///
+    /// Falls back to an entity type with the same name when the document has
+    /// no matching entity set, which is common for RESO servers.
+    pub fn entity_type_for_resource(&self, resource: &str) -> Option<&EntityType> {
+        self.entity_sets
+            .iter()
+            .find(|s| s.name == resource)
+            .and_then(|s| self.entity_type(&s.entity_type))
+            .or_else(|| self.entity_type(resource))
+    }
+}
+
+impl EntityType {
+    /// Namespace-qualified type name
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Find a structural property by name
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Find a navigation property by name
+    pub fn navigation_property(&self, name: &str) -> Option<&NavigationProperty> {
+        self.navigation_properties.iter().find(|p| p.name == name)
+    }
+}
+
+impl Property {
+    /// Whether the property holds a collection of values
+    pub fn is_collection(&self) -> bool {
+        collection_element(&self.type_name).is_some()
+    }
+
+    /// Type of a single value, with any `Collection(...)` wrapper removed
+    pub fn element_type(&self) -> &str {
+        collection_element(&self.type_name).unwrap_or(&self.type_name)
+    }
+}
+
+impl NavigationProperty {
+    /// Whether the relationship points at many entities
+    pub fn is_collection(&self) -> bool {
+        collection_element(&self.type_name).is_some()
+    }
+
+    /// Target entity type, with any `Collection(...)` wrapper removed
+    pub fn target_type(&self) -> &str {
+        collection_element(&self.type_name).unwrap_or(&self.type_name)
+    }
+}
+
+impl EnumType {
+    /// Namespace-qualified type name
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Find a member by name
+    pub fn member(&self, name: &str) -> Option<&EnumMember> {
+        self.members.iter().find(|m| m.name == name)
+    }
+}
+
+fn parse_entity_type(namespace: &str, node: roxmltree::Node) -> EntityType {
+    let mut entity_type = EntityType {
+        namespace: namespace.to_string(),
+        name: attr(node, "Name"),
+        ..Default::default()
+    };
+
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "Key" => entity_type.key.extend(
+                child
+                    .children()
+                    .filter(|n| is_local(n, "PropertyRef"))
+                    .map(|n| attr(n, "Name")),
+            ),
+            "Property" => entity_type.properties.push(Property {
+                name: attr(child, "Name"),
+                type_name: attr(child, "Type"),
+                nullable: child.attribute("Nullable") != Some("false"),
+                max_length: number_attr(child, "MaxLength"),
+                precision: number_attr(child, "Precision"),
+                scale: number_attr(child, "Scale"),
+                lookup_name: child
+                    .children()
+                    .find(|n| {
+                        is_local(n, "Annotation")
+                            && n.attribute("Term") == Some("RESO.OData.Metadata.LookupName")
+                    })
+                    .and_then(|n| n.attribute("String"))
+                    .map(String::from),
+            }),
+            "NavigationProperty" => entity_type.navigation_properties.push(NavigationProperty {
+                name: attr(child, "Name"),
+                type_name: attr(child, "Type"),
+            }),
+            _ => {}
+        }
+    }
+
+    entity_type
+}
+
+fn parse_enum_type(namespace: &str, node: roxmltree::Node) -> EnumType {
+    EnumType {
+        namespace: namespace.to_string(),
+        name: attr(node, "Name"),
+        is_flags: node.attribute("IsFlags") == Some("true"),
+        members: node
+            .children()
+            .filter(|n| is_local(n, "Member"))
+            .map(|n| EnumMember {
+                name: attr(n, "Name"),
+                value: n.attribute("Value").and_then(|v| v.parse().ok()),
+            })
+            .collect(),
+    }
+}
+
+fn is_local(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn attr(node: roxmltree::Node, name: &str) -> String {
+    node.attribute(name).unwrap_or_default().to_string()
+}
+
+fn number_attr(node: roxmltree::Node, name: &str) -> Option<u32> {
+    node.attribute(name).and_then(|v| v.parse().ok())
+}
+
+fn qualify(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+fn collection_element(type_name: &str) -> Option<&str> {
+    type_name
+        .strip_prefix("Collection(")
+        .and_then(|rest| rest.strip_suffix(')'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String" Nullable="false" MaxLength="255"/>
+        <Property Name="ListPrice" Type="Edm.Decimal" Precision="14" Scale="2"/>
+        <Property Name="Appliances" Type="Collection(org.reso.metadata.enums.Appliances)"/>
+        <Property Name="StandardStatus" Type="Edm.String">
+          <Annotation Term="RESO.OData.Metadata.LookupName" String="org.reso.metadata.enums.StandardStatus"/>
+        </Property>
+        <NavigationProperty Name="ListAgent" Type="org.reso.metadata.Member"/>
+        <NavigationProperty Name="Media" Type="Collection(org.reso.metadata.Media)"/>
+      </EntityType>
+      <EntityType Name="Member">
+        <Key><PropertyRef Name="MemberKey"/></Key>
+        <Property Name="MemberKey" Type="Edm.String" Nullable="false"/>
+      </EntityType>
+      <EntityContainer Name="Default">
+        <EntitySet Name="Property" EntityType="org.reso.metadata.Property"/>
+        <EntitySet Name="Agents" EntityType="org.reso.metadata.Member"/>
+      </EntityContainer>
+    </Schema>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata.enums">
+      <EnumType Name="Appliances" IsFlags="true">
+        <Member Name="Dishwasher" Value="1"/>
+        <Member Name="Refrigerator" Value="2"/>
+      </EnumType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+    #[test]
+    fn test_parse_entity_types() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+
+        assert_eq!(metadata.entity_types.len(), 2);
+        let property = metadata.entity_type("Property").unwrap();
+        assert_eq!(property.namespace, "org.reso.metadata");
+        assert_eq!(property.key, vec!["ListingKey"]);
+        assert_eq!(property.properties.len(), 4);
+        assert_eq!(property.navigation_properties.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_property_facets() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+        let property = metadata.entity_type("Property").unwrap();
+
+        let key = property.property("ListingKey").unwrap();
+        assert!(!key.nullable);
+        assert_eq!(key.max_length, Some(255));
+
+        let price = property.property("ListPrice").unwrap();
+        assert!(price.nullable);
+        assert_eq!(price.precision, Some(14));
+        assert_eq!(price.scale, Some(2));
+        assert_eq!(price.lookup_name, None);
+
+        let status = property.property("StandardStatus").unwrap();
+        assert_eq!(
+            status.lookup_name.as_deref(),
+            Some("org.reso.metadata.enums.StandardStatus")
+        );
+    }
+
+    #[test]
+    fn test_collection_types() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+        let property = metadata.entity_type("Property").unwrap();
+
+        let appliances = property.property("Appliances").unwrap();
+        assert!(appliances.is_collection());
+        assert_eq!(
+            appliances.element_type(),
+            "org.reso.metadata.enums.Appliances"
+        );
+        assert!(!property.property("ListPrice").unwrap().is_collection());
+
+        let media = property.navigation_property("Media").unwrap();
+        assert!(media.is_collection());
+        assert_eq!(media.target_type(), "org.reso.metadata.Media");
+        assert!(!property
+            .navigation_property("ListAgent")
+            .unwrap()
+            .is_collection());
+    }
+
+    #[test]
+    fn test_parse_enum_types() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+        let appliances = metadata
+            .enum_type("org.reso.metadata.enums.Appliances")
+            .unwrap();
+
+        assert!(appliances.is_flags);
+        assert_eq!(appliances.members.len(), 2);
+        assert_eq!(appliances.member("Refrigerator").unwrap().value, Some(2));
+        assert!(metadata.enum_type("Appliances").is_some());
+    }
+
+    #[test]
+    fn test_entity_type_for_resource() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+
+        assert_eq!(
+            metadata.entity_type_for_resource("Agents").unwrap().name,
+            "Member"
+        );
+        // Falls back to the type name when no entity set matches
+        assert_eq!(
+            metadata.entity_type_for_resource("Member").unwrap().name,
+            "Member"
+        );
+        assert!(metadata.entity_type_for_resource("Office").is_none());
+    }
+
+    #[test]
+    fn test_qualified_lookup() {
+        let metadata = Metadata::parse(SAMPLE).unwrap();
+        let member = metadata.entity_type("org.reso.metadata.Member").unwrap();
+        assert_eq!(member.qualified_name(), "org.reso.metadata.Member");
+    }
+
+    #[test]
+    fn test_parse_invalid_xml() {
+        let result = Metadata::parse("<edmx:Edmx>");
+        assert!(matches!(result, Err(ResoError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_empty_document() {
+        let metadata = Metadata::parse("<Edmx/>").unwrap();
+        assert_eq!(metadata, Metadata::default());
+    }
+}
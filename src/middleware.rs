@@ -0,0 +1,220 @@
+// src/middleware.rs
+
+//! Request middleware
+//!
+//! Every request the client sends to the RESO server passes through a chain
+//! of [`Middleware`]s before reaching the network. A middleware sees the
+//! outgoing [`Request`], may change it, and either passes it on with
+//! [`Next::run`] or answers it itself. This is the place for request
+//! signing, custom headers, logging, metrics and fault injection.
+//!
+//! # Order
+//!
+//! The chain runs outermost first:
+//!
+//! 1. retries, if a [`RetryPolicy`](crate::RetryPolicy) is configured
+//! 2. rate limiting, if a [`RateLimiter`](crate::RateLimiter) is configured
+//! 3. authentication: the bearer token or OAuth client credentials
+//! 4. middlewares added with
+//!    [`ClientConfig::with_middleware`](crate::ClientConfig::with_middleware),
+//!    in the order they were added
+//! 5. a custom [`Transport`], if one is set with
+//!    [`ClientConfig::with_transport`](crate::ClientConfig::with_transport)
+//!
+//! Custom middlewares therefore run once per attempt and see the final
+//! headers, including `Authorization`. Responses built by a middleware go
+//! back through the retry and authentication layers like real ones.
+//!
+//! Token requests to an OAuth token endpoint and fixture replay (see
+//! [`fixtures`](crate::fixtures)) do not pass through the chain.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use reso_client::middleware::{Middleware, Next, Request, Response};
+//! use reso_client::{async_trait, ClientConfig, Result};
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//!
+//! /// Counts requests and tags them with a client name
+//! #[derive(Default)]
+//! struct Metrics {
+//!     requests: AtomicU64,
+//! }
+//!
+//! #[async_trait]
+//! impl Middleware for Metrics {
+//!     async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response> {
+//!         self.requests.fetch_add(1, Ordering::Relaxed);
+//!         request
+//!             .headers_mut()
+//!             .insert("X-Client-Name", "listing-sync".parse().unwrap());
+//!         next.run(request).await
+//!     }
+//! }
+//!
+//! let config = ClientConfig::from_env()?.with_middleware(Metrics::default());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::transport::Transport;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub use reqwest::{Request, Response};
+
+/// A step in the request chain
+///
+/// Build a response without calling `next` (for example for fault
+/// injection) by converting an [`http::Response`]:
+///
+/// ```
+/// # use reso_client::middleware::Response;
+/// let response = Response::from(
+///     http::Response::builder().status(503).body("unavailable").unwrap(),
+/// );
+/// assert_eq!(response.status(), 503);
+/// ```
+#[async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    /// Handle a request, usually by passing it on with `next.run(request)`
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response>;
+}
+
+/// The rest of the chain after the current middleware
+///
+/// `Next` is `Copy`, so a middleware may run it several times, e.g. to retry
+/// a cloned request.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    http_client: &'a reqwest::Client,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        http_client: &'a reqwest::Client,
+        middlewares: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Self {
+            http_client,
+            middlewares,
+        }
+    }
+
+    /// Pass the request to the next middleware, or send it if none is left
+    pub async fn run(self, request: Request) -> Result<Response> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => {
+                middleware
+                    .handle(request, Next::new(self.http_client, rest))
+                    .await
+            }
+            None => self
+                .http_client
+                .execute(request)
+                .await
+                .map_err(|e| ResoError::Network(e.to_string())),
+        }
+    }
+}
+
+/// Sets a fixed bearer token
+pub(crate) struct BearerAuth {
+    pub(crate) token: String,
+}
+
+#[async_trait]
+impl Middleware for BearerAuth {
+    async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response> {
+        set_bearer(&mut request, &self.token)?;
+        next.run(request).await
+    }
+}
+
+/// Hands requests to a custom transport instead of the network
+pub(crate) struct SendVia(pub(crate) Arc<dyn Transport>);
+
+#[async_trait]
+impl Middleware for SendVia {
+    async fn handle(&self, request: Request, _: Next<'_>) -> Result<Response> {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(<[u8]>::to_vec)
+            .unwrap_or_default();
+        let mut http_request = http::Request::new(body);
+        *http_request.method_mut() = request.method().clone();
+        *http_request.uri_mut() = request
+            .url()
+            .as_str()
+            .parse()
+            .map_err(|e| ResoError::Config(format!("Invalid request URL: {}", e)))?;
+        *http_request.headers_mut() = request.headers().clone();
+
+        Ok(Response::from(self.0.send(http_request).await?))
+    }
+}
+
+pub(crate) fn set_bearer(request: &mut Request, token: &str) -> Result<()> {
+    let value = format!("Bearer {}", token)
+        .parse()
+        .map_err(|_| ResoError::Config("Token contains invalid header characters".into()))?;
+    request
+        .headers_mut()
+        .insert(reqwest::header::AUTHORIZATION, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every request with a fixed status
+    struct Status(u16);
+
+    #[async_trait]
+    impl Middleware for Status {
+        async fn handle(&self, _: Request, _: Next<'_>) -> Result<Response> {
+            Ok(Response::from(
+                http::Response::builder()
+                    .status(self.0)
+                    .body(String::new())
+                    .unwrap(),
+            ))
+        }
+    }
+
+    /// Records the Authorization header it sees
+    struct Capture(std::sync::Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl Middleware for Capture {
+        async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response> {
+            let auth = request.headers()[reqwest::header::AUTHORIZATION]
+                .to_str()
+                .unwrap()
+                .to_string();
+            self.0.lock().unwrap().push(auth);
+            next.run(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_runs_in_order() {
+        let capture = Arc::new(Capture(Default::default()));
+        let chain: Vec<Arc<dyn Middleware>> = vec![
+            Arc::new(BearerAuth {
+                token: "secret".into(),
+            }),
+            capture.clone(),
+            Arc::new(Status(418)),
+        ];
+        let http_client = reqwest::Client::new();
+        let request = http_client.get("http://localhost:1/").build().unwrap();
+
+        let response = Next::new(&http_client, &chain).run(request).await.unwrap();
+        assert_eq!(response.status(), 418);
+        assert_eq!(*capture.0.lock().unwrap(), ["Bearer secret"]);
+    }
+}
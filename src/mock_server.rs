@@ -0,0 +1,747 @@
+// src/mock_server.rs
+
+//! In-process mock RESO server for tests
+//!
+//! Available with the `mock-server` feature. [`MockResoServer`] starts a local
+//! HTTP server seeded with JSON records and answers queries the way a RESO Web
+//! API server would, so code built on this crate can be tested against real
+//! query behavior without network access or hand-written stubs.
+//!
+//! Supported requests:
+//!
+//! - `GET /{Resource}` with `$filter`, `$select`, `$orderby`, `$top`, `$skip`
+//!   and `$count=true`; results are paged with `@odata.nextLink` when
+//!   [`max_page_size`](MockResoServerBuilder::max_page_size) is set
+//! - `GET /{Resource}/$count` with `$filter`
+//! - `GET /{Resource}('key')` with `$select`
+//! - `GET /$metadata`, either supplied or generated from the records
+//! - `GET /{Resource}/replication` with `$filter`, `$select` and `$top`, paged
+//!   through `next` headers
+//!
+//! `$expand` and `$apply` are accepted and ignored. Malformed queries get a
+//! 400 response with an OData error body, unknown resources and keys a 404.
+//!
+//! `$filter` supports comparisons (including `in` and `has`), `and`, `or`,
+//! `not`, the common string and date functions, and `any`/`all` over
+//! collections. Date-time values are compared as instants.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{MockResoServer, QueryBuilder};
+//! # use serde_json::json;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = MockResoServer::builder()
+//!     .resource(
+//!         "Property",
+//!         "ListingKey",
+//!         vec![
+//!             json!({"ListingKey": "1", "City": "Austin", "ListPrice": 450000}),
+//!             json!({"ListingKey": "2", "City": "Dallas", "ListPrice": 325000}),
+//!         ],
+//!     )
+//!     .start()
+//!     .await;
+//!
+//! let client = server.client()?;
+//! let query = QueryBuilder::new("Property")
+//!     .filter("ListPrice gt 400000")
+//!     .build()?;
+//! let results = client.execute(&query).await?;
+//! assert_eq!(results["value"][0]["City"], "Austin");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::{ClientConfig, ResoClient};
+use crate::error::{ResoError, Result};
+use crate::filter::{self, Expr};
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// Token expected by [`MockResoServer::client_config`]
+const DEFAULT_TOKEN: &str = "mock-token";
+
+/// Replication page size when the request has no `$top`
+const REPLICATION_PAGE_SIZE: usize = 2000;
+
+/// Key field the mock server assumes for a resource
+///
+/// `ListingKey` for `Property`, `{Resource}Key` otherwise (`MemberKey`,
+/// `OfficeKey`, `MediaKey`, ...).
+pub fn default_key(resource: &str) -> String {
+    match resource {
+        "Property" => "ListingKey".to_string(),
+        _ => format!("{}Key", resource),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockResource {
+    key: String,
+    records: Vec<JsonValue>,
+}
+
+/// Builder for [`MockResoServer`]
+#[derive(Debug, Clone, Default)]
+pub struct MockResoServerBuilder {
+    resources: BTreeMap<String, MockResource>,
+    metadata: Option<String>,
+    max_page_size: Option<usize>,
+    token: Option<String>,
+    dataset_id: Option<String>,
+}
+
+impl MockResoServerBuilder {
+    /// Serve `records` as `resource`, identified by the `key` field
+    pub fn resource(
+        mut self,
+        resource: impl Into<String>,
+        key: impl Into<String>,
+        records: Vec<JsonValue>,
+    ) -> Self {
+        self.resources.insert(
+            resource.into(),
+            MockResource {
+                key: key.into(),
+                records,
+            },
+        );
+        self
+    }
+
+    /// Serve records read from a JSON file as `resource`
+    ///
+    /// The file holds either an array of records or an OData response with a
+    /// `value` array. The key field is [`default_key`] for the resource.
+    pub fn resource_from_file(
+        self,
+        resource: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let resource = resource.into();
+        let records = read_records(path.as_ref())?;
+        let key = default_key(&resource);
+        Ok(self.resource(resource, key, records))
+    }
+
+    /// Serve every `{Resource}.json` file in `dir` as that resource
+    ///
+    /// Equivalent to calling
+    /// [`resource_from_file`](Self::resource_from_file) for each file.
+    pub fn fixtures_dir(mut self, dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| ResoError::Config(format!("Failed to read {}: {}", dir.display(), e)))?;
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            if let Some(resource) = path.file_stem().and_then(|s| s.to_str()) {
+                self = self.resource_from_file(resource.to_string(), &path)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Serve this document at `$metadata` instead of a generated one
+    pub fn metadata(mut self, xml: impl Into<String>) -> Self {
+        self.metadata = Some(xml.into());
+        self
+    }
+
+    /// Page standard query results, returning `@odata.nextLink` links
+    pub fn max_page_size(mut self, size: usize) -> Self {
+        self.max_page_size = Some(size.max(1));
+        self
+    }
+
+    /// Require this bearer token; other requests get 401
+    ///
+    /// By default any token is accepted.
+    pub fn require_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Serve resources under `/{dataset_id}/` as well as the root
+    pub fn dataset_id(mut self, dataset_id: impl Into<String>) -> Self {
+        self.dataset_id = Some(dataset_id.into());
+        self
+    }
+
+    /// Start the server on a random local port
+    pub async fn start(self) -> MockResoServer {
+        let server = MockServer::start().await;
+        let metadata = self
+            .metadata
+            .clone()
+            .unwrap_or_else(|| generate_metadata(&self.resources));
+        let responder = Responder {
+            state: Arc::new(State {
+                resources: self.resources,
+                uri: server.uri(),
+                metadata,
+                max_page_size: self.max_page_size,
+                token: self.token.clone(),
+                dataset_id: self.dataset_id.clone(),
+            }),
+        };
+        Mock::given(any())
+            .respond_with(responder)
+            .mount(&server)
+            .await;
+
+        MockResoServer {
+            server,
+            token: self.token,
+            dataset_id: self.dataset_id,
+        }
+    }
+}
+
+/// A running mock RESO server
+///
+/// The server shuts down when dropped.
+pub struct MockResoServer {
+    server: MockServer,
+    token: Option<String>,
+    dataset_id: Option<String>,
+}
+
+impl std::fmt::Debug for MockResoServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockResoServer")
+            .field("uri", &self.server.uri())
+            .field("dataset_id", &self.dataset_id)
+            .finish()
+    }
+}
+
+impl MockResoServer {
+    /// Create a builder
+    pub fn builder() -> MockResoServerBuilder {
+        MockResoServerBuilder::default()
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:54321`
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Client configuration pointing at this server
+    ///
+    /// Uses the required token if one was set, and the dataset ID if one was
+    /// set.
+    pub fn client_config(&self) -> ClientConfig {
+        let token = self.token.as_deref().unwrap_or(DEFAULT_TOKEN);
+        let config = ClientConfig::new(self.uri(), token);
+        match &self.dataset_id {
+            Some(dataset_id) => config.with_dataset_id(dataset_id),
+            None => config,
+        }
+    }
+
+    /// Client pointing at this server
+    pub fn client(&self) -> Result<ResoClient> {
+        ResoClient::with_config(self.client_config())
+    }
+
+    /// Path and query of every request received so far, in order
+    pub async fn received_requests(&self) -> Vec<String> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|request| match request.url.query() {
+                Some(query) => format!("{}?{}", request.url.path(), query),
+                None => request.url.path().to_string(),
+            })
+            .collect()
+    }
+}
+
+fn read_records(path: &Path) -> Result<Vec<JsonValue>> {
+    let contents = std::fs::read(path)
+        .map_err(|e| ResoError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+    let json: JsonValue = serde_json::from_slice(&contents)
+        .map_err(|e| ResoError::Parse(format!("Invalid JSON in {}: {}", path.display(), e)))?;
+
+    match json {
+        JsonValue::Array(records) => Ok(records),
+        JsonValue::Object(mut object) => match object.remove("value") {
+            Some(JsonValue::Array(records)) => Ok(records),
+            _ => Err(ResoError::Parse(format!(
+                "{} has no \"value\" array",
+                path.display()
+            ))),
+        },
+        _ => Err(ResoError::Parse(format!(
+            "{} must contain an array of records",
+            path.display()
+        ))),
+    }
+}
+
+struct State {
+    /// Base URL for links; the request URL does not carry the port
+    uri: String,
+    resources: BTreeMap<String, MockResource>,
+    metadata: String,
+    max_page_size: Option<usize>,
+    token: Option<String>,
+    dataset_id: Option<String>,
+}
+
+struct Responder {
+    state: Arc<State>,
+}
+
+/// A 4xx response with an OData error body
+fn odata_error(status: u16, code: &str, message: impl Into<String>) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({
+        "error": { "code": code, "message": message.into() }
+    }))
+}
+
+fn bad_request(message: String) -> ResponseTemplate {
+    odata_error(400, "BadRequest", message)
+}
+
+/// An error response, boxed to keep results small
+type Failure = Box<ResponseTemplate>;
+
+fn invalid(message: String) -> Failure {
+    Box::new(bad_request(message))
+}
+
+impl Respond for Responder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let state = &self.state;
+
+        if let Some(token) = &state.token {
+            let expected = format!("Bearer {}", token);
+            let authorized = request
+                .headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v == expected);
+            if !authorized {
+                return odata_error(401, "Unauthorized", "Invalid or missing bearer token");
+            }
+        }
+        if request.method.as_str() != "GET" {
+            return odata_error(405, "MethodNotAllowed", "Only GET is supported");
+        }
+
+        let mut path = request.url.path().trim_start_matches('/');
+        let mut base = state.uri.clone();
+        if let Some(dataset_id) = &state.dataset_id {
+            if let Some(rest) = path
+                .strip_prefix(dataset_id.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                path = rest;
+                base = format!("{}/{}", base, dataset_id);
+            }
+        }
+        let path = urlencoding::decode(path)
+            .map(|p| p.into_owned())
+            .unwrap_or_else(|_| path.to_string());
+
+        let mut values = HashMap::new();
+        for (name, value) in request.url.query_pairs() {
+            values
+                .entry(name.into_owned())
+                .or_insert_with(|| value.into_owned());
+        }
+        let params = Params { values, request };
+
+        if path == "$metadata" {
+            return ResponseTemplate::new(200)
+                .insert_header("content-type", "application/xml")
+                .set_body_string(state.metadata.clone());
+        }
+
+        let (segment, operation) = match path.split_once('/') {
+            Some((segment, operation)) => (segment, Some(operation)),
+            None => (path.as_str(), None),
+        };
+        let (name, key) = match segment.split_once('(') {
+            Some((name, rest)) => match rest.strip_suffix(')') {
+                Some(key) => (name, Some(key.trim_matches('\''))),
+                None => return bad_request(format!("Malformed key in {}", segment)),
+            },
+            None => (segment, None),
+        };
+        let Some(resource) = state.resources.get(name) else {
+            return odata_error(404, "NotFound", format!("Resource not found: {}", name));
+        };
+
+        let result = match (key, operation) {
+            (Some(key), None) => state.by_key(resource, key, &params),
+            (None, None) => state.query(name, resource, &params, &base),
+            (None, Some("$count")) => state.count(resource, &params),
+            (None, Some("replication")) => state.replication(name, resource, &params, &base),
+            _ => Err(Box::new(odata_error(
+                404,
+                "NotFound",
+                format!("Unsupported path: /{}", path),
+            ))),
+        };
+        result.unwrap_or_else(|error| *error)
+    }
+}
+
+/// Decoded query string parameters
+struct Params<'a> {
+    values: HashMap<String, String>,
+    request: &'a Request,
+}
+
+impl Params<'_> {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn number(&self, name: &str) -> std::result::Result<Option<usize>, Failure> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid {}: {}", name, value)))
+            })
+            .transpose()
+    }
+
+    fn select(&self) -> Option<Vec<String>> {
+        self.get("$select").map(|fields| {
+            fields
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect()
+        })
+    }
+
+    /// This request's URL with `$skiptoken` replaced
+    fn with_skiptoken(&self, base: &str, path: &str, skiptoken: usize) -> String {
+        let mut query: Vec<String> = self
+            .request
+            .url
+            .query_pairs()
+            .filter(|(k, _)| k != "$skiptoken")
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(&v)))
+            .collect();
+        query.push(format!("$skiptoken={}", skiptoken));
+        format!("{}/{}?{}", base, path, query.join("&"))
+    }
+}
+
+type Response = std::result::Result<ResponseTemplate, Failure>;
+
+impl State {
+    /// Records matching `$filter`, sorted by `$orderby`
+    fn matching<'a>(
+        &self,
+        resource: &'a MockResource,
+        params: &Params,
+    ) -> std::result::Result<Vec<&'a JsonValue>, Failure> {
+        let filter = params
+            .get("$filter")
+            .map(Expr::parse)
+            .transpose()
+            .map_err(invalid)?;
+        let mut records: Vec<&JsonValue> = resource
+            .records
+            .iter()
+            .filter(|record| filter.as_ref().is_none_or(|f| f.matches(record)))
+            .collect();
+
+        if let Some(orderby) = params.get("$orderby") {
+            let keys = filter::parse_orderby(orderby).map_err(invalid)?;
+            records.sort_by(|a, b| {
+                keys.iter()
+                    .map(|(path, descending)| {
+                        let null = JsonValue::Null;
+                        let a = filter::lookup(a, path).unwrap_or(&null);
+                        let b = filter::lookup(b, path).unwrap_or(&null);
+                        let ordering = filter::order(a, b);
+                        if *descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        }
+                    })
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        Ok(records)
+    }
+
+    fn query(&self, name: &str, resource: &MockResource, params: &Params, base: &str) -> Response {
+        let matching = self.matching(resource, params)?;
+        let total = matching.len();
+        let skip = params.number("$skip")?.unwrap_or(0);
+        let top = params.number("$top")?.unwrap_or(usize::MAX);
+        let results: Vec<&JsonValue> = matching.into_iter().skip(skip).take(top).collect();
+
+        let offset = params.number("$skiptoken")?.unwrap_or(0);
+        let page_size = self.max_page_size.unwrap_or(usize::MAX);
+        let select = params.select();
+        let page: Vec<JsonValue> = results
+            .iter()
+            .skip(offset)
+            .take(page_size)
+            .map(|record| project(record, select.as_deref()))
+            .collect();
+
+        let mut body = Map::new();
+        body.insert(
+            "@odata.context".to_string(),
+            json!(format!("{}/$metadata#{}", base, name)),
+        );
+        if params.get("$count") == Some("true") {
+            body.insert("@odata.count".to_string(), json!(total));
+        }
+        body.insert("value".to_string(), JsonValue::Array(page));
+        let next = offset.saturating_add(page_size);
+        if next < results.len() {
+            body.insert(
+                "@odata.nextLink".to_string(),
+                json!(params.with_skiptoken(base, name, next)),
+            );
+        }
+        Ok(ResponseTemplate::new(200).set_body_json(JsonValue::Object(body)))
+    }
+
+    fn count(&self, resource: &MockResource, params: &Params) -> Response {
+        let count = self.matching(resource, params)?.len();
+        Ok(ResponseTemplate::new(200)
+            .insert_header("content-type", "text/plain")
+            .set_body_string(count.to_string()))
+    }
+
+    fn by_key(&self, resource: &MockResource, key: &str, params: &Params) -> Response {
+        let record = resource
+            .records
+            .iter()
+            .find(|record| match record.get(&resource.key) {
+                Some(JsonValue::String(value)) => value == key,
+                Some(JsonValue::Number(value)) => value.to_string() == key,
+                _ => false,
+            })
+            .ok_or_else(|| {
+                Box::new(odata_error(
+                    404,
+                    "NotFound",
+                    format!("No record with key {}", key),
+                ))
+            })?;
+        Ok(ResponseTemplate::new(200).set_body_json(project(record, params.select().as_deref())))
+    }
+
+    fn replication(
+        &self,
+        name: &str,
+        resource: &MockResource,
+        params: &Params,
+        base: &str,
+    ) -> Response {
+        let matching = self.matching(resource, params)?;
+        let page_size = params
+            .number("$top")?
+            .unwrap_or(REPLICATION_PAGE_SIZE)
+            .max(1);
+        let offset = params.number("$skiptoken")?.unwrap_or(0);
+        let select = params.select();
+        let page: Vec<JsonValue> = matching
+            .iter()
+            .skip(offset)
+            .take(page_size)
+            .map(|record| project(record, select.as_deref()))
+            .collect();
+
+        let mut response = ResponseTemplate::new(200).set_body_json(json!({ "value": page }));
+        let next = offset.saturating_add(page_size);
+        if next < matching.len() {
+            let path = format!("{}/replication", name);
+            response = response.insert_header("next", params.with_skiptoken(base, &path, next));
+        }
+        Ok(response)
+    }
+}
+
+/// Keep only the selected fields of a record
+fn project(record: &JsonValue, select: Option<&[String]>) -> JsonValue {
+    match (select, record) {
+        (Some(fields), JsonValue::Object(object)) => JsonValue::Object(
+            fields
+                .iter()
+                .filter_map(|field| object.get(field).map(|v| (field.clone(), v.clone())))
+                .collect(),
+        ),
+        _ => record.clone(),
+    }
+}
+
+/// EDMX type for a sample JSON value
+fn edm_type(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Bool(_) => "Edm.Boolean".to_string(),
+        JsonValue::Number(n) if n.is_f64() => "Edm.Decimal".to_string(),
+        JsonValue::Number(_) => "Edm.Int64".to_string(),
+        JsonValue::String(s) if s.len() == 10 && filter::parse_time(s).is_some() => {
+            "Edm.Date".to_string()
+        }
+        JsonValue::String(s) if s.contains('T') && filter::parse_time(s).is_some() => {
+            "Edm.DateTimeOffset".to_string()
+        }
+        JsonValue::Array(items) => format!(
+            "Collection({})",
+            items
+                .iter()
+                .find(|v| !v.is_null())
+                .map(edm_type)
+                .unwrap_or_else(|| "Edm.String".to_string())
+        ),
+        _ => "Edm.String".to_string(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Minimal EDMX document with one entity type per resource
+///
+/// Property types are inferred from the first non-null value of each field.
+fn generate_metadata(resources: &BTreeMap<String, MockResource>) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+"#,
+    );
+
+    for (name, resource) in resources {
+        let mut fields: BTreeMap<&str, Option<&JsonValue>> = BTreeMap::new();
+        fields.insert(&resource.key, None);
+        for record in &resource.records {
+            if let JsonValue::Object(object) = record {
+                for (field, value) in object {
+                    let sample = fields.entry(field).or_insert(None);
+                    if sample.is_none() && !value.is_null() {
+                        *sample = Some(value);
+                    }
+                }
+            }
+        }
+
+        xml.push_str(&format!(
+            "      <EntityType Name=\"{}\">\n        <Key><PropertyRef Name=\"{}\"/></Key>\n",
+            escape_xml(name),
+            escape_xml(&resource.key)
+        ));
+        for (field, sample) in fields {
+            let edm = sample
+                .map(edm_type)
+                .unwrap_or_else(|| "Edm.String".to_string());
+            let nullable = if field == resource.key {
+                " Nullable=\"false\""
+            } else {
+                ""
+            };
+            xml.push_str(&format!(
+                "        <Property Name=\"{}\" Type=\"{}\"{}/>\n",
+                escape_xml(field),
+                edm,
+                nullable
+            ));
+        }
+        xml.push_str("      </EntityType>\n");
+    }
+
+    xml.push_str("      <EntityContainer Name=\"Default\">\n");
+    for name in resources.keys() {
+        xml.push_str(&format!(
+            "        <EntitySet Name=\"{0}\" EntityType=\"org.reso.metadata.{0}\"/>\n",
+            escape_xml(name)
+        ));
+    }
+    xml.push_str("      </EntityContainer>\n    </Schema>\n  </edmx:DataServices>\n</edmx:Edmx>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Metadata;
+
+    #[test]
+    fn test_default_key() {
+        assert_eq!(default_key("Property"), "ListingKey");
+        assert_eq!(default_key("Member"), "MemberKey");
+    }
+
+    #[test]
+    fn test_project() {
+        let record = json!({"ListingKey": "1", "City": "Austin", "ListPrice": 1});
+        let fields = vec!["City".to_string(), "Missing".to_string()];
+        assert_eq!(project(&record, Some(&fields)), json!({"City": "Austin"}));
+        assert_eq!(project(&record, None), record);
+    }
+
+    #[test]
+    fn test_generated_metadata_parses() {
+        let mut resources = BTreeMap::new();
+        resources.insert(
+            "Property".to_string(),
+            MockResource {
+                key: "ListingKey".to_string(),
+                records: vec![
+                    json!({"ListingKey": "1", "ListPrice": null, "Appliances": ["Range"]}),
+                    json!({
+                        "ListingKey": "2",
+                        "ListPrice": 1.5,
+                        "BedroomsTotal": 3,
+                        "ListingContractDate": "2025-01-01",
+                        "ModificationTimestamp": "2025-01-01T00:00:00Z"
+                    }),
+                ],
+            },
+        );
+
+        let xml = generate_metadata(&resources);
+        let metadata = Metadata::parse(&xml).unwrap();
+        let property = metadata.entity_type("Property").unwrap();
+
+        assert_eq!(property.key, vec!["ListingKey".to_string()]);
+        let type_of = |name: &str| property.property(name).map(|p| p.type_name.clone());
+        assert_eq!(type_of("ListPrice").as_deref(), Some("Edm.Decimal"));
+        assert_eq!(type_of("BedroomsTotal").as_deref(), Some("Edm.Int64"));
+        assert_eq!(type_of("ListingContractDate").as_deref(), Some("Edm.Date"));
+        assert_eq!(
+            type_of("ModificationTimestamp").as_deref(),
+            Some("Edm.DateTimeOffset")
+        );
+        assert_eq!(
+            type_of("Appliances").as_deref(),
+            Some("Collection(Edm.String)")
+        );
+    }
+}
@@ -0,0 +1,543 @@
+// src/options.rs
+
+//! Per-call request options and response headers
+//!
+//! [`ClientConfig::timeout`](crate::ClientConfig::timeout) bounds every
+//! request the client sends. [`RequestOptions`] tightens that for a single
+//! call with a timeout, an absolute deadline and a [`CancellationToken`], so
+//! that a count query can give up after a few seconds while bulk pages get
+//! minutes, and a long replication run can be stopped from another task.
+//!
+//! The same options set the request headers of a call: OData `Prefer`
+//! preferences (`odata.maxpagesize`, `odata.include-annotations`,
+//! `return=minimal`), the `odata.metadata` level in `Accept`,
+//! `Accept-Encoding`, and any custom header. Calls with headers bypass the
+//! response cache. [`ODataResponse`] carries the status and headers of a
+//! response next to its body, including the preferences the server applied.
+//!
+//! A call that runs out of time fails with [`ResoError::Timeout`]; a
+//! cancelled one with [`ResoError::Cancelled`]. Both cover the whole call,
+//! including retries. On the streaming APIs the options apply to each
+//! `next_*` call, and a deadline to the whole run. Records already handed to
+//! a sink are committed page by page, so a cancelled
+//! [`drain_into`](crate::ReplicationStream::drain_into) leaves the checkpoint
+//! at the last complete page.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{CancellationToken, QueryBuilder, ReplicationQueryBuilder, RequestOptions, ResoClient, MemorySink};
+//! # use std::time::Duration;
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let query = QueryBuilder::new("Property").count().build()?;
+//! let quick = RequestOptions::new().with_timeout(Duration::from_secs(5));
+//! let count = client.execute_count_with(&query, &quick).await?;
+//!
+//! // Stop a replication run from elsewhere, e.g. a shutdown signal handler
+//! let token = CancellationToken::new();
+//! let stop = token.clone();
+//! tokio::spawn(async move {
+//!     tokio::time::sleep(Duration::from_secs(3600)).await;
+//!     stop.cancel();
+//! });
+//!
+//! let query = ReplicationQueryBuilder::new("Property").top(2000).build()?;
+//! let mut sink = MemorySink::new();
+//! let result = client
+//!     .replicate(&query)
+//!     .with_options(RequestOptions::new().with_cancellation(token))
+//!     .drain_into(&mut sink)
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```no_run
+//! # use reso_client::{MetadataLevel, QueryBuilder, RequestOptions, ResoClient};
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let query = QueryBuilder::new("Property").build()?;
+//! let options = RequestOptions::new()
+//!     .with_max_page_size(500)
+//!     .with_metadata(MetadataLevel::None)
+//!     .with_header("X-Request-Source", "nightly-sync");
+//!
+//! let response = client.execute_response(&query, &options).await?;
+//! if response.applied_max_page_size() != Some(500) {
+//!     println!("Server pages by {:?}", response.preference_applied());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Options for a single call
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Longest the call may take
+    pub timeout: Option<Duration>,
+
+    /// Point in time by which the call must have finished
+    pub deadline: Option<Instant>,
+
+    /// Token that aborts the call when cancelled
+    pub cancellation: Option<CancellationToken>,
+
+    /// `Prefer: odata.maxpagesize`, the page size asked of the server
+    pub max_page_size: Option<u32>,
+
+    /// `Prefer: odata.include-annotations`, e.g. `"*"` or `"-*"`
+    pub include_annotations: Option<String>,
+
+    /// `Prefer: return=minimal`
+    pub return_minimal: bool,
+
+    /// `odata.metadata` parameter of the JSON `Accept` header
+    pub metadata: Option<MetadataLevel>,
+
+    /// `Accept-Encoding` to send instead of the client's default
+    pub accept_encoding: Option<String>,
+
+    /// Further headers, set after all others
+    pub headers: Vec<(String, String)>,
+}
+
+/// Amount of OData control information asked for in JSON responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataLevel {
+    /// `odata.metadata=minimal`, the server default
+    Minimal,
+    /// `odata.metadata=full`
+    Full,
+    /// `odata.metadata=none`, without `@odata.context` and similar
+    None,
+}
+
+impl MetadataLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetadataLevel::Minimal => "minimal",
+            MetadataLevel::Full => "full",
+            MetadataLevel::None => "none",
+        }
+    }
+}
+
+impl RequestOptions {
+    /// No limits beyond the client configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail the call if it takes longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Fail the call if it has not finished by `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Abort the call when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Ask the server for pages of at most `size` records
+    pub fn with_max_page_size(mut self, size: u32) -> Self {
+        self.max_page_size = Some(size);
+        self
+    }
+
+    /// Ask the server to include the annotations matching `filter`
+    pub fn with_include_annotations(mut self, filter: impl Into<String>) -> Self {
+        self.include_annotations = Some(filter.into());
+        self
+    }
+
+    /// Ask the server for a minimal response
+    pub fn with_return_minimal(mut self) -> Self {
+        self.return_minimal = true;
+        self
+    }
+
+    /// Ask for JSON responses with the given amount of control information
+    pub fn with_metadata(mut self, level: MetadataLevel) -> Self {
+        self.metadata = Some(level);
+        self
+    }
+
+    /// Send `Accept-Encoding: encoding`
+    pub fn with_accept_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.accept_encoding = Some(encoding.into());
+        self
+    }
+
+    /// Send a custom header, replacing any earlier value of the same name
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The `Prefer` header value, if any preference is set
+    pub fn prefer(&self) -> Option<String> {
+        let mut preferences = Vec::new();
+        if let Some(size) = self.max_page_size {
+            preferences.push(format!("odata.maxpagesize={}", size));
+        }
+        if let Some(filter) = &self.include_annotations {
+            preferences.push(format!("odata.include-annotations=\"{}\"", filter));
+        }
+        if self.return_minimal {
+            preferences.push("return=minimal".to_string());
+        }
+        (!preferences.is_empty()).then(|| preferences.join(", "))
+    }
+
+    /// Whether these options change the request headers
+    pub(crate) fn has_headers(&self) -> bool {
+        self.prefer().is_some()
+            || self.metadata.is_some()
+            || self.accept_encoding.is_some()
+            || !self.headers.is_empty()
+    }
+
+    /// The `Accept` header for a call that would otherwise send `accept`
+    pub(crate) fn accept(&self, accept: &str) -> String {
+        match self.metadata {
+            Some(level) if accept == "application/json" => {
+                format!("application/json;odata.metadata={}", level.as_str())
+            }
+            _ => accept.to_string(),
+        }
+    }
+
+    /// Set the headers of these options on `headers`
+    pub(crate) fn apply_headers(&self, headers: &mut HeaderMap) -> Result<()> {
+        let prefer = self.prefer().map(|value| ("Prefer".to_string(), value));
+        let encoding = self
+            .accept_encoding
+            .clone()
+            .map(|value| ("Accept-Encoding".to_string(), value));
+
+        for (name, value) in prefer.iter().chain(&encoding).chain(&self.headers) {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| ResoError::Config(format!("Invalid header name: {}", name)))?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                ResoError::Config(format!("Invalid value for header {}: {}", name, value))
+            })?;
+            headers.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// The earlier of the deadline and the timeout counted from now
+    fn effective_deadline(&self) -> Option<Instant> {
+        let from_timeout = self.timeout.map(|timeout| Instant::now() + timeout);
+        match (from_timeout, self.deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Run `future` within these limits
+    pub(crate) async fn run<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        if self.timeout.is_none() && self.deadline.is_none() && self.cancellation.is_none() {
+            return future.await;
+        }
+        if self.cancellation.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(ResoError::Cancelled);
+        }
+
+        let deadline = self.effective_deadline();
+        let mut future = std::pin::pin!(future);
+        let mut expired = std::pin::pin!(async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                None => std::future::pending().await,
+            }
+        });
+        let mut cancelled = std::pin::pin!(async {
+            match &self.cancellation {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        });
+
+        std::future::poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(ResoError::Cancelled));
+            }
+            if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+            if expired.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(ResoError::Timeout(
+                    "request did not finish before its deadline".into(),
+                )));
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// A response body together with the status and headers it came with
+#[derive(Debug, Clone)]
+pub struct ODataResponse<T> {
+    /// Parsed body
+    pub body: T,
+
+    /// HTTP status code
+    pub status: u16,
+
+    /// Response headers
+    pub headers: HeaderMap,
+}
+
+impl<T> ODataResponse<T> {
+    /// A header value, if present and valid text
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Preferences the server applied, from `Preference-Applied`
+    ///
+    /// Each entry is one preference as sent back, e.g.
+    /// `odata.maxpagesize=500`. Servers that ignore `Prefer` return none.
+    pub fn preference_applied(&self) -> Vec<&str> {
+        self.headers
+            .get_all("Preference-Applied")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(split_preferences)
+            .collect()
+    }
+
+    /// The page size the server applied, if it reported one
+    pub fn applied_max_page_size(&self) -> Option<u32> {
+        self.preference_applied()
+            .into_iter()
+            .find_map(|preference| {
+                let (name, value) = preference.split_once('=')?;
+                if name.trim().eq_ignore_ascii_case("odata.maxpagesize") {
+                    value.trim().trim_matches('"').parse().ok()
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Replace the body, keeping the status and headers
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ODataResponse<U> {
+        ODataResponse {
+            body: f(self.body),
+            status: self.status,
+            headers: self.headers,
+        }
+    }
+}
+
+/// Split a `Prefer`-style header value on commas outside quotes
+fn split_preferences(value: &str) -> Vec<&str> {
+    let mut preferences = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                preferences.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    preferences.push(&value[start..]);
+    preferences
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Cancels calls made with it from any task or thread
+///
+/// Clones share the same state; cancelling one cancels them all, and a
+/// cancelled token stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// A token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every call using this token
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.state.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_enforces_timeout_and_cancellation() {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        };
+
+        let options = RequestOptions::new().with_timeout(Duration::from_millis(10));
+        assert!(matches!(
+            options.run(slow()).await,
+            Err(ResoError::Timeout(_))
+        ));
+
+        let options = RequestOptions::new()
+            .with_timeout(Duration::from_secs(60))
+            .with_deadline(Instant::now() + Duration::from_millis(10));
+        assert!(matches!(
+            options.run(slow()).await,
+            Err(ResoError::Timeout(_))
+        ));
+
+        let token = CancellationToken::new();
+        let options = RequestOptions::new().with_cancellation(token.clone());
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            token.cancel();
+        });
+        assert!(matches!(
+            options.run(slow()).await,
+            Err(ResoError::Cancelled)
+        ));
+        canceller.await.unwrap();
+
+        // A cancelled token fails calls before they start
+        assert!(matches!(
+            options.run(async { Ok(()) }).await,
+            Err(ResoError::Cancelled)
+        ));
+
+        let options = RequestOptions::new().with_timeout(Duration::from_secs(60));
+        assert_eq!(options.run(async { Ok(7) }).await.unwrap(), 7);
+    }
+
+    #[test]
+    fn test_headers_from_options() {
+        let options = RequestOptions::new();
+        assert!(!options.has_headers());
+        assert_eq!(options.prefer(), None);
+        assert_eq!(options.accept("application/json"), "application/json");
+
+        let options = RequestOptions::new()
+            .with_max_page_size(500)
+            .with_include_annotations("*")
+            .with_return_minimal()
+            .with_metadata(MetadataLevel::None)
+            .with_accept_encoding("identity")
+            .with_header("X-Trace", "abc")
+            .with_header("Prefer", "respond-async");
+        assert!(options.has_headers());
+        assert_eq!(
+            options.prefer().unwrap(),
+            "odata.maxpagesize=500, odata.include-annotations=\"*\", return=minimal"
+        );
+        assert_eq!(
+            options.accept("application/json"),
+            "application/json;odata.metadata=none"
+        );
+        // Only JSON requests take the metadata level
+        assert_eq!(options.accept("text/plain"), "text/plain");
+
+        let mut headers = HeaderMap::new();
+        options.apply_headers(&mut headers).unwrap();
+        assert_eq!(headers["Accept-Encoding"], "identity");
+        assert_eq!(headers["X-Trace"], "abc");
+        // Custom headers come last and replace generated ones
+        assert_eq!(headers["Prefer"], "respond-async");
+
+        let options = RequestOptions::new().with_header("Bad Name", "x");
+        assert!(matches!(
+            options.apply_headers(&mut HeaderMap::new()),
+            Err(ResoError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_preference_applied() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "Preference-Applied",
+            HeaderValue::from_static("odata.maxpagesize=500, odata.include-annotations=\"a,b\""),
+        );
+        headers.append(
+            "Preference-Applied",
+            HeaderValue::from_static("return=minimal"),
+        );
+        let response = ODataResponse {
+            body: (),
+            status: 200,
+            headers,
+        };
+
+        assert_eq!(
+            response.preference_applied(),
+            vec![
+                "odata.maxpagesize=500",
+                "odata.include-annotations=\"a,b\"",
+                "return=minimal"
+            ]
+        );
+        assert_eq!(response.applied_max_page_size(), Some(500));
+
+        let response = response.map(|_| 1);
+        assert_eq!(response.body, 1);
+        assert_eq!(
+            response.header("preference-applied").unwrap(),
+            "odata.maxpagesize=500, odata.include-annotations=\"a,b\""
+        );
+    }
+}
@@ -0,0 +1,516 @@
+// src/profile.rs
+
+//! Named feed profiles loaded from a TOML file
+//!
+//! Applications that talk to several MLS feeds describe each one once in a
+//! profile file and build clients by name with
+//! [`ClientConfig::from_profile`](crate::ClientConfig::from_profile).
+//!
+//! ```toml
+//! # Settings shared by every feed; each feed may override them
+//! [defaults]
+//! timeout_secs = 60
+//! retry = { max_retries = 3, initial_backoff_ms = 500 }
+//!
+//! [feeds.crmls]
+//! base_url = "https://api.crmls.org/odata"
+//! resources = ["Property", "Member", "Office"]
+//! rate_limit = { requests_per_second = 2 }
+//! auth = { method = "bearer", token = { env = "CRMLS_TOKEN" } }
+//!
+//! [feeds.bridge]
+//! base_url = "https://api.bridgedataoutput.com/api/v2/OData"
+//! dataset_id = "actris_ref"
+//! rate_limit = { requests_per_minute = 300 }
+//!
+//! [feeds.bridge.auth]
+//! method = "client_credentials"
+//! token_url = "https://api.bridgedataoutput.com/oauth/token"
+//! client_id = "my-client"
+//! client_secret = { file = "/run/secrets/bridge_secret" }
+//! ```
+//!
+//! # Secrets
+//!
+//! Tokens and client secrets are never written in the file itself. They are
+//! references, either `{ env = "VAR" }` for an environment variable or
+//! `{ file = "path" }` for a file whose trimmed contents hold the secret.
+//!
+//! # Locating the file
+//!
+//! [`Profiles::discover`] uses the first of:
+//!
+//! 1. the path in `RESO_PROFILES`
+//! 2. `reso.toml` in the current directory
+//! 3. `$XDG_CONFIG_HOME/reso/profiles.toml`, or `~/.config/reso/profiles.toml`
+//!
+//! # Environment overrides
+//!
+//! For a feed named `crmls`, the variables `RESO_CRMLS_BASE_URL`,
+//! `RESO_CRMLS_TOKEN`, `RESO_CRMLS_DATASET_ID` and `RESO_CRMLS_TIMEOUT`
+//! take precedence over the file. Feed names are upper-cased and any other
+//! character than a letter or digit becomes `_`. An overriding token switches
+//! the feed to bearer authentication.
+
+use crate::auth::ClientCredentials;
+use crate::client::ClientConfig;
+use crate::error::{ResoError, Result};
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A profile file: shared defaults and named feeds
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    /// Settings applied to every feed unless the feed sets them
+    #[serde(default)]
+    pub defaults: FeedProfile,
+
+    /// Feeds by name
+    #[serde(default)]
+    pub feeds: BTreeMap<String, FeedProfile>,
+}
+
+/// Settings for one feed
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeedProfile {
+    /// Base URL of the RESO Web API
+    pub base_url: Option<String>,
+
+    /// Optional dataset ID
+    pub dataset_id: Option<String>,
+
+    /// Request timeout in seconds
+    pub timeout_secs: Option<u64>,
+
+    /// How to authenticate
+    pub auth: Option<AuthProfile>,
+
+    /// Client-side rate limit
+    pub rate_limit: Option<RateLimitProfile>,
+
+    /// Retry policy for transient failures
+    pub retry: Option<RetryProfile>,
+
+    /// Resources this feed serves by default
+    pub resources: Option<Vec<String>>,
+}
+
+/// Authentication method of a feed
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AuthProfile {
+    /// Long-lived bearer token
+    Bearer {
+        /// Reference to the token
+        token: Secret,
+    },
+
+    /// OAuth 2.0 client-credentials grant
+    ClientCredentials {
+        /// Token endpoint URL
+        token_url: String,
+        /// Client identifier
+        client_id: String,
+        /// Reference to the client secret
+        client_secret: Secret,
+        /// Optional space-separated scopes
+        scope: Option<String>,
+    },
+}
+
+/// Reference to a secret value
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    /// Read from an environment variable
+    Env {
+        /// Variable name
+        env: String,
+    },
+
+    /// Read from a file, trimming surrounding whitespace
+    File {
+        /// File path
+        file: PathBuf,
+    },
+
+    /// A value written directly in the file; always rejected
+    Plain(String),
+}
+
+impl Secret {
+    /// The secret value
+    pub fn resolve(&self) -> Result<String> {
+        match self {
+            Secret::Env { env } => std::env::var(env).map_err(|_| {
+                ResoError::Config(format!("Secret environment variable {} not set", env))
+            }),
+            Secret::File { file } => std::fs::read_to_string(file)
+                .map(|s| s.trim().to_string())
+                .map_err(|e| {
+                    ResoError::Config(format!(
+                        "Failed to read secret file {}: {}",
+                        file.display(),
+                        e
+                    ))
+                }),
+            Secret::Plain(_) => Err(ResoError::Config(
+                "Plain-text secrets are not allowed in profiles; use { env = \"VAR\" } or { file = \"path\" }"
+                    .into(),
+            )),
+        }
+    }
+}
+
+/// Client-side rate limit; give one of the two fields
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitProfile {
+    /// Requests allowed per second
+    pub requests_per_second: Option<f64>,
+
+    /// Requests allowed per minute
+    pub requests_per_minute: Option<f64>,
+}
+
+/// Retry policy; unset fields keep the [`RetryPolicy`] defaults
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryProfile {
+    /// Retries after the first attempt
+    pub max_retries: Option<u32>,
+
+    /// Delay before the first retry, in milliseconds
+    pub initial_backoff_ms: Option<u64>,
+
+    /// Longest delay between attempts, in milliseconds
+    pub max_backoff_ms: Option<u64>,
+}
+
+impl Profiles {
+    /// Parse a profile file
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::Profiles;
+    /// let profiles = Profiles::from_toml(r#"
+    ///     [feeds.local]
+    ///     base_url = "http://localhost:8080/odata"
+    ///     auth = { method = "bearer", token = { env = "LOCAL_TOKEN" } }
+    /// "#)?;
+    /// assert_eq!(profiles.names().collect::<Vec<_>>(), ["local"]);
+    /// # Ok::<(), reso_client::ResoError>(())
+    /// ```
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| ResoError::Config(format!("Invalid profile file: {}", e)))
+    }
+
+    /// Read and parse a profile file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            ResoError::Config(format!(
+                "Failed to read profile file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        toml::from_str(&text).map_err(|e| {
+            ResoError::Config(format!("Invalid profile file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Load the profile file from its standard location
+    ///
+    /// See the [module documentation](self) for the search order.
+    pub fn discover() -> Result<Self> {
+        if let Ok(path) = std::env::var("RESO_PROFILES") {
+            return Self::load(path);
+        }
+
+        let local = PathBuf::from("reso.toml");
+        if local.is_file() {
+            return Self::load(local);
+        }
+
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+        if let Some(path) = config_dir.map(|dir| dir.join("reso").join("profiles.toml")) {
+            if path.is_file() {
+                return Self::load(path);
+            }
+        }
+
+        Err(ResoError::Config(
+            "No profile file found; set RESO_PROFILES or create reso.toml".into(),
+        ))
+    }
+
+    /// Feed names, in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.feeds.keys().map(String::as_str)
+    }
+
+    /// Client configuration for the feed called `name`
+    ///
+    /// Merges the feed over the defaults, applies environment overrides and
+    /// resolves secret references.
+    pub fn client_config(&self, name: &str) -> Result<ClientConfig> {
+        let feed = self.feeds.get(name).ok_or_else(|| {
+            ResoError::Config(format!(
+                "Unknown feed profile '{}'; available: {}",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+        let defaults = &self.defaults;
+        let env = |key: &str| std::env::var(format!("RESO_{}_{}", env_name(name), key)).ok();
+
+        let base_url = env("BASE_URL")
+            .or_else(|| feed.base_url.clone())
+            .or_else(|| defaults.base_url.clone())
+            .ok_or_else(|| ResoError::Config(format!("Feed profile '{}' has no base_url", name)))?;
+        let mut config = ClientConfig::new(base_url, "");
+
+        if let Some(dataset_id) = env("DATASET_ID")
+            .or_else(|| feed.dataset_id.clone())
+            .or_else(|| defaults.dataset_id.clone())
+        {
+            config = config.with_dataset_id(dataset_id);
+        }
+
+        let timeout = match env("TIMEOUT") {
+            Some(secs) => Some(secs.parse::<u64>().map_err(|_| {
+                ResoError::Config(format!("Invalid timeout for feed '{}': {}", name, secs))
+            })?),
+            None => feed.timeout_secs.or(defaults.timeout_secs),
+        };
+        if let Some(secs) = timeout {
+            config = config.with_timeout(Duration::from_secs(secs));
+        }
+
+        match (env("TOKEN"), feed.auth.as_ref().or(defaults.auth.as_ref())) {
+            (Some(token), _) => config.token = token,
+            (None, Some(AuthProfile::Bearer { token })) => config.token = token.resolve()?,
+            (
+                None,
+                Some(AuthProfile::ClientCredentials {
+                    token_url,
+                    client_id,
+                    client_secret,
+                    scope,
+                }),
+            ) => {
+                let mut credentials =
+                    ClientCredentials::new(token_url, client_id, client_secret.resolve()?);
+                credentials.scope = scope.clone();
+                config = config.with_client_credentials(credentials);
+            }
+            (None, None) => {
+                return Err(ResoError::Config(format!(
+                    "Feed profile '{}' has no auth; set it or RESO_{}_TOKEN",
+                    name,
+                    env_name(name)
+                )))
+            }
+        }
+
+        if let Some(limit) = feed.rate_limit.or(defaults.rate_limit) {
+            config = config.with_rate_limiter(limit.limiter(name)?);
+        }
+
+        if let Some(retry) = feed.retry.or(defaults.retry) {
+            config = config.with_retry(retry.policy());
+        }
+
+        config.resources = feed
+            .resources
+            .clone()
+            .or_else(|| defaults.resources.clone())
+            .unwrap_or_default();
+
+        Ok(config)
+    }
+}
+
+impl RateLimitProfile {
+    fn limiter(&self, feed: &str) -> Result<RateLimiter> {
+        let invalid = || {
+            ResoError::Config(format!(
+                "Feed profile '{}' needs one positive rate limit",
+                feed
+            ))
+        };
+        match (self.requests_per_second, self.requests_per_minute) {
+            (Some(n), None) if n > 0.0 => Ok(RateLimiter::per_second(n)),
+            (None, Some(n)) if n > 0.0 => Ok(RateLimiter::per_minute(n)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl RetryProfile {
+    fn policy(&self) -> RetryPolicy {
+        let mut policy = RetryPolicy::default();
+        if let Some(max_retries) = self.max_retries {
+            policy.max_retries = max_retries;
+        }
+        if let Some(ms) = self.initial_backoff_ms {
+            policy.initial_backoff = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.max_backoff_ms {
+            policy.max_backoff = Duration::from_millis(ms);
+        }
+        policy
+    }
+}
+
+/// Feed name as used in environment variable names
+fn env_name(feed: &str) -> String {
+    feed.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = r#"
+        [defaults]
+        timeout_secs = 45
+        retry = { max_retries = 4, initial_backoff_ms = 100 }
+
+        [feeds.alpha]
+        base_url = "https://alpha.example.com/odata/"
+        dataset_id = "alpha_ds"
+        resources = ["Property", "Member"]
+        rate_limit = { requests_per_minute = 120 }
+        auth = { method = "bearer", token = { env = "RESO_PROFILE_TEST_ALPHA_TOKEN" } }
+
+        [feeds.beta-mls]
+        base_url = "https://beta.example.com/odata"
+        timeout_secs = 10
+
+        [feeds.beta-mls.auth]
+        method = "client_credentials"
+        token_url = "https://beta.example.com/token"
+        client_id = "beta"
+        client_secret = { file = "SECRET_FILE" }
+        scope = "api"
+
+        [feeds.plain]
+        base_url = "https://plain.example.com/odata"
+        auth = { method = "bearer", token = "written-in-the-file" }
+    "#;
+
+    fn profiles(secret_file: &Path) -> Profiles {
+        let text = PROFILES.replace("SECRET_FILE", secret_file.to_str().unwrap());
+        Profiles::from_toml(&text).unwrap()
+    }
+
+    #[test]
+    fn test_profiles_resolve_defaults_and_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("secret");
+        std::fs::write(&secret, "s3cret\n").unwrap();
+        std::env::set_var("RESO_PROFILE_TEST_ALPHA_TOKEN", "alpha-token");
+        let profiles = profiles(&secret);
+
+        assert_eq!(
+            profiles.names().collect::<Vec<_>>(),
+            ["alpha", "beta-mls", "plain"]
+        );
+
+        let alpha = profiles.client_config("alpha").unwrap();
+        assert_eq!(alpha.base_url, "https://alpha.example.com/odata");
+        assert_eq!(alpha.token, "alpha-token");
+        assert_eq!(alpha.dataset_id.as_deref(), Some("alpha_ds"));
+        assert_eq!(alpha.timeout, Duration::from_secs(45));
+        assert_eq!(alpha.resources, ["Property", "Member"]);
+        assert_eq!(
+            alpha.rate_limiter.unwrap().interval(),
+            Duration::from_millis(500)
+        );
+        let retry = alpha.retry.unwrap();
+        assert_eq!(retry.max_retries, 4);
+        assert_eq!(retry.initial_backoff, Duration::from_millis(100));
+        assert_eq!(retry.max_backoff, RetryPolicy::default().max_backoff);
+
+        let beta = profiles.client_config("beta-mls").unwrap();
+        assert_eq!(beta.timeout, Duration::from_secs(10));
+        let credentials = beta.client_credentials.unwrap();
+        assert_eq!(credentials.client_secret, "s3cret");
+        assert_eq!(credentials.scope.as_deref(), Some("api"));
+        assert!(!format!("{:?}", credentials).contains("s3cret"));
+
+        let err = profiles.client_config("plain").unwrap_err();
+        assert!(err.to_string().contains("Plain-text secrets"));
+        let err = profiles.client_config("gamma").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("available: alpha, beta-mls, plain"));
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let profiles = Profiles::from_toml(&format!(
+            r#"
+            [feeds.override-test]
+            base_url = "https://override.example.com/odata"
+            timeout_secs = 10
+            auth = {{ method = "client_credentials", token_url = "https://override.example.com/token", client_id = "id", client_secret = {{ file = "{}" }} }}
+            "#,
+            missing.display()
+        ))
+        .unwrap();
+
+        // Without the override, the missing secret file is an error
+        assert!(profiles.client_config("override-test").is_err());
+
+        std::env::set_var("RESO_OVERRIDE_TEST_TOKEN", "override-token");
+        std::env::set_var("RESO_OVERRIDE_TEST_BASE_URL", "http://localhost:9000");
+        std::env::set_var("RESO_OVERRIDE_TEST_TIMEOUT", "5");
+        let config = profiles.client_config("override-test").unwrap();
+        assert_eq!(config.base_url, "http://localhost:9000");
+        assert_eq!(config.token, "override-token");
+        assert_eq!(config.timeout, Duration::from_secs(5));
+        assert!(config.client_credentials.is_none());
+    }
+
+    #[test]
+    fn test_invalid_profiles() {
+        assert!(Profiles::from_toml("[feeds.x]\nbase_uri = \"typo\"").is_err());
+
+        let profiles = Profiles::from_toml(
+            r#"
+            [feeds.rate-test]
+            base_url = "https://x.example.com"
+            rate_limit = { requests_per_second = 1, requests_per_minute = 60 }
+            "#,
+        )
+        .unwrap();
+        let err = profiles.client_config("rate-test").unwrap_err();
+        assert!(err.to_string().contains("has no auth"));
+
+        std::env::set_var("RESO_RATE_TEST_TOKEN", "token");
+        let err = profiles.client_config("rate-test").unwrap_err();
+        assert!(err.to_string().contains("one positive rate limit"));
+    }
+}
@@ -0,0 +1,367 @@
+// src/progress.rs
+
+//! Progress reporting for long-running replication runs
+//!
+//! Replication jobs can run for hours on large feeds. This module provides a
+//! [`ReplicationProgress`] snapshot that is updated after every page, a
+//! [`ProgressObserver`] trait to receive those snapshots, and a ready-made
+//! [`TracingProgressReporter`] that logs them through `tracing`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{ResoClient, ReplicationQueryBuilder, TracingProgressReporter};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let query = ReplicationQueryBuilder::new("Property")
+//!     .filter("StandardStatus eq 'Active'")
+//!     .top(2000)
+//!     .build()?;
+//!
+//! let mut stream = client
+//!     .replicate(&query)
+//!     .with_progress(TracingProgressReporter::new());
+//!
+//! while let Some(page) = stream.next_page().await? {
+//!     println!("Got {} records", page.record_count);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// Snapshot of a replication run's progress
+///
+/// A fresh snapshot is delivered to the [`ProgressObserver`] after every page.
+/// Throughput and the estimated time remaining are derived from the counters
+/// and the elapsed time, so they are always consistent with each other.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::ReplicationProgress;
+/// # use std::time::Duration;
+/// let progress = ReplicationProgress {
+///     total_expected: Some(10_000),
+///     records_resumed: 0,
+///     records_fetched: 2_000,
+///     pages_fetched: 1,
+///     bytes_downloaded: 4_000_000,
+///     elapsed: Duration::from_secs(4),
+/// };
+///
+/// assert_eq!(progress.records_per_second(), 500.0);
+/// assert_eq!(progress.estimated_remaining(), Some(Duration::from_secs(16)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplicationProgress {
+    /// Total number of records expected, from `/$count` with the same filter
+    ///
+    /// `None` if the count request failed or was not performed.
+    pub total_expected: Option<u64>,
+
+    /// Number of records committed by the earlier runs this run resumed
+    pub records_resumed: u64,
+
+    /// Number of records fetched so far in this run
+    pub records_fetched: u64,
+
+    /// Number of pages fetched so far
+    pub pages_fetched: u64,
+
+    /// Number of response body bytes downloaded so far
+    pub bytes_downloaded: u64,
+
+    /// Time elapsed since the replication run started
+    pub elapsed: Duration,
+}
+
+impl ReplicationProgress {
+    /// Records resumed from earlier runs plus records fetched in this one
+    pub fn records_done(&self) -> u64 {
+        self.records_resumed + self.records_fetched
+    }
+
+    /// Records fetched per second since the run started
+    ///
+    /// Returns `0.0` until some time has elapsed.
+    pub fn records_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.records_fetched as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Bytes downloaded per second since the run started
+    ///
+    /// Returns `0.0` until some time has elapsed.
+    pub fn bytes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.bytes_downloaded as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Percentage of the expected records done so far (0.0 - 100.0)
+    ///
+    /// Counts [`records_done`](Self::records_done), so a resumed run includes
+    /// the records of the runs before it. Returns `None` if the total is
+    /// unknown. Capped at 100.0 since records added on the server during the
+    /// run can push the count past the initial total.
+    pub fn percent_complete(&self) -> Option<f64> {
+        let total = self.total_expected?;
+        if total == 0 {
+            return Some(100.0);
+        }
+        Some((self.records_done() as f64 / total as f64 * 100.0).min(100.0))
+    }
+
+    /// Estimated time remaining based on the current throughput
+    ///
+    /// Returns `None` if the total is unknown or no records have been fetched yet.
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        let total = self.total_expected?;
+        let remaining = total.saturating_sub(self.records_done());
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+
+        let rate = self.records_per_second();
+        if rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+/// Receives progress updates from a replication run
+///
+/// Implement this trait to feed progress into dashboards, metrics or logs.
+/// All methods take `&self` so a single observer can be shared; use interior
+/// mutability if you need to accumulate state.
+///
+/// A `std::sync::mpsc::Sender<ReplicationProgress>` is also an observer, which
+/// lets you consume updates from another thread instead of implementing the trait.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::{ProgressObserver, ReplicationProgress};
+/// struct PrintObserver;
+///
+/// impl ProgressObserver for PrintObserver {
+///     fn on_page(&self, progress: &ReplicationProgress) {
+///         println!("{} records fetched", progress.records_fetched);
+///     }
+/// }
+/// ```
+pub trait ProgressObserver: Send + Sync {
+    /// Called once before the first page is requested, after the total count is known
+    fn on_start(&self, progress: &ReplicationProgress) {
+        let _ = progress;
+    }
+
+    /// Called after every page is fetched
+    fn on_page(&self, progress: &ReplicationProgress);
+
+    /// Called once after the last page has been fetched
+    fn on_complete(&self, progress: &ReplicationProgress) {
+        let _ = progress;
+    }
+}
+
+impl ProgressObserver for Sender<ReplicationProgress> {
+    fn on_page(&self, progress: &ReplicationProgress) {
+        // A dropped receiver just means nobody is listening anymore
+        let _ = self.send(progress.clone());
+    }
+}
+
+/// Progress observer that logs through `tracing`
+///
+/// Emits an `info` event on start, every `interval` pages, and on completion.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::TracingProgressReporter;
+/// // Log every 10th page
+/// let reporter = TracingProgressReporter::new().with_interval(10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TracingProgressReporter {
+    interval: u64,
+}
+
+impl TracingProgressReporter {
+    /// Create a reporter that logs every page
+    pub fn new() -> Self {
+        Self { interval: 1 }
+    }
+
+    /// Log only every `pages` pages (minimum 1)
+    pub fn with_interval(mut self, pages: u64) -> Self {
+        self.interval = pages.max(1);
+        self
+    }
+}
+
+impl Default for TracingProgressReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressObserver for TracingProgressReporter {
+    fn on_start(&self, progress: &ReplicationProgress) {
+        match progress.total_expected {
+            Some(total) => tracing::info!("Replication started: {} records expected", total),
+            None => tracing::info!("Replication started: total record count unknown"),
+        }
+    }
+
+    fn on_page(&self, progress: &ReplicationProgress) {
+        if !progress.pages_fetched.is_multiple_of(self.interval) {
+            return;
+        }
+
+        let percent = progress
+            .percent_complete()
+            .map(|p| format!("{:.1}%", p))
+            .unwrap_or_else(|| "?".to_string());
+        let eta = progress
+            .estimated_remaining()
+            .map(|d| format!("{}s", d.as_secs()))
+            .unwrap_or_else(|| "?".to_string());
+
+        tracing::info!(
+            "Replication progress: {} records ({}) in {} pages, {} bytes, {:.1} records/s, ETA {}",
+            progress.records_done(),
+            percent,
+            progress.pages_fetched,
+            progress.bytes_downloaded,
+            progress.records_per_second(),
+            eta
+        );
+    }
+
+    fn on_complete(&self, progress: &ReplicationProgress) {
+        tracing::info!(
+            "Replication complete: {} records in {} pages, {} bytes, {:.1}s",
+            progress.records_fetched,
+            progress.pages_fetched,
+            progress.bytes_downloaded,
+            progress.elapsed.as_secs_f64()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(total: Option<u64>, records: u64, secs: u64) -> ReplicationProgress {
+        ReplicationProgress {
+            total_expected: total,
+            records_resumed: 0,
+            records_fetched: records,
+            pages_fetched: 1,
+            bytes_downloaded: records * 100,
+            elapsed: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_records_per_second() {
+        assert_eq!(progress(None, 1000, 10).records_per_second(), 100.0);
+    }
+
+    #[test]
+    fn test_records_per_second_zero_elapsed() {
+        assert_eq!(progress(None, 1000, 0).records_per_second(), 0.0);
+    }
+
+    #[test]
+    fn test_bytes_per_second() {
+        assert_eq!(progress(None, 1000, 10).bytes_per_second(), 10_000.0);
+    }
+
+    #[test]
+    fn test_percent_complete() {
+        assert_eq!(progress(Some(4000), 1000, 1).percent_complete(), Some(25.0));
+        assert_eq!(progress(None, 1000, 1).percent_complete(), None);
+    }
+
+    #[test]
+    fn test_percent_complete_capped() {
+        assert_eq!(progress(Some(100), 150, 1).percent_complete(), Some(100.0));
+    }
+
+    #[test]
+    fn test_percent_complete_empty_total() {
+        assert_eq!(progress(Some(0), 0, 1).percent_complete(), Some(100.0));
+    }
+
+    #[test]
+    fn test_estimated_remaining() {
+        let eta = progress(Some(3000), 1000, 10).estimated_remaining();
+        assert_eq!(eta, Some(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_estimated_remaining_unknown() {
+        assert_eq!(progress(None, 1000, 10).estimated_remaining(), None);
+        assert_eq!(progress(Some(3000), 0, 10).estimated_remaining(), None);
+    }
+
+    #[test]
+    fn test_resumed_records_count_as_done_but_not_as_throughput() {
+        let resumed = ReplicationProgress {
+            records_resumed: 2000,
+            ..progress(Some(4000), 1000, 10)
+        };
+
+        assert_eq!(resumed.records_done(), 3000);
+        assert_eq!(resumed.records_per_second(), 100.0);
+        assert_eq!(resumed.percent_complete(), Some(75.0));
+        assert_eq!(resumed.estimated_remaining(), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_estimated_remaining_done() {
+        let eta = progress(Some(1000), 1200, 10).estimated_remaining();
+        assert_eq!(eta, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_channel_observer() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let snapshot = progress(Some(10), 5, 1);
+
+        tx.on_page(&snapshot);
+
+        assert_eq!(rx.recv().unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_channel_observer_dropped_receiver() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        drop(rx);
+
+        // Must not panic
+        tx.on_page(&progress(None, 1, 1));
+    }
+
+    #[test]
+    fn test_tracing_reporter_interval_minimum() {
+        let reporter = TracingProgressReporter::new().with_interval(0);
+        assert_eq!(reporter.interval, 1);
+    }
+}
@@ -183,6 +183,36 @@ impl Query {
 
         parts.concat()
     }
+
+    /// Get the resource name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::QueryBuilder;
+    /// let query = QueryBuilder::new("Member").build()?;
+    /// assert_eq!(query.resource(), "Member");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    /// Get the `$select` field list, if any
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::QueryBuilder;
+    /// let query = QueryBuilder::new("Property")
+    ///     .select(&["ListingKey", "City"])
+    ///     .build()?;
+    /// assert_eq!(query.select_fields(), Some(&["ListingKey".to_string(), "City".to_string()][..]));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn select_fields(&self) -> Option<&[String]> {
+        self.select_fields.as_deref()
+    }
 }
 
 impl ReplicationQuery {
@@ -264,6 +294,22 @@ impl ReplicationQuery {
     pub fn resource(&self) -> &str {
         &self.resource
     }
+
+    /// Get the `$select` field list, if any
+    pub fn select_fields(&self) -> Option<&[String]> {
+        self.select_fields.as_deref()
+    }
+
+    /// Build the `/$count` query matching this replication query's filter
+    ///
+    /// Used to learn how many records a replication run is expected to return.
+    #[cfg(feature = "client")]
+    pub(crate) fn count_query(&self) -> Query {
+        let mut query = Query::new(self.resource.clone());
+        query.filter = self.filter.clone();
+        query.count_only = true;
+        query
+    }
 }
 
 /// Fluent query builder
@@ -388,7 +434,9 @@ impl QueryBuilder {
     /// If your server doesn't support aggregation, use multiple filtered queries instead:
     ///
     /// ```no_run
+    /// # #[cfg(feature = "client")]
     /// # use reso_client::{ResoClient, QueryBuilder};
+    /// # #[cfg(feature = "client")]
     /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let statuses = ["Active", "Pending", "Closed"];
     /// for status in statuses {
@@ -476,6 +524,24 @@ impl QueryBuilder {
         self
     }
 
+    /// Order by a complete `$orderby` expression, e.g. several sort keys
+    ///
+    /// Replaces any order set by [`order_by`](Self::order_by).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::QueryBuilder;
+    /// let query = QueryBuilder::new("Property")
+    ///     .order_by_expression("City asc, ListPrice desc")
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn order_by_expression(mut self, expression: impl Into<String>) -> Self {
+        self.query.order_by = Some(expression.into());
+        self
+    }
+
     /// Limit number of results
     ///
     /// # Examples
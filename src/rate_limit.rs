@@ -0,0 +1,121 @@
+// src/rate_limit.rs
+
+//! Client-side request rate limiting
+//!
+//! Most MLS feeds publish a request quota and answer `429 Too Many Requests`
+//! once it is exceeded. A [`RateLimiter`] attached to the client
+//! configuration spaces requests evenly so the quota is never hit. Clones
+//! share their schedule, so one limiter can govern several clients talking
+//! to the same feed.
+//!
+//! The limiter runs as a [middleware](crate::middleware) inside retries, so
+//! retried attempts wait for a slot too.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ClientConfig, RateLimiter};
+//! // At most two requests per second across every client sharing `limiter`
+//! let limiter = RateLimiter::per_second(2.0);
+//! let config = ClientConfig::from_env()?.with_rate_limiter(limiter.clone());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::Result;
+use crate::middleware::{Middleware, Next, Request, Response};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Spaces requests at a fixed minimum interval
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Allow `requests` requests per second
+    ///
+    /// # Panics
+    ///
+    /// If `requests` is not positive.
+    pub fn per_second(requests: f64) -> Self {
+        assert!(requests > 0.0, "rate limit must be positive");
+        Self::with_interval(Duration::from_secs_f64(1.0 / requests))
+    }
+
+    /// Allow `requests` requests per minute
+    ///
+    /// # Panics
+    ///
+    /// If `requests` is not positive.
+    pub fn per_minute(requests: f64) -> Self {
+        assert!(requests > 0.0, "rate limit must be positive");
+        Self::with_interval(Duration::from_secs_f64(60.0 / requests))
+    }
+
+    /// Wait at least `interval` between requests
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Arc::default(),
+        }
+    }
+
+    /// Minimum time between requests
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Wait for the next request slot
+    pub(crate) async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let now = Instant::now();
+            let slot = next.map_or(now, |next| next.max(now));
+            *next = Some(slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[async_trait]
+impl Middleware for RateLimiter {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response> {
+        self.acquire().await;
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervals() {
+        assert_eq!(
+            RateLimiter::per_second(4.0).interval(),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            RateLimiter::per_minute(30.0).interval(),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_requests() {
+        let limiter = RateLimiter::per_second(50.0);
+        let shared = limiter.clone();
+        let start = Instant::now();
+
+        limiter.acquire().await;
+        shared.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
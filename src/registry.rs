@@ -0,0 +1,228 @@
+// src/registry.rs
+
+//! Querying several MLS feeds at once
+//!
+//! A [`FeedRegistry`] holds named [`ResoClient`]s and sends the same
+//! [`Query`] to every feed concurrently. Each record in the result is tagged
+//! with the feed it came from, and a feed that fails is reported in
+//! [`FanOutResult::errors`] without affecting the others.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{FeedRegistry, Profiles, QueryBuilder};
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let registry = FeedRegistry::from_profiles(&Profiles::discover()?)?;
+//!
+//! let query = QueryBuilder::new("Property")
+//!     .filter("City eq 'Austin'")
+//!     .top(50)
+//!     .build()?;
+//! let result = registry.execute(&query).await;
+//!
+//! for record in &result.records {
+//!     println!("{}: {}", record.feed, record.record["ListingKey"]);
+//! }
+//! for (feed, error) in &result.errors {
+//!     eprintln!("{} failed: {}", feed, error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::ResoClient;
+use crate::error::{ResoError, Result};
+use crate::profile::Profiles;
+use crate::queries::Query;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Named clients queried together
+#[derive(Default)]
+pub struct FeedRegistry {
+    feeds: BTreeMap<String, Arc<ResoClient>>,
+}
+
+/// A record and the feed it came from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedRecord {
+    /// Feed name in the registry
+    pub feed: String,
+
+    /// The record as returned by the feed
+    pub record: JsonValue,
+}
+
+/// Outcome of sending one query to every feed
+#[derive(Debug, Default)]
+pub struct FanOutResult {
+    /// Records from every successful feed, grouped by feed in name order
+    pub records: Vec<FeedRecord>,
+
+    /// Errors by feed name
+    pub errors: BTreeMap<String, ResoError>,
+}
+
+impl FanOutResult {
+    /// Whether every feed succeeded
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Records returned by one feed
+    pub fn records_from<'a>(&'a self, feed: &'a str) -> impl Iterator<Item = &'a JsonValue> + 'a {
+        self.records
+            .iter()
+            .filter(move |r| r.feed == feed)
+            .map(|r| &r.record)
+    }
+}
+
+impl FeedRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with a client for every feed in a profile file
+    ///
+    /// Fails if any feed's configuration cannot be resolved.
+    pub fn from_profiles(profiles: &Profiles) -> Result<Self> {
+        let mut registry = Self::new();
+        for name in profiles.names() {
+            let client = ResoClient::with_config(profiles.client_config(name)?)?;
+            registry.insert(name, client);
+        }
+        Ok(registry)
+    }
+
+    /// Add a feed
+    pub fn with_feed(mut self, name: impl Into<String>, client: ResoClient) -> Self {
+        self.insert(name, client);
+        self
+    }
+
+    /// Add or replace a feed, returning the client it replaced
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        client: ResoClient,
+    ) -> Option<Arc<ResoClient>> {
+        self.feeds.insert(name.into(), Arc::new(client))
+    }
+
+    /// Remove a feed
+    pub fn remove(&mut self, name: &str) -> Option<Arc<ResoClient>> {
+        self.feeds.remove(name)
+    }
+
+    /// Client for a feed
+    pub fn get(&self, name: &str) -> Option<&ResoClient> {
+        self.feeds.get(name).map(|client| client.as_ref())
+    }
+
+    /// Feed names, in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.feeds.keys().map(String::as_str)
+    }
+
+    /// Number of feeds
+    pub fn len(&self) -> usize {
+        self.feeds.len()
+    }
+
+    /// Whether the registry has no feeds
+    pub fn is_empty(&self) -> bool {
+        self.feeds.is_empty()
+    }
+
+    /// Execute a query against every feed concurrently
+    ///
+    /// Each feed returns one page, as with [`ResoClient::execute`].
+    pub async fn execute(&self, query: &Query) -> FanOutResult {
+        self.fan_out(query, |client, query| async move {
+            let mut json = client.execute(&query).await?;
+            Ok(match json.get_mut("value").map(JsonValue::take) {
+                Some(JsonValue::Array(records)) => records,
+                _ => Vec::new(),
+            })
+        })
+        .await
+    }
+
+    /// Execute a query against every feed, following `@odata.nextLink`
+    ///
+    /// Each feed returns all of its pages, as with
+    /// [`ResoClient::execute_into`].
+    pub async fn execute_all(&self, query: &Query) -> FanOutResult {
+        self.fan_out(query, |client, query| async move {
+            let mut sink = crate::sink::MemorySink::new();
+            client.execute_into(&query, &mut sink).await?;
+            Ok(sink.into_records())
+        })
+        .await
+    }
+
+    async fn fan_out<F, Fut>(&self, query: &Query, fetch: F) -> FanOutResult
+    where
+        F: Fn(Arc<ResoClient>, Query) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<JsonValue>>> + Send + 'static,
+    {
+        use tracing::{info, warn};
+
+        info!("Fanning out query to {} feeds", self.feeds.len());
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (name, client) in &self.feeds {
+            let (name, fetch) = (name.clone(), fetch(client.clone(), query.clone()));
+            tasks.spawn(async move { (name, fetch.await) });
+        }
+
+        let mut results = BTreeMap::new();
+        let mut result = FanOutResult::default();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((name, Ok(records))) => {
+                    results.insert(name, records);
+                }
+                Ok((name, Err(e))) => {
+                    warn!("Feed {} failed: {}", name, e);
+                    result.errors.insert(name, e);
+                }
+                Err(e) => warn!("Feed task failed: {}", e),
+            }
+        }
+
+        // A panicked task loses its feed name, so report every feed that
+        // has no outcome
+        for name in self.feeds.keys() {
+            if !results.contains_key(name) && !result.errors.contains_key(name) {
+                result.errors.insert(
+                    name.clone(),
+                    ResoError::Network("Feed task did not complete".to_string()),
+                );
+            }
+        }
+
+        result.records = results
+            .into_iter()
+            .flat_map(|(feed, records)| {
+                records.into_iter().map(move |record| FeedRecord {
+                    feed: feed.clone(),
+                    record,
+                })
+            })
+            .collect();
+        result
+    }
+}
+
+impl std::fmt::Debug for FeedRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeedRegistry")
+            .field("feeds", &self.feeds.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
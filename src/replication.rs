@@ -1,9 +1,24 @@
 // src/replication.rs
 
-//! Replication endpoint response types
+//! Replication endpoint response types and page streaming
 
 use serde_json::Value as JsonValue;
 
+#[cfg(feature = "client")]
+use crate::{
+    client::ResoClient,
+    error::{ResoError, Result},
+    options::RequestOptions,
+    progress::{ProgressObserver, ReplicationProgress},
+    queries::ReplicationQuery,
+    sink::{PageDelivery, RecordSink},
+    streaming::RecordReader,
+};
+#[cfg(feature = "client")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "client")]
+use std::time::Instant;
+
 /// Response from a replication endpoint query
 ///
 /// The replication endpoint returns records along with a `next` link in the
@@ -12,7 +27,9 @@ use serde_json::Value as JsonValue;
 /// # Example
 ///
 /// ```no_run
+/// # #[cfg(feature = "client")]
 /// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+/// # #[cfg(feature = "client")]
 /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
 /// let query = ReplicationQueryBuilder::new("Property")
 ///     .top(2000)
@@ -77,7 +94,9 @@ impl ReplicationResponse {
     /// # Examples
     ///
     /// ```no_run
+    /// # #[cfg(feature = "client")]
     /// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+    /// # #[cfg(feature = "client")]
     /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let query = ReplicationQueryBuilder::new("Property")
     ///     .top(2000)
@@ -106,7 +125,9 @@ impl ReplicationResponse {
     /// # Examples
     ///
     /// ```no_run
+    /// # #[cfg(feature = "client")]
     /// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+    /// # #[cfg(feature = "client")]
     /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
     /// let query = ReplicationQueryBuilder::new("Property").build()?;
     /// let mut response = client.execute_replication(&query).await?;
@@ -127,6 +148,349 @@ impl ReplicationResponse {
     }
 }
 
+/// Page-by-page stream over a replication query
+///
+/// Created by [`ResoClient::replicate`]. Each call to [`next_page`](Self::next_page)
+/// fetches the next batch of records, following `next` links until the server
+/// stops returning one. [`next_record`](Self::next_record) instead returns
+/// records one at a time, parsed as each page's body arrives.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let query = ReplicationQueryBuilder::new("Property")
+///     .top(2000)
+///     .build()?;
+///
+/// // Receive progress updates on a channel
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// let mut stream = client.replicate(&query).with_progress(tx);
+///
+/// while let Some(page) = stream.next_page().await? {
+///     let progress = rx.recv()?;
+///     println!(
+///         "{} records so far, ETA {:?}",
+///         progress.records_fetched,
+///         progress.estimated_remaining()
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "client")]
+pub struct ReplicationStream<'a> {
+    client: &'a ResoClient,
+    query: ReplicationQuery,
+    state: StreamState,
+    observer: Option<Box<dyn ProgressObserver + 'a>>,
+    progress: ReplicationProgress,
+    started_at: Option<Instant>,
+    open_page: Option<OpenPage>,
+    options: RequestOptions,
+}
+
+/// A page whose body is still being read
+#[cfg(feature = "client")]
+struct OpenPage {
+    reader: RecordReader,
+    next_link: Option<String>,
+    records: u64,
+}
+
+/// Where a [`ReplicationStream`] is in its run
+#[cfg(feature = "client")]
+enum StreamState {
+    /// No page requested yet
+    NotStarted,
+    /// The next page lives at this URL
+    Next(String),
+    /// The server returned no further next link
+    Done,
+}
+
+#[cfg(feature = "client")]
+impl<'a> ReplicationStream<'a> {
+    pub(crate) fn new(client: &'a ResoClient, query: ReplicationQuery) -> Self {
+        Self {
+            client,
+            query,
+            state: StreamState::NotStarted,
+            observer: None,
+            progress: ReplicationProgress::default(),
+            started_at: None,
+            open_page: None,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Attach a progress observer
+    ///
+    /// When an observer is attached, the stream requests the total record count
+    /// once via `/$count` with the same filter and options before fetching the
+    /// first page.
+    /// If the count request fails, replication continues with an unknown total.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder, TracingProgressReporter};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Property").build()?;
+    /// let stream = client
+    ///     .replicate(&query)
+    ///     .with_progress(TracingProgressReporter::new().with_interval(10));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_progress(mut self, observer: impl ProgressObserver + 'a) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Apply per-call options to the run
+    ///
+    /// Every page is requested with the headers of `options`. The timeout
+    /// applies to each `next_*` call, the deadline and token to the run as a
+    /// whole.
+    ///
+    /// A call that times out or is cancelled discards the page it was
+    /// reading, and the next call requests that page again from its start.
+    /// Records of that page already returned by
+    /// [`next_record`](Self::next_record) are then returned again, so like
+    /// sinks, callers see each record at least once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder, RequestOptions};
+    /// # use std::time::Duration;
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Property").build()?;
+    /// let stream = client
+    ///     .replicate(&query)
+    ///     .with_options(RequestOptions::new().with_timeout(Duration::from_secs(120)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Progress of the run so far
+    pub fn progress(&self) -> &ReplicationProgress {
+        &self.progress
+    }
+
+    /// Fetch the next page of records
+    ///
+    /// If [`next_record`](Self::next_record) stopped partway through a page,
+    /// the page holds only its remaining records.
+    ///
+    /// Returns `Ok(None)` once all pages have been fetched.
+    pub async fn next_page(&mut self) -> Result<Option<ReplicationResponse>> {
+        let options = self.options.clone();
+        options.run(self.read_page()).await
+    }
+
+    /// Read the open page, or the next one, to its end
+    async fn read_page(&mut self) -> Result<Option<ReplicationResponse>> {
+        let mut page = match self.open_page.take() {
+            Some(page) => page,
+            None => match self.open_next_page().await? {
+                Some(page) => page,
+                None => return Ok(None),
+            },
+        };
+
+        let records = page.reader.collect().await?;
+        page.records += records.len() as u64;
+        self.finish_page(&page);
+
+        Ok(Some(ReplicationResponse::new(records, page.next_link)))
+    }
+
+    /// Fetch the next record
+    ///
+    /// Records are parsed one at a time as the page body arrives, so a page
+    /// is never held in memory as a whole. Progress is updated, and observers
+    /// notified, when a page has been read to its end.
+    ///
+    /// Returns `Ok(None)` once all pages have been read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Property").top(2000).build()?;
+    ///
+    /// let mut stream = client.replicate(&query);
+    /// while let Some(record) = stream.next_record().await? {
+    ///     println!("{}", record["ListingKey"]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn next_record(&mut self) -> Result<Option<JsonValue>> {
+        self.next_record_as().await
+    }
+
+    /// Fetch the next record, deserialized as `T`
+    ///
+    /// Like [`next_record`](Self::next_record), but deserializes straight
+    /// from the response bytes without building a [`JsonValue`] first.
+    pub async fn next_record_as<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        let options = self.options.clone();
+        let result = options.run(self.read_record()).await;
+        if matches!(result, Err(ResoError::Timeout(_) | ResoError::Cancelled)) {
+            // Like `next_page`, start the interrupted page over
+            self.open_page = None;
+        }
+        result
+    }
+
+    /// Read one record, opening and finishing pages as needed
+    async fn read_record<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        loop {
+            match &mut self.open_page {
+                Some(page) => {
+                    if let Some(record) = page.reader.next_as().await? {
+                        page.records += 1;
+                        return Ok(Some(record));
+                    }
+                }
+                None => match self.open_next_page().await? {
+                    Some(page) => {
+                        self.open_page = Some(page);
+                        continue;
+                    }
+                    None => return Ok(None),
+                },
+            }
+
+            if let Some(page) = self.open_page.take() {
+                self.finish_page(&page);
+            }
+        }
+    }
+
+    /// Request the next page, unless the run is over
+    async fn open_next_page(&mut self) -> Result<Option<OpenPage>> {
+        if matches!(self.state, StreamState::Done) {
+            return Ok(None);
+        }
+        if self.started_at.is_none() {
+            self.start().await;
+        }
+
+        let url = match &self.state {
+            StreamState::Next(url) => url.clone(),
+            _ => self.client.replication_url(&self.query),
+        };
+
+        let (reader, next_link) = self
+            .client
+            .open_replication_page(&url, &self.options)
+            .await?;
+        Ok(Some(OpenPage {
+            reader,
+            next_link,
+            records: 0,
+        }))
+    }
+
+    /// Account for a page read to its end and move on to the following one
+    fn finish_page(&mut self, page: &OpenPage) {
+        self.progress.pages_fetched += 1;
+        self.progress.records_fetched += page.records;
+        self.progress.bytes_downloaded += page.reader.bytes_read();
+        self.progress.elapsed = self.started_at.map(|t| t.elapsed()).unwrap_or_default();
+
+        if let Some(observer) = &self.observer {
+            observer.on_page(&self.progress);
+        }
+
+        self.state = match &page.next_link {
+            Some(next) => StreamState::Next(next.clone()),
+            None => {
+                if let Some(observer) = &self.observer {
+                    observer.on_complete(&self.progress);
+                }
+                StreamState::Done
+            }
+        };
+    }
+
+    /// Fetch all remaining pages into a sink
+    ///
+    /// Writes every page to the sink in batches of at most
+    /// [`RecordSink::max_batch_size`] records, flushes it, and commits a
+    /// [`ReplicationCheckpoint`](crate::sink::ReplicationCheckpoint) pointing
+    /// at the following page. If the sink reports an unfinished checkpoint for
+    /// the same resource, the run resumes from it instead of starting over.
+    ///
+    /// Returns the final progress of the run.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use reso_client::{ResoClient, ReplicationQueryBuilder, MemorySink, TracingProgressReporter};
+    /// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+    /// let query = ReplicationQueryBuilder::new("Property").top(2000).build()?;
+    /// let mut sink = MemorySink::new();
+    ///
+    /// let progress = client
+    ///     .replicate(&query)
+    ///     .with_progress(TracingProgressReporter::new())
+    ///     .drain_into(&mut sink)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn drain_into<S: RecordSink + ?Sized>(
+        mut self,
+        sink: &mut S,
+    ) -> Result<ReplicationProgress> {
+        let resource = self.query.resource().to_string();
+        let (mut delivery, resume_link) =
+            PageDelivery::resume(sink, &resource, &self.query.to_odata_string()).await?;
+
+        if let Some(next_link) = resume_link {
+            if matches!(self.state, StreamState::NotStarted) {
+                self.progress.records_resumed = delivery.records_committed();
+                self.state = StreamState::Next(next_link);
+            }
+        }
+
+        while let Some(page) = self.next_page().await? {
+            delivery.deliver(&page.records, page.next_link).await?;
+        }
+
+        Ok(self.progress)
+    }
+
+    /// Record the start time and, if observed, fetch the expected total
+    async fn start(&mut self) {
+        self.started_at = Some(Instant::now());
+
+        if let Some(observer) = &self.observer {
+            let count_query = self.query.count_query();
+            match self
+                .client
+                .execute_count_with(&count_query, &self.options)
+                .await
+            {
+                Ok(total) => self.progress.total_expected = Some(total),
+                Err(e) => tracing::warn!("Could not determine replication total: {}", e),
+            }
+            observer.on_start(&self.progress);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,241 @@
+// src/retry.rs
+
+//! Retrying transient failures with exponential backoff
+//!
+//! A [`RetryPolicy`] attached to the client configuration re-sends requests
+//! that failed with a network error or one of the transient statuses
+//! `429 Too Many Requests`, `500`, `502`, `503` and `504`. The delay doubles
+//! after each attempt, starting from the initial backoff and capped at the
+//! maximum. A `Retry-After` header given in seconds takes precedence, still
+//! capped at the maximum.
+//!
+//! Other errors (`400`, `401`, `404`, ...) are returned immediately.
+//!
+//! The policy is the outermost [middleware](crate::middleware), so every
+//! attempt passes through rate limiting, authentication and custom
+//! middlewares again.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use reso_client::{ClientConfig, RetryPolicy};
+//! # use std::time::Duration;
+//! let config = ClientConfig::from_env()?.with_retry(
+//!     RetryPolicy::new(5)
+//!         .with_initial_backoff(Duration::from_millis(250))
+//!         .with_max_backoff(Duration::from_secs(10)),
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::middleware::{Middleware, Next, Request, Response};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// How often and how patiently to retry transient failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Longest delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Three retries, backing off from 500 ms up to 30 seconds
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy with `max_retries` retries and the default backoff
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the longest delay between attempts
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Delay before retry number `retry` (starting at 0)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::RetryPolicy;
+    /// # use std::time::Duration;
+    /// let policy = RetryPolicy::default();
+    /// assert_eq!(policy.backoff(0), Duration::from_millis(500));
+    /// assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    /// assert_eq!(policy.backoff(20), Duration::from_secs(30));
+    /// ```
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Delay before retry number `retry`, honouring a server `Retry-After`
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.backoff(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Whether a response status is worth retrying
+    pub(crate) fn is_transient(status: u16) -> bool {
+        matches!(status, 429 | 500 | 502 | 503 | 504)
+    }
+}
+
+#[async_trait]
+impl Middleware for RetryPolicy {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response> {
+        use tracing::warn;
+
+        let mut retries = 0;
+        loop {
+            // Requests with streaming bodies cannot be replayed
+            let Some(attempt) = request.try_clone() else {
+                return next.run(request).await;
+            };
+            let result = next.run(attempt).await;
+
+            let retry_after = match &result {
+                Ok(response) if Self::is_transient(response.status().as_u16()) => {
+                    Some(retry_after(response.headers()))
+                }
+                Err(ResoError::Network(_)) => Some(None),
+                _ => None,
+            };
+            match retry_after {
+                Some(retry_after) if retries < self.max_retries => {
+                    let delay = self.delay(retries, retry_after);
+                    warn!(
+                        "Retrying {} in {:?} ({} of {})",
+                        request.url(),
+                        delay,
+                        retries + 1,
+                        self.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    retries += 1;
+                }
+                _ => return result,
+            }
+        }
+    }
+}
+
+/// `Retry-After` given in seconds; HTTP dates are ignored
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_prefers_retry_after() {
+        let policy = RetryPolicy::new(2).with_max_backoff(Duration::from_secs(5));
+
+        assert_eq!(policy.delay(1, None), Duration::from_secs(1));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(60))),
+            Duration::from_secs(5)
+        );
+        assert!(RetryPolicy::is_transient(503));
+        assert!(!RetryPolicy::is_transient(404));
+    }
+
+    /// Fails with 503 until `failures` requests have been seen
+    struct Flaky {
+        failures: u32,
+        seen: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl Middleware for Flaky {
+        async fn handle(&self, _: Request, _: Next<'_>) -> Result<Response> {
+            let seen = self.seen.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let status = if seen < self.failures { 503 } else { 200 };
+            Ok(Response::from(
+                http::Response::builder()
+                    .status(status)
+                    .body(String::new())
+                    .unwrap(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware() {
+        let http_client = reqwest::Client::new();
+        let policy = RetryPolicy::new(2).with_initial_backoff(Duration::from_millis(1));
+
+        for (failures, status, attempts) in [(2, 200, 3), (3, 503, 3)] {
+            let flaky = std::sync::Arc::new(Flaky {
+                failures,
+                seen: Default::default(),
+            });
+            let chain: Vec<std::sync::Arc<dyn Middleware>> =
+                vec![std::sync::Arc::new(policy), flaky.clone()];
+            let request = http_client.get("http://localhost:1/").build().unwrap();
+
+            let response = Next::new(&http_client, &chain).run(request).await.unwrap();
+            assert_eq!(response.status(), status);
+            assert_eq!(
+                flaky.seen.load(std::sync::atomic::Ordering::SeqCst),
+                attempts
+            );
+        }
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("Retry-After", "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(
+            "Retry-After",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+}
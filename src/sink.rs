@@ -0,0 +1,582 @@
+// src/sink.rs
+
+//! Pluggable destinations for replicated records
+//!
+//! A [`RecordSink`] receives batches of records from the replication driver
+//! ([`ResoClient::replicate_into`](crate::ResoClient::replicate_into)) and
+//! stores them somewhere: files, a database, a message queue. The driver
+//! handles pagination and batching, and ties each sink commit to a
+//! [`ReplicationCheckpoint`] so a crashed run can resume without skipping
+//! records.
+//!
+//! # Delivery Guarantees
+//!
+//! For every page the driver calls [`write_batch`](RecordSink::write_batch) one
+//! or more times, then [`flush`](RecordSink::flush), then
+//! [`commit`](RecordSink::commit) with the checkpoint pointing at the *next*
+//! page. If the process dies before a commit, the next run resumes from the
+//! last committed checkpoint and re-delivers the uncommitted page. Records may
+//! therefore be written more than once (at-least-once), but never skipped.
+//! Sinks that need exactly-once results should upsert by key.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{ResoClient, ReplicationQueryBuilder, MemorySink};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let query = ReplicationQueryBuilder::new("Property")
+//!     .select(&["ListingKey", "ListPrice"])
+//!     .top(2000)
+//!     .build()?;
+//!
+//! let mut sink = MemorySink::new();
+//! let progress = client.replicate_into(&query, &mut sink).await?;
+//!
+//! println!("Stored {} records", progress.records_fetched);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod csv;
+pub mod files;
+pub mod json;
+#[cfg(feature = "arrow")]
+pub mod parquet;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use crate::error::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Durable position in a replication run
+///
+/// Sinks store the checkpoint they are given in [`RecordSink::commit`] and
+/// hand it back from [`RecordSink::last_checkpoint`] so an interrupted run can
+/// continue where it left off.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::ReplicationCheckpoint;
+/// let checkpoint = ReplicationCheckpoint {
+///     resource: "Property".to_string(),
+///     query: Some("Property/replication?$top=2000".to_string()),
+///     next_link: Some("https://api.mls.com/odata/Property/replication?next=abc".to_string()),
+///     records_committed: 2000,
+/// };
+///
+/// assert!(!checkpoint.is_complete());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicationCheckpoint {
+    /// Resource being replicated (e.g., `Property`)
+    pub resource: String,
+
+    /// Query the run started from, as rendered by `to_odata_string()`
+    ///
+    /// A run only resumes from a checkpoint of the same query; `None`
+    /// (checkpoints written before this was recorded) never matches.
+    #[serde(default)]
+    pub query: Option<String>,
+
+    /// URL of the first page that has not been committed yet
+    ///
+    /// `None` once the run has delivered its last page.
+    pub next_link: Option<String>,
+
+    /// Number of records committed so far in this run
+    pub records_committed: u64,
+}
+
+impl ReplicationCheckpoint {
+    /// Check if the run this checkpoint belongs to has finished
+    pub fn is_complete(&self) -> bool {
+        self.next_link.is_none()
+    }
+}
+
+/// Destination for replicated records
+///
+/// Implement this trait to plug a storage backend into
+/// [`ResoClient::replicate_into`](crate::ResoClient::replicate_into). See the
+/// [module documentation](self) for the delivery guarantees the driver provides.
+///
+/// # Examples
+///
+/// ```
+/// use reso_client::{async_trait, RecordSink, ReplicationCheckpoint, Result};
+/// use serde_json::Value;
+///
+/// struct CountingSink {
+///     seen: usize,
+/// }
+///
+/// #[async_trait]
+/// impl RecordSink for CountingSink {
+///     async fn write_batch(&mut self, records: &[Value]) -> Result<()> {
+///         self.seen += records.len();
+///         Ok(())
+///     }
+///
+///     async fn commit(&mut self, _checkpoint: &ReplicationCheckpoint) -> Result<()> {
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait RecordSink: Send {
+    /// Write a batch of records
+    ///
+    /// Records are not considered delivered until the following `commit` succeeds.
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()>;
+
+    /// Make all written records durable
+    ///
+    /// Called after the last batch of every page, before `commit`.
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Record that everything written so far has been delivered
+    ///
+    /// The checkpoint points at the next page to fetch. Store it atomically with
+    /// the flushed records where the backend allows it.
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()>;
+
+    /// Return the last committed checkpoint, if any
+    ///
+    /// The driver resumes an unfinished run from this checkpoint. Only read
+    /// the checkpoint here; the driver follows up with
+    /// [`resume`](RecordSink::resume) or [`reset`](RecordSink::reset). The
+    /// default implementation always starts a fresh run.
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        Ok(None)
+    }
+
+    /// Continue the unfinished run of `checkpoint`
+    ///
+    /// Called after `last_checkpoint` when the driver resumes from the
+    /// checkpoint it returned.
+    async fn resume(&mut self, _checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        Ok(())
+    }
+
+    /// Prepare for a fresh run
+    ///
+    /// Called after `last_checkpoint` when the driver starts over: there is no
+    /// checkpoint, the last run finished, or it belongs to another resource or
+    /// query. Sinks that keep per-run state, like the file exports, drop the
+    /// previous run's here.
+    async fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Maximum number of records passed to a single `write_batch` call
+    ///
+    /// `None` (the default) writes each page as one batch.
+    fn max_batch_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink + ?Sized> RecordSink for Box<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        (**self).write_batch(records).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        (**self).flush().await
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        (**self).commit(checkpoint).await
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        (**self).last_checkpoint().await
+    }
+
+    async fn resume(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        (**self).resume(checkpoint).await
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        (**self).reset().await
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        (**self).max_batch_size()
+    }
+}
+
+/// Delivers pages to a sink with checkpointed commits
+///
+/// Shared by the replication and paginated-query drivers so both follow the
+/// same write → flush → commit sequence.
+#[cfg(feature = "client")]
+pub(crate) struct PageDelivery<'s, S: RecordSink + ?Sized> {
+    sink: &'s mut S,
+    resource: String,
+    query: String,
+    records_committed: u64,
+    batch_size: usize,
+}
+
+#[cfg(feature = "client")]
+impl<'s, S: RecordSink + ?Sized> PageDelivery<'s, S> {
+    /// Prepare delivery, returning the next link to resume from if the sink
+    /// holds an unfinished checkpoint for the same resource and query
+    pub(crate) async fn resume(
+        sink: &'s mut S,
+        resource: &str,
+        query: &str,
+    ) -> Result<(PageDelivery<'s, S>, Option<String>)> {
+        let mut records_committed = 0;
+        let mut resume_link = None;
+
+        match sink.last_checkpoint().await? {
+            Some(checkpoint)
+                if checkpoint.resource == resource
+                    && checkpoint.query.as_deref() == Some(query) =>
+            {
+                if let Some(next_link) = &checkpoint.next_link {
+                    tracing::info!(
+                        "Resuming {} after {} committed records",
+                        resource,
+                        checkpoint.records_committed
+                    );
+                    records_committed = checkpoint.records_committed;
+                    resume_link = Some(next_link.clone());
+                    sink.resume(&checkpoint).await?;
+                }
+            }
+            Some(checkpoint) if checkpoint.resource != resource => tracing::warn!(
+                "Ignoring checkpoint for {} while delivering {}",
+                checkpoint.resource,
+                resource
+            ),
+            Some(checkpoint) if checkpoint.next_link.is_some() => tracing::warn!(
+                "Ignoring unfinished checkpoint of query {:?}, starting {} afresh",
+                checkpoint.query.unwrap_or_default(),
+                query
+            ),
+            Some(_) => {}
+            None => {}
+        }
+        if resume_link.is_none() {
+            sink.reset().await?;
+        }
+
+        let batch_size = sink.max_batch_size().unwrap_or(usize::MAX).max(1);
+
+        Ok((
+            Self {
+                sink,
+                resource: resource.to_string(),
+                query: query.to_string(),
+                records_committed,
+                batch_size,
+            },
+            resume_link,
+        ))
+    }
+
+    /// Number of records committed so far, including any resumed run
+    pub(crate) fn records_committed(&self) -> u64 {
+        self.records_committed
+    }
+
+    /// Write, flush and commit one page
+    pub(crate) async fn deliver(
+        &mut self,
+        records: &[JsonValue],
+        next_link: Option<String>,
+    ) -> Result<()> {
+        for batch in records.chunks(self.batch_size) {
+            self.sink.write_batch(batch).await?;
+        }
+        self.sink.flush().await?;
+
+        self.records_committed += records.len() as u64;
+        self.sink
+            .commit(&ReplicationCheckpoint {
+                resource: self.resource.clone(),
+                query: Some(self.query.clone()),
+                next_link,
+                records_committed: self.records_committed,
+            })
+            .await
+    }
+}
+
+/// Replication query for records changed since `high_water_mark`
+///
+/// Shared by the database mirrors. Filters on `timestamp_field ge <mark>`,
+/// combined with `filter` if given; with no mark yet it is a full replication.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn incremental_query(
+    resource: &str,
+    timestamp_field: &str,
+    high_water_mark: Option<String>,
+    filter: Option<&str>,
+) -> crate::queries::ReplicationQueryBuilder {
+    let since = high_water_mark.map(|mark| format!("{} ge {}", timestamp_field, mark));
+
+    let combined = match (filter, since) {
+        (Some(filter), Some(since)) => Some(format!("({}) and {}", filter, since)),
+        (Some(filter), None) => Some(filter.to_string()),
+        (None, since) => since,
+    };
+
+    let builder = crate::queries::ReplicationQueryBuilder::new(resource);
+    match combined {
+        Some(filter) => builder.filter(filter),
+        None => builder,
+    }
+}
+
+/// High-water mark an unfinished incremental run started from
+///
+/// Returns `Some(mark)` when `checkpoint` is an unfinished run of the query
+/// [`incremental_query`] builds for `timestamp_field` and `filter`; `mark` is
+/// `None` for an interrupted full replication. Resuming from that mark keeps
+/// the query, and so the checkpoint, stable while the mirror fills up.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn unfinished_mark(
+    checkpoint: &ReplicationCheckpoint,
+    timestamp_field: &str,
+    filter: Option<&str>,
+) -> Option<Option<String>> {
+    if checkpoint.is_complete() {
+        return None;
+    }
+    let query = checkpoint.query.as_deref()?;
+    let (path, params) = query.split_once('?').unwrap_or((query, ""));
+    if path != format!("{}/replication", checkpoint.resource) {
+        return None;
+    }
+
+    let stored = match params.split('&').find_map(|p| p.strip_prefix("$filter=")) {
+        Some(encoded) => Some(urlencoding::decode(encoded).ok()?.into_owned()),
+        None => None,
+    };
+    let Some(stored) = stored else {
+        return filter.is_none().then_some(None);
+    };
+    if Some(stored.as_str()) == filter {
+        return Some(None);
+    }
+
+    let prefix = match filter {
+        Some(filter) => format!("({}) and {} ge ", filter, timestamp_field),
+        None => format!("{} ge ", timestamp_field),
+    };
+    stored
+        .strip_prefix(&prefix)
+        .map(|mark| Some(mark.to_string()))
+}
+
+/// Sink that keeps committed records in memory
+///
+/// Useful for tests and small extracts. Records written but not yet committed
+/// are held separately and discarded if the run fails before the commit.
+///
+/// # Examples
+///
+/// ```
+/// # use reso_client::MemorySink;
+/// let sink = MemorySink::new();
+/// assert!(sink.records().is_empty());
+/// assert!(sink.checkpoint().is_none());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemorySink {
+    committed: Vec<JsonValue>,
+    pending: Vec<JsonValue>,
+    checkpoint: Option<ReplicationCheckpoint>,
+}
+
+impl MemorySink {
+    /// Create an empty memory sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Committed records
+    pub fn records(&self) -> &[JsonValue] {
+        &self.committed
+    }
+
+    /// Consume the sink and return the committed records
+    pub fn into_records(self) -> Vec<JsonValue> {
+        self.committed
+    }
+
+    /// Last committed checkpoint
+    pub fn checkpoint(&self) -> Option<&ReplicationCheckpoint> {
+        self.checkpoint.as_ref()
+    }
+}
+
+#[async_trait]
+impl RecordSink for MemorySink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        self.pending.extend_from_slice(records);
+        Ok(())
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.committed.append(&mut self.pending);
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        // Anything written after the last commit was never delivered
+        self.pending.clear();
+        Ok(self.checkpoint.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn checkpoint(next_link: Option<&str>, committed: u64) -> ReplicationCheckpoint {
+        ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication".to_string()),
+            next_link: next_link.map(|s| s.to_string()),
+            records_committed: committed,
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_is_complete() {
+        assert!(checkpoint(None, 10).is_complete());
+        assert!(!checkpoint(Some("https://api.example.com/next"), 10).is_complete());
+    }
+
+    #[test]
+    fn test_checkpoint_serde_roundtrip() {
+        let original = checkpoint(Some("https://api.example.com/next"), 2000);
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: ReplicationCheckpoint = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, original);
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    #[test]
+    fn test_unfinished_mark_recovers_the_started_query() {
+        let started = |mark: Option<&str>, filter: Option<&str>| ReplicationCheckpoint {
+            query: Some(
+                incremental_query(
+                    "Property",
+                    "ModificationTimestamp",
+                    mark.map(str::to_string),
+                    filter,
+                )
+                .top(2000)
+                .build()
+                .unwrap()
+                .to_odata_string(),
+            ),
+            ..checkpoint(Some("https://api.example.com/next"), 2000)
+        };
+        let mark = Some("2025-01-01T00:00:00Z");
+        let city = Some("City eq 'Austin'");
+
+        assert_eq!(
+            unfinished_mark(&started(mark, city), "ModificationTimestamp", city),
+            Some(Some("2025-01-01T00:00:00Z".to_string()))
+        );
+        assert_eq!(
+            unfinished_mark(&started(mark, None), "ModificationTimestamp", None),
+            Some(Some("2025-01-01T00:00:00Z".to_string()))
+        );
+        assert_eq!(
+            unfinished_mark(&started(None, city), "ModificationTimestamp", city),
+            Some(None)
+        );
+        assert_eq!(
+            unfinished_mark(&started(None, None), "ModificationTimestamp", None),
+            Some(None)
+        );
+        // Another filter, another field or a finished run
+        assert_eq!(
+            unfinished_mark(&started(mark, city), "ModificationTimestamp", None),
+            None
+        );
+        assert_eq!(
+            unfinished_mark(&started(mark, None), "PhotosChangeTimestamp", None),
+            None
+        );
+        let finished = ReplicationCheckpoint {
+            next_link: None,
+            ..started(mark, None)
+        };
+        assert_eq!(
+            unfinished_mark(&finished, "ModificationTimestamp", None),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_sink_commit_moves_pending() {
+        let mut sink = MemorySink::new();
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+
+        assert!(sink.records().is_empty());
+
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        assert_eq!(sink.records().len(), 1);
+        assert_eq!(sink.checkpoint(), Some(&checkpoint(None, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_memory_sink_discards_uncommitted_on_resume() {
+        let mut sink = MemorySink::new();
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+        sink.write_batch(&[json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+
+        let resumed = sink.last_checkpoint().await.unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        assert_eq!(resumed.unwrap().records_committed, 1);
+        assert_eq!(sink.into_records(), vec![json!({"ListingKey": "1"})]);
+    }
+
+    #[tokio::test]
+    async fn test_boxed_sink_delegates() {
+        let mut sink: Box<dyn RecordSink> = Box::new(MemorySink::new());
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        assert_eq!(
+            sink.last_checkpoint().await.unwrap(),
+            Some(checkpoint(None, 1))
+        );
+        assert_eq!(sink.max_batch_size(), None);
+    }
+}
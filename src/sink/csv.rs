@@ -0,0 +1,556 @@
+// src/sink/csv.rs
+
+//! CSV file sink with schema-driven columns
+
+use crate::error::{ResoError, Result};
+use crate::metadata::EntityType;
+use crate::queries::{Query, ReplicationQuery};
+use crate::sink::files::{Compression, ExportManifest, PartFiles, PartLayout};
+use crate::sink::{RecordSink, ReplicationCheckpoint};
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
+
+/// Sink that exports records to rotating CSV files
+///
+/// Columns are fixed up front, taken from a `$select` list, a parsed
+/// `$metadata` entity type, or an explicit list. Every part starts with a
+/// header row. Parts and the manifest are laid out like
+/// [`JsonFileSink`](crate::JsonFileSink)'s.
+///
+/// # Column Values
+///
+/// - Dotted column names reach into expanded or complex values, e.g.
+///   `ListAgent.MemberFullName`.
+/// - Collections (e.g. `Appliances`, or `Media.MediaURL` through an expanded
+///   collection) are joined with `,` by default; see
+///   [`collection_delimiter`](Self::collection_delimiter). One column can be
+///   [exploded](Self::explode) into one row per element instead.
+/// - Objects and arrays that are not reached through a dotted name are written
+///   as JSON text.
+/// - Nulls and missing fields are written as an empty field unless a
+///   [null value](Self::null_value) is set.
+///
+/// Fields containing the delimiter, quotes or line breaks are quoted per RFC 4180.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "client")]
+/// # use reso_client::{ResoClient, QueryBuilder, CsvFileSink};
+/// # #[cfg(feature = "client")]
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let query = QueryBuilder::new("Property")
+///     .filter("City eq 'Austin'")
+///     .select(&["ListingKey", "ListPrice", "Appliances", "ListAgent"])
+///     .expand(&["ListAgent"])
+///     .build()?;
+///
+/// let mut sink = CsvFileSink::for_query("exports", "austin", &query)?
+///     .with_column("ListAgent.MemberFullName")
+///     .null_value("NULL");
+/// client.execute_into(&query, &mut sink).await?;
+/// sink.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CsvFileSink {
+    parts: PartFiles,
+    columns: Vec<Column>,
+    delimiter: char,
+    collection_delimiter: String,
+    null_value: String,
+    explode: Option<usize>,
+}
+
+/// A CSV column and the path it reads from each record
+struct Column {
+    name: String,
+    path: Vec<String>,
+}
+
+/// A column value resolved from one record
+enum Cell {
+    Scalar(Option<String>),
+    Collection(Vec<String>),
+}
+
+impl CsvFileSink {
+    /// Create a sink with an explicit column list
+    pub fn new<I, S>(dir: impl Into<PathBuf>, prefix: impl Into<String>, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut sink = Self {
+            parts: PartFiles::new(dir, prefix, "csv", "csv", PartLayout::default()),
+            columns: Vec::new(),
+            delimiter: ',',
+            collection_delimiter: ",".to_string(),
+            null_value: String::new(),
+            explode: None,
+        };
+        for column in columns {
+            sink.columns.push(Column::new(column.into()));
+        }
+        sink.update_header();
+        sink
+    }
+
+    /// Create a sink whose columns are the query's `$select` list
+    ///
+    /// Returns [`ResoError::InvalidQuery`] if the query has no `$select`.
+    pub fn for_query(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        query: &Query,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            dir,
+            prefix,
+            select_columns(query.select_fields())?,
+        ))
+    }
+
+    /// Create a sink whose columns are the replication query's `$select` list
+    ///
+    /// Returns [`ResoError::InvalidQuery`] if the query has no `$select`.
+    pub fn for_replication_query(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        query: &ReplicationQuery,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            dir,
+            prefix,
+            select_columns(query.select_fields())?,
+        ))
+    }
+
+    /// Create a sink with one column per structural property of an entity type
+    ///
+    /// Use [`Metadata::entity_type`](crate::Metadata::entity_type) on the result
+    /// of [`ResoClient::fetch_parsed_metadata`](crate::ResoClient::fetch_parsed_metadata)
+    /// to get the entity type.
+    pub fn for_entity_type(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        entity_type: &EntityType,
+    ) -> Self {
+        Self::new(
+            dir,
+            prefix,
+            entity_type.properties.iter().map(|p| p.name.clone()),
+        )
+    }
+
+    /// Append a column, e.g. a dotted path into an expanded entity
+    pub fn with_column(mut self, column: impl Into<String>) -> Self {
+        self.columns.push(Column::new(column.into()));
+        self.update_header();
+        self
+    }
+
+    /// Field delimiter (default `,`)
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self.update_header();
+        self
+    }
+
+    /// Separator placed between joined collection elements (default `,`)
+    pub fn collection_delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.collection_delimiter = delimiter.into();
+        self
+    }
+
+    /// Write one row per element of a collection column
+    ///
+    /// Other columns are repeated on every row. Records where the column is
+    /// null or empty still produce a single row. Ignored if `column` is not
+    /// one of the sink's columns.
+    pub fn explode(mut self, column: &str) -> Self {
+        self.explode = self.columns.iter().position(|c| c.name == column);
+        self
+    }
+
+    /// Text written for null and missing values (default: empty field)
+    pub fn null_value(mut self, value: impl Into<String>) -> Self {
+        self.null_value = value.into();
+        self
+    }
+
+    /// Compress every part file
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.parts.compression = compression;
+        self
+    }
+
+    /// Start a new part once the current one holds `records` records
+    ///
+    /// Counts source records, so exploded rows of one record stay together.
+    pub fn rotate_after_records(mut self, records: u64) -> Self {
+        self.parts.max_records = Some(records.max(1));
+        self
+    }
+
+    /// Start a new part once the current one holds `bytes` bytes of CSV
+    ///
+    /// Counts uncompressed bytes, so compressed parts end up smaller.
+    pub fn rotate_after_bytes(mut self, bytes: u64) -> Self {
+        self.parts.max_bytes = Some(bytes.max(1));
+        self
+    }
+
+    /// Column names in output order
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    /// Path of the manifest file
+    pub fn manifest_path(&self) -> PathBuf {
+        self.parts.manifest_path()
+    }
+
+    /// Close the open part and write the final manifest
+    pub fn finish(&mut self) -> Result<ExportManifest> {
+        self.parts.finish()
+    }
+
+    fn update_header(&mut self) {
+        let mut header = Vec::new();
+        let names: Vec<Option<String>> =
+            self.columns.iter().map(|c| Some(c.name.clone())).collect();
+        self.write_row(&mut header, &names);
+        self.parts.set_layout(PartLayout {
+            prologue: header,
+            ..Default::default()
+        });
+    }
+
+    /// Encode one record as one or more CSV rows
+    fn encode(&self, record: &JsonValue) -> Vec<u8> {
+        let cells: Vec<Cell> = self
+            .columns
+            .iter()
+            .map(|column| column.resolve(record))
+            .collect();
+
+        let mut row: Vec<Option<String>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Scalar(value) => value.clone(),
+                Cell::Collection(values) => Some(values.join(&self.collection_delimiter)),
+            })
+            .collect();
+
+        let mut out = Vec::new();
+        match self.explode.map(|i| (i, &cells[i])) {
+            Some((i, Cell::Collection(values))) if !values.is_empty() => {
+                for value in values {
+                    row[i] = Some(value.clone());
+                    self.write_row(&mut out, &row);
+                }
+            }
+            Some((i, Cell::Collection(_))) => {
+                row[i] = None;
+                self.write_row(&mut out, &row);
+            }
+            _ => self.write_row(&mut out, &row),
+        }
+        out
+    }
+
+    fn write_row(&self, out: &mut Vec<u8>, fields: &[Option<String>]) {
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(self.delimiter.encode_utf8(&mut buf).as_bytes());
+            }
+            let text = field.as_deref().unwrap_or(&self.null_value);
+            if text.contains([self.delimiter, '"', '\n', '\r']) {
+                out.push(b'"');
+                out.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+                out.push(b'"');
+            } else {
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        out.push(b'\n');
+    }
+}
+
+impl Column {
+    fn new(name: String) -> Self {
+        let path = name.split('.').map(|s| s.to_string()).collect();
+        Self { name, path }
+    }
+
+    fn resolve(&self, record: &JsonValue) -> Cell {
+        let mut values = Vec::new();
+        if collect_values(record, &self.path, &mut values) {
+            Cell::Collection(values.into_iter().filter_map(render).collect())
+        } else {
+            Cell::Scalar(values.first().and_then(|v| render(v)))
+        }
+    }
+}
+
+/// Collect the values at `path`, returning whether a collection was crossed
+fn collect_values<'a>(value: &'a JsonValue, path: &[String], out: &mut Vec<&'a JsonValue>) -> bool {
+    match (path.split_first(), value) {
+        (None, JsonValue::Array(items)) => {
+            out.extend(items);
+            true
+        }
+        (None, value) => {
+            out.push(value);
+            false
+        }
+        (Some(_), JsonValue::Array(items)) => {
+            for item in items {
+                collect_values(item, path, out);
+            }
+            true
+        }
+        (Some((head, rest)), JsonValue::Object(map)) => match map.get(head) {
+            Some(child) => collect_values(child, rest, out),
+            None => false,
+        },
+        (Some(_), _) => false,
+    }
+}
+
+/// Text for a single value, `None` for null
+fn render(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Number(_) | JsonValue::Bool(_) => Some(value.to_string()),
+        JsonValue::Array(_) | JsonValue::Object(_) => Some(value.to_string()),
+    }
+}
+
+fn select_columns(fields: Option<&[String]>) -> Result<Vec<String>> {
+    fields.map(|f| f.to_vec()).ok_or_else(|| {
+        ResoError::InvalidQuery(
+            "CSV export needs a $select list to derive columns; \
+             use CsvFileSink::new or CsvFileSink::for_entity_type instead"
+                .to_string(),
+        )
+    })
+}
+
+#[async_trait]
+impl RecordSink for CsvFileSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        for record in records {
+            let bytes = self.encode(record);
+            self.parts.write_record(&bytes)?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.parts.end_frame()
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.parts.commit(checkpoint)
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        self.parts.last_checkpoint()
+    }
+
+    async fn resume(&mut self, _checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.parts.resume()
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.parts.reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::{QueryBuilder, ReplicationQueryBuilder};
+    use serde_json::json;
+
+    fn sink(columns: &[&str]) -> CsvFileSink {
+        CsvFileSink::new("unused", "test", columns.iter().copied())
+    }
+
+    fn encode(sink: &CsvFileSink, record: JsonValue) -> String {
+        String::from_utf8(sink.encode(&record)).unwrap()
+    }
+
+    #[test]
+    fn test_scalar_values() {
+        let sink = sink(&["ListingKey", "ListPrice", "NewConstructionYN"]);
+        let row = encode(
+            &sink,
+            json!({"ListingKey": "1", "ListPrice": 450000.5, "NewConstructionYN": true}),
+        );
+        assert_eq!(row, "1,450000.5,true\n");
+    }
+
+    #[test]
+    fn test_quoting() {
+        let sink = sink(&["PublicRemarks", "City"]);
+        let row = encode(
+            &sink,
+            json!({"PublicRemarks": "Big \"yard\", pool\nand spa", "City": "Austin"}),
+        );
+        assert_eq!(row, "\"Big \"\"yard\"\", pool\nand spa\",Austin\n");
+    }
+
+    #[test]
+    fn test_null_value() {
+        let sink = sink(&["ListingKey", "ListPrice", "Missing"]).null_value("NULL");
+        let row = encode(&sink, json!({"ListingKey": "1", "ListPrice": null}));
+        assert_eq!(row, "1,NULL,NULL\n");
+    }
+
+    #[test]
+    fn test_default_null_is_empty() {
+        let sink = sink(&["ListingKey", "ListPrice"]);
+        assert_eq!(encode(&sink, json!({"ListingKey": "1"})), "1,\n");
+    }
+
+    #[test]
+    fn test_collection_joined() {
+        let sink = sink(&["ListingKey", "Appliances"]).collection_delimiter("|");
+        let row = encode(
+            &sink,
+            json!({"ListingKey": "1", "Appliances": ["Dishwasher", "Refrigerator"]}),
+        );
+        assert_eq!(row, "1,Dishwasher|Refrigerator\n");
+    }
+
+    #[test]
+    fn test_collection_default_delimiter_is_quoted() {
+        let sink = sink(&["Appliances"]);
+        let row = encode(&sink, json!({"Appliances": ["Dishwasher", "Refrigerator"]}));
+        assert_eq!(row, "\"Dishwasher,Refrigerator\"\n");
+    }
+
+    #[test]
+    fn test_collection_exploded() {
+        let sink = sink(&["ListingKey", "Appliances"]).explode("Appliances");
+        let row = encode(
+            &sink,
+            json!({"ListingKey": "1", "Appliances": ["Dishwasher", "Refrigerator"]}),
+        );
+        assert_eq!(row, "1,Dishwasher\n1,Refrigerator\n");
+    }
+
+    #[test]
+    fn test_explode_empty_collection_keeps_row() {
+        let sink = sink(&["ListingKey", "Appliances"])
+            .explode("Appliances")
+            .null_value("NULL");
+        assert_eq!(
+            encode(&sink, json!({"ListingKey": "1", "Appliances": []})),
+            "1,NULL\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_expanded_values() {
+        let sink = sink(&["ListingKey", "ListAgent.MemberFullName", "Media.MediaURL"]);
+        let row = encode(
+            &sink,
+            json!({
+                "ListingKey": "1",
+                "ListAgent": {"MemberFullName": "Jane Doe"},
+                "Media": [{"MediaURL": "a.jpg"}, {"MediaURL": "b.jpg"}]
+            }),
+        );
+        assert_eq!(row, "1,Jane Doe,\"a.jpg,b.jpg\"\n");
+    }
+
+    #[test]
+    fn test_unflattened_object_written_as_json() {
+        let sink = sink(&["ListAgent"]).with_delimiter('\t');
+        let row = encode(&sink, json!({"ListAgent": {"MemberKey": "A1"}}));
+        assert_eq!(row, "\"{\"\"MemberKey\"\":\"\"A1\"\"}\"\n");
+    }
+
+    #[test]
+    fn test_header_uses_delimiter() {
+        let sink = sink(&["ListingKey", "City"]).with_delimiter(';');
+        let mut header = Vec::new();
+        let names: Vec<Option<String>> =
+            sink.columns().iter().map(|c| Some(c.to_string())).collect();
+        sink.write_row(&mut header, &names);
+        assert_eq!(header, b"ListingKey;City\n");
+    }
+
+    #[test]
+    fn test_for_query_uses_select() {
+        let query = QueryBuilder::new("Property")
+            .select(&["ListingKey", "City"])
+            .build()
+            .unwrap();
+        let sink = CsvFileSink::for_query("unused", "test", &query).unwrap();
+        assert_eq!(sink.columns(), vec!["ListingKey", "City"]);
+    }
+
+    #[test]
+    fn test_for_query_requires_select() {
+        let query = QueryBuilder::new("Property").build().unwrap();
+        let result = CsvFileSink::for_query("unused", "test", &query);
+        assert!(matches!(result, Err(ResoError::InvalidQuery(_))));
+
+        let replication = ReplicationQueryBuilder::new("Property").build().unwrap();
+        let result = CsvFileSink::for_replication_query("unused", "test", &replication);
+        assert!(matches!(result, Err(ResoError::InvalidQuery(_))));
+    }
+
+    #[test]
+    fn test_for_entity_type() {
+        let entity_type = EntityType {
+            name: "Property".to_string(),
+            properties: vec![
+                crate::metadata::Property {
+                    name: "ListingKey".to_string(),
+                    type_name: "Edm.String".to_string(),
+                    ..Default::default()
+                },
+                crate::metadata::Property {
+                    name: "ListPrice".to_string(),
+                    type_name: "Edm.Decimal".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let sink = CsvFileSink::for_entity_type("unused", "test", &entity_type);
+        assert_eq!(sink.columns(), vec!["ListingKey", "ListPrice"]);
+    }
+
+    #[tokio::test]
+    async fn test_every_part_has_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink =
+            CsvFileSink::new(dir.path(), "property", ["ListingKey"]).rotate_after_records(1);
+
+        sink.write_batch(&[json!({"ListingKey": "1"}), json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+        sink.commit(&ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: None,
+            next_link: None,
+            records_committed: 2,
+        })
+        .await
+        .unwrap();
+
+        let second = std::fs::read_to_string(dir.path().join("property-00001.csv")).unwrap();
+        assert_eq!(second, "ListingKey\n2\n");
+        assert_eq!(sink.finish().unwrap().format, "csv");
+    }
+}
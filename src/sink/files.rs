@@ -0,0 +1,780 @@
+// src/sink/files.rs
+
+//! Rotating, optionally compressed part files shared by the file export sinks
+//!
+//! File sinks write records into numbered part files
+//! (`{prefix}-00000.ndjson`, `{prefix}-00001.ndjson`, ...) and keep a
+//! `{prefix}-manifest.json` next to them listing every part with its record
+//! count, size and SHA-256 checksum.
+//!
+//! Every flush ends a compression frame (a gzip member or zstd frame), and the
+//! manifest written on commit records the committed length of the part that is
+//! still open. A resumed run truncates that part back to the committed length
+//! and appends new frames, so the files stay valid after a crash.
+
+use crate::error::{ResoError, Result};
+use crate::sink::ReplicationCheckpoint;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Compression applied to exported part files
+///
+/// Gzip and zstd are available with the `gzip` and `zstd` cargo features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Plain, uncompressed files
+    #[default]
+    None,
+
+    /// Gzip (`.gz`) compression
+    #[cfg(feature = "gzip")]
+    Gzip,
+
+    /// Zstandard (`.zst`) compression
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Name recorded in the manifest
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => "gzip",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// File name suffix appended after the format extension
+    fn suffix(&self) -> &'static str {
+        match self {
+            Compression::None => "",
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => ".gz",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => ".zst",
+        }
+    }
+}
+
+/// Manifest describing the parts produced by a file export
+///
+/// Written to `{prefix}-manifest.json` on every commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// Record format (e.g., `ndjson`, `json`, `csv`)
+    pub format: String,
+
+    /// Compression applied to every part
+    pub compression: String,
+
+    /// Parts in write order
+    pub parts: Vec<ManifestPart>,
+
+    /// Total records across all parts
+    pub total_records: u64,
+
+    /// Replication checkpoint committed together with this manifest
+    pub checkpoint: Option<ReplicationCheckpoint>,
+
+    /// Whether the export finished
+    pub complete: bool,
+}
+
+/// One exported part file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestPart {
+    /// File name relative to the manifest's directory
+    pub file: String,
+
+    /// Number of records in the part
+    pub records: u64,
+
+    /// Size of the part file in bytes
+    pub bytes: u64,
+
+    /// Size of the part's contents before compression
+    ///
+    /// Equal to `bytes` for formats that compress internally, such as Parquet.
+    /// Manifests written before this field existed read it as `0`.
+    #[serde(default)]
+    pub uncompressed_bytes: u64,
+
+    /// Hex-encoded SHA-256 of the part file
+    ///
+    /// `None` while the part is still being written.
+    pub sha256: Option<String>,
+}
+
+/// Bytes written around and between records in every part
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PartLayout {
+    /// Written at the start of every part (e.g., `[` or a CSV header)
+    pub prologue: Vec<u8>,
+    /// Written between two records of the same part
+    pub separator: Vec<u8>,
+    /// Written when a part is closed (e.g., `]`)
+    pub epilogue: Vec<u8>,
+}
+
+/// Writer for a single compression frame
+enum FrameWriter {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl FrameWriter {
+    fn start(file: File, compression: Compression) -> io::Result<Self> {
+        let writer = BufWriter::new(file);
+        Ok(match compression {
+            Compression::None => FrameWriter::Plain(writer),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => FrameWriter::Gzip(flate2::write::GzEncoder::new(
+                writer,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => FrameWriter::Zstd(zstd::stream::write::Encoder::new(writer, 0)?),
+        })
+    }
+
+    /// Complete the frame and hand back the underlying file
+    // Only the plain variant exists without compression features
+    #[allow(clippy::infallible_destructuring_match)]
+    fn finish(self) -> io::Result<File> {
+        let writer = match self {
+            FrameWriter::Plain(writer) => writer,
+            #[cfg(feature = "gzip")]
+            FrameWriter::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            FrameWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        writer.into_inner().map_err(|e| e.into_error())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            FrameWriter::Plain(writer) => writer.write_all(buf),
+            #[cfg(feature = "gzip")]
+            FrameWriter::Gzip(encoder) => encoder.write_all(buf),
+            #[cfg(feature = "zstd")]
+            FrameWriter::Zstd(encoder) => encoder.write_all(buf),
+        }
+    }
+}
+
+/// File handle of an open part, either between frames or inside one
+enum PartHandle {
+    Idle(File),
+    Framing(FrameWriter),
+}
+
+/// The part currently being written
+struct OpenPart {
+    file_name: String,
+    handle: Option<PartHandle>,
+    records: u64,
+    uncompressed_bytes: u64,
+    committed_records: u64,
+    committed_uncompressed_bytes: u64,
+    committed_len: u64,
+}
+
+impl OpenPart {
+    /// Write chunks into the current frame, starting a new frame if needed
+    fn write(&mut self, compression: Compression, chunks: &[&[u8]]) -> Result<()> {
+        let mut writer = match self.handle.take() {
+            Some(PartHandle::Idle(file)) => {
+                FrameWriter::start(file, compression).map_err(|e| io_error(&self.file_name, e))?
+            }
+            Some(PartHandle::Framing(writer)) => writer,
+            None => return Err(not_open(&self.file_name)),
+        };
+
+        let result = chunks.iter().try_for_each(|chunk| writer.write_all(chunk));
+        self.handle = Some(PartHandle::Framing(writer));
+        result.map_err(|e| io_error(&self.file_name, e))
+    }
+
+    /// Finish the current frame and sync the file to disk
+    fn end_frame(&mut self) -> Result<()> {
+        let file = match self.handle.take() {
+            Some(PartHandle::Framing(writer)) => writer.finish(),
+            Some(PartHandle::Idle(file)) => Ok(file),
+            None => return Err(not_open(&self.file_name)),
+        }
+        .map_err(|e| io_error(&self.file_name, e))?;
+
+        let synced = file.sync_data().and_then(|_| file.metadata());
+        self.handle = Some(PartHandle::Idle(file));
+        self.committed_len = synced.map_err(|e| io_error(&self.file_name, e))?.len();
+        self.committed_records = self.records;
+        self.committed_uncompressed_bytes = self.uncompressed_bytes;
+        Ok(())
+    }
+}
+
+/// Set of rotating part files plus their manifest
+pub(crate) struct PartFiles {
+    dir: PathBuf,
+    prefix: String,
+    format: &'static str,
+    extension: &'static str,
+    layout: PartLayout,
+    pub(crate) compression: Compression,
+    pub(crate) max_records: Option<u64>,
+    pub(crate) max_bytes: Option<u64>,
+    parts: Vec<ManifestPart>,
+    open: Option<OpenPart>,
+    checkpoint: Option<ReplicationCheckpoint>,
+    /// Manifest of the previous run, loaded by `last_checkpoint`
+    previous: Option<ExportManifest>,
+    /// Parts of an overwritten export, deleted once the manifest drops them
+    stale: Vec<String>,
+}
+
+impl PartFiles {
+    pub(crate) fn new(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        format: &'static str,
+        extension: &'static str,
+        layout: PartLayout,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.into(),
+            format,
+            extension,
+            layout,
+            compression: Compression::None,
+            max_records: None,
+            max_bytes: None,
+            parts: Vec::new(),
+            open: None,
+            checkpoint: None,
+            previous: None,
+            stale: Vec::new(),
+        }
+    }
+
+    /// Replace the part layout used for parts opened from now on
+    pub(crate) fn set_layout(&mut self, layout: PartLayout) {
+        self.layout = layout;
+    }
+
+    /// Path of the manifest file
+    pub(crate) fn manifest_path(&self) -> PathBuf {
+        self.dir.join(format!("{}-manifest.json", self.prefix))
+    }
+
+    /// Append one encoded record, rotating to a new part when a limit is reached
+    pub(crate) fn write_record(&mut self, record: &[u8]) -> Result<()> {
+        if self.limit_reached() {
+            self.close_part()?;
+        }
+        if self.open.is_none() {
+            self.open_part()?;
+        }
+
+        let open = self.open.as_mut().expect("part opened above");
+        let separator: &[u8] = if open.records > 0 {
+            &self.layout.separator
+        } else {
+            &[]
+        };
+        open.write(self.compression, &[separator, record])?;
+
+        open.records += 1;
+        open.uncompressed_bytes += (separator.len() + record.len()) as u64;
+        Ok(())
+    }
+
+    /// Finish the current compression frame and make the open part durable
+    pub(crate) fn end_frame(&mut self) -> Result<()> {
+        match self.open.as_mut() {
+            Some(open) => open.end_frame(),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the open part, writing its epilogue and checksum
+    pub(crate) fn close_part(&mut self) -> Result<()> {
+        let Some(mut open) = self.open.take() else {
+            return Ok(());
+        };
+
+        open.write(self.compression, &[&self.layout.epilogue])?;
+        open.end_frame()?;
+
+        let path = self.dir.join(&open.file_name);
+        let sha256 = sha256_file(&path).map_err(|e| io_error(&open.file_name, e))?;
+        self.parts.push(ManifestPart {
+            file: open.file_name,
+            records: open.records,
+            bytes: open.committed_len,
+            uncompressed_bytes: open.uncompressed_bytes,
+            sha256: Some(sha256),
+        });
+        Ok(())
+    }
+
+    /// Make written records durable and record `checkpoint` in the manifest
+    ///
+    /// A complete checkpoint also closes the open part.
+    pub(crate) fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.end_frame()?;
+        let complete = checkpoint.is_complete();
+        if complete {
+            self.close_part()?;
+        }
+
+        let manifest = self.manifest(Some(checkpoint.clone()), complete);
+        self.write_manifest(&manifest)?;
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    /// Load the checkpoint of a previous run without touching its parts
+    pub(crate) fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        self.previous = self.load_manifest()?;
+        Ok(self
+            .previous
+            .as_ref()
+            .and_then(|manifest| manifest.checkpoint.clone()))
+    }
+
+    /// Continue the previous run, restoring its parts if it is unfinished
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        let manifest = match self.previous.take() {
+            Some(manifest) => Some(manifest),
+            None => self.load_manifest()?,
+        };
+        let Some(manifest) = manifest else {
+            return Ok(());
+        };
+
+        if !manifest.complete {
+            self.restore(&manifest)?;
+        }
+        self.checkpoint = manifest.checkpoint;
+        Ok(())
+    }
+
+    /// Start a fresh run that overwrites the previous one
+    ///
+    /// Parts of the previous export that the new run does not rewrite are
+    /// deleted with the first manifest it writes, so a directory glob never
+    /// picks up records of the old run.
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        let previous = match self.previous.take() {
+            Some(manifest) => Some(manifest),
+            None => self.load_manifest()?,
+        };
+        self.stale = previous
+            .map(|manifest| manifest.parts.into_iter().map(|p| p.file).collect())
+            .unwrap_or_default();
+        self.parts.clear();
+        self.open = None;
+        self.checkpoint = None;
+        Ok(())
+    }
+
+    /// Close the open part and write the final manifest
+    pub(crate) fn finish(&mut self) -> Result<ExportManifest> {
+        self.close_part()?;
+        let complete = self
+            .checkpoint
+            .as_ref()
+            .is_none_or(ReplicationCheckpoint::is_complete);
+        let manifest = self.manifest(self.checkpoint.clone(), complete);
+        self.write_manifest(&manifest)?;
+        Ok(manifest)
+    }
+
+    /// Build the manifest for the current state
+    pub(crate) fn manifest(
+        &self,
+        checkpoint: Option<ReplicationCheckpoint>,
+        complete: bool,
+    ) -> ExportManifest {
+        let mut parts = self.parts.clone();
+        if let Some(open) = &self.open {
+            parts.push(ManifestPart {
+                file: open.file_name.clone(),
+                records: open.committed_records,
+                bytes: open.committed_len,
+                uncompressed_bytes: open.committed_uncompressed_bytes,
+                sha256: None,
+            });
+        }
+
+        ExportManifest {
+            format: self.format.to_string(),
+            compression: self.compression.name().to_string(),
+            total_records: parts.iter().map(|p| p.records).sum(),
+            parts,
+            checkpoint,
+            complete,
+        }
+    }
+
+    /// Atomically replace the manifest file
+    pub(crate) fn write_manifest(&mut self, manifest: &ExportManifest) -> Result<()> {
+        write_manifest_file(&self.manifest_path(), manifest)?;
+        remove_stale_parts(&self.dir, &mut self.stale, manifest)
+    }
+
+    /// Read the manifest left by a previous run, if any
+    pub(crate) fn load_manifest(&self) -> Result<Option<ExportManifest>> {
+        load_manifest_file(&self.manifest_path())
+    }
+
+    /// Continue an unfinished export described by `manifest`
+    ///
+    /// Closed parts are kept as they are. The part that was open is truncated
+    /// to its committed length so records written after the last commit are
+    /// dropped before the run resumes.
+    pub(crate) fn restore(&mut self, manifest: &ExportManifest) -> Result<()> {
+        self.parts.clear();
+        self.open = None;
+
+        for part in &manifest.parts {
+            if part.sha256.is_some() {
+                self.parts.push(part.clone());
+                continue;
+            }
+
+            let path = self.dir.join(&part.file);
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .map_err(|e| io_error(&part.file, e))?;
+            file.set_len(part.bytes)
+                .and_then(|_| file.seek(SeekFrom::End(0)))
+                .map_err(|e| io_error(&part.file, e))?;
+
+            self.open = Some(OpenPart {
+                file_name: part.file.clone(),
+                handle: Some(PartHandle::Idle(file)),
+                records: part.records,
+                uncompressed_bytes: part.uncompressed_bytes,
+                committed_records: part.records,
+                committed_uncompressed_bytes: part.uncompressed_bytes,
+                committed_len: part.bytes,
+            });
+        }
+        Ok(())
+    }
+
+    fn limit_reached(&self) -> bool {
+        let Some(open) = &self.open else {
+            return false;
+        };
+        self.max_records.is_some_and(|max| open.records >= max)
+            || self
+                .max_bytes
+                .is_some_and(|max| open.uncompressed_bytes >= max)
+    }
+
+    fn open_part(&mut self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir.display(), e))?;
+
+        let file_name = format!(
+            "{}-{:05}.{}{}",
+            self.prefix,
+            self.parts.len(),
+            self.extension,
+            self.compression.suffix()
+        );
+        let file = File::create(self.dir.join(&file_name)).map_err(|e| io_error(&file_name, e))?;
+
+        let mut open = OpenPart {
+            file_name,
+            handle: Some(PartHandle::Idle(file)),
+            records: 0,
+            uncompressed_bytes: self.layout.prologue.len() as u64,
+            committed_records: 0,
+            committed_uncompressed_bytes: 0,
+            committed_len: 0,
+        };
+        open.write(self.compression, &[&self.layout.prologue])?;
+
+        self.open = Some(open);
+        Ok(())
+    }
+}
+
+/// Delete the `stale` parts of an overwritten export that `manifest` does not list
+pub(crate) fn remove_stale_parts(
+    dir: &Path,
+    stale: &mut Vec<String>,
+    manifest: &ExportManifest,
+) -> Result<()> {
+    for file in std::mem::take(stale) {
+        // Only plain file names next to the manifest
+        let plain = Path::new(&file)
+            .file_name()
+            .is_some_and(|name| name == file.as_str());
+        if !plain || manifest.parts.iter().any(|part| part.file == file) {
+            continue;
+        }
+        match std::fs::remove_file(dir.join(&file)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(io_error(&file, e)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Atomically replace a manifest file via a temporary file and rename
+pub(crate) fn write_manifest_file(path: &Path, manifest: &ExportManifest) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| ResoError::Sink(format!("Failed to encode manifest: {}", e)))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| io_error(&dir.display(), e))?;
+    }
+    let mut file = File::create(&tmp).map_err(|e| io_error(&tmp.display(), e))?;
+    file.write_all(&json)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| io_error(&tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| io_error(&path.display(), e))
+}
+
+/// Read a manifest file, returning `None` if it does not exist
+pub(crate) fn load_manifest_file(path: &Path) -> Result<Option<ExportManifest>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(io_error(&path.display(), e)),
+    };
+
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| ResoError::Sink(format!("Invalid manifest {}: {}", path.display(), e)))
+}
+
+/// Hex-encoded SHA-256 of a file's contents
+pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+pub(crate) fn io_error(target: &dyn std::fmt::Display, e: io::Error) -> ResoError {
+    ResoError::Sink(format!("I/O error on {}: {}", target, e))
+}
+
+fn not_open(file_name: &str) -> ResoError {
+    ResoError::Sink(format!("Part {} is not open", file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(dir: &Path) -> PartFiles {
+        PartFiles::new(
+            dir,
+            "export",
+            "ndjson",
+            "ndjson",
+            PartLayout {
+                separator: b"\n".to_vec(),
+                epilogue: b"\n".to_vec(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_rotation_by_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.max_records = Some(2);
+
+        for i in 0..5 {
+            files.write_record(format!("{}", i).as_bytes()).unwrap();
+        }
+        files.close_part().unwrap();
+
+        let manifest = files.manifest(None, true);
+        let counts: Vec<u64> = manifest.parts.iter().map(|p| p.records).collect();
+        assert_eq!(counts, vec![2, 2, 1]);
+        assert_eq!(manifest.total_records, 5);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("export-00001.ndjson")).unwrap(),
+            "2\n3\n"
+        );
+    }
+
+    #[test]
+    fn test_rotation_by_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.max_bytes = Some(10);
+
+        for _ in 0..3 {
+            files.write_record(b"0123456789").unwrap();
+        }
+        files.close_part().unwrap();
+
+        assert_eq!(files.manifest(None, true).parts.len(), 3);
+    }
+
+    #[test]
+    fn test_checksum_matches_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.write_record(b"abc").unwrap();
+        files.close_part().unwrap();
+
+        let manifest = files.manifest(None, true);
+        let part = &manifest.parts[0];
+        // sha256("abc\n")
+        assert_eq!(
+            part.sha256.as_deref(),
+            Some("edeaaff3f1774ad2888673770c6d64097e391bc362d7d6fb34982ddf0efd18cb")
+        );
+        assert_eq!(part.bytes, 4);
+    }
+
+    #[test]
+    fn test_open_part_listed_without_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.write_record(b"abc").unwrap();
+        files.end_frame().unwrap();
+        files.write_record(b"def").unwrap();
+
+        let manifest = files.manifest(None, false);
+        assert_eq!(manifest.parts[0].records, 1);
+        assert_eq!(manifest.parts[0].bytes, 3);
+        assert_eq!(manifest.parts[0].sha256, None);
+    }
+
+    #[test]
+    fn test_restore_truncates_uncommitted_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.write_record(b"committed").unwrap();
+        files.end_frame().unwrap();
+        let manifest = files.manifest(None, false);
+        files.write_record(b"lost").unwrap();
+        files.end_frame().unwrap();
+        drop(files);
+
+        let mut resumed = parts(dir.path());
+        resumed.restore(&manifest).unwrap();
+        resumed.write_record(b"resumed").unwrap();
+        resumed.close_part().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("export-00000.ndjson")).unwrap(),
+            "committed\nresumed\n"
+        );
+        assert_eq!(resumed.manifest(None, true).parts[0].records, 2);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_restore_keeps_uncompressed_size_for_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.compression = Compression::Gzip;
+        files.max_bytes = Some(25);
+        files.write_record(b"0123456789").unwrap();
+        files.write_record(b"0123456789").unwrap();
+        files.end_frame().unwrap();
+        let manifest = files.manifest(None, false);
+        assert_eq!(manifest.parts[0].uncompressed_bytes, 21);
+        drop(files);
+
+        let mut resumed = parts(dir.path());
+        resumed.compression = Compression::Gzip;
+        resumed.max_bytes = Some(25);
+        resumed.restore(&manifest).unwrap();
+        resumed.write_record(b"0123456789").unwrap();
+        resumed.write_record(b"0123456789").unwrap();
+        resumed.close_part().unwrap();
+
+        let counts: Vec<u64> = resumed
+            .manifest(None, true)
+            .parts
+            .iter()
+            .map(|p| p.records)
+            .collect();
+        assert_eq!(counts, vec![3, 1]);
+    }
+
+    #[test]
+    fn test_fresh_run_removes_parts_of_longer_previous_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        files.max_records = Some(1);
+        for record in [b"1", b"2", b"3"] {
+            files.write_record(record).unwrap();
+        }
+        files.finish().unwrap();
+        assert!(dir.path().join("export-00002.ndjson").exists());
+
+        let mut rerun = parts(dir.path());
+        rerun.max_records = Some(1);
+        rerun.last_checkpoint().unwrap();
+        rerun.reset().unwrap();
+        rerun.write_record(b"new").unwrap();
+        let manifest = rerun.finish().unwrap();
+
+        let mut remaining: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec!["export-00000.ndjson", "export-manifest.json"]
+        );
+        assert_eq!(manifest.total_records, 1);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("export-00000.ndjson")).unwrap(),
+            "new\n"
+        );
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = parts(dir.path());
+        assert!(files.load_manifest().unwrap().is_none());
+
+        files.write_record(b"abc").unwrap();
+        files.close_part().unwrap();
+        let manifest = files.manifest(None, true);
+        files.write_manifest(&manifest).unwrap();
+
+        assert_eq!(files.load_manifest().unwrap(), Some(manifest));
+        assert!(!dir.path().join("export-manifest.json.tmp").exists());
+    }
+}
@@ -0,0 +1,333 @@
+// src/sink/json.rs
+
+//! NDJSON and JSON array file sinks
+
+use crate::error::{ResoError, Result};
+use crate::sink::files::{Compression, ExportManifest, PartFiles, PartLayout};
+use crate::sink::{RecordSink, ReplicationCheckpoint};
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
+
+/// Layout of records inside a JSON export part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// One JSON object per line (`.ndjson`)
+    Ndjson,
+
+    /// A single JSON array per part (`.json`)
+    Array,
+}
+
+/// Sink that exports records to rotating NDJSON or JSON array files
+///
+/// Records are written to `{prefix}-00000.ndjson`, `{prefix}-00001.ndjson`, ...
+/// in the target directory, with a `{prefix}-manifest.json` listing every part,
+/// its record count and its SHA-256 checksum. The manifest also stores the
+/// replication checkpoint, so an interrupted export resumes where it stopped
+/// when the same directory and prefix are used again.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "client")]
+/// # use reso_client::{ResoClient, ReplicationQueryBuilder, JsonFileSink};
+/// # #[cfg(feature = "client")]
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let query = ReplicationQueryBuilder::new("Property")
+///     .top(2000)
+///     .build()?;
+///
+/// let mut sink = JsonFileSink::ndjson("exports", "property")
+///     .rotate_after_records(100_000);
+/// client.replicate_into(&query, &mut sink).await?;
+///
+/// let manifest = sink.finish()?;
+/// println!("Exported {} records in {} files", manifest.total_records, manifest.parts.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonFileSink {
+    parts: PartFiles,
+    format: JsonFormat,
+}
+
+impl JsonFileSink {
+    /// Create a sink writing newline-delimited JSON
+    pub fn ndjson(dir: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self::new(dir, prefix, JsonFormat::Ndjson)
+    }
+
+    /// Create a sink writing one JSON array per file
+    pub fn json_array(dir: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self::new(dir, prefix, JsonFormat::Array)
+    }
+
+    /// Create a sink writing the given format
+    pub fn new(dir: impl Into<PathBuf>, prefix: impl Into<String>, format: JsonFormat) -> Self {
+        let parts = match format {
+            JsonFormat::Ndjson => {
+                PartFiles::new(dir, prefix, "ndjson", "ndjson", PartLayout::default())
+            }
+            JsonFormat::Array => PartFiles::new(
+                dir,
+                prefix,
+                "json",
+                "json",
+                PartLayout {
+                    prologue: b"[\n".to_vec(),
+                    separator: b",\n".to_vec(),
+                    epilogue: b"\n]\n".to_vec(),
+                },
+            ),
+        };
+
+        Self { parts, format }
+    }
+
+    /// Compress every part file
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.parts.compression = compression;
+        self
+    }
+
+    /// Start a new part once the current one holds `records` records
+    pub fn rotate_after_records(mut self, records: u64) -> Self {
+        self.parts.max_records = Some(records.max(1));
+        self
+    }
+
+    /// Start a new part once the current one holds `bytes` bytes of JSON
+    ///
+    /// Counts uncompressed bytes, so compressed parts end up smaller.
+    pub fn rotate_after_bytes(mut self, bytes: u64) -> Self {
+        self.parts.max_bytes = Some(bytes.max(1));
+        self
+    }
+
+    /// Path of the manifest file
+    pub fn manifest_path(&self) -> PathBuf {
+        self.parts.manifest_path()
+    }
+
+    /// Close the open part and write the final manifest
+    ///
+    /// Call this after the export driver returns. The manifest is marked
+    /// complete when the last committed checkpoint was complete.
+    pub fn finish(&mut self) -> Result<ExportManifest> {
+        self.parts.finish()
+    }
+
+    fn encode(&self, record: &JsonValue) -> Result<Vec<u8>> {
+        let mut bytes = serde_json::to_vec(record)
+            .map_err(|e| ResoError::Sink(format!("Failed to encode record: {}", e)))?;
+        if self.format == JsonFormat::Ndjson {
+            bytes.push(b'\n');
+        }
+        Ok(bytes)
+    }
+}
+
+#[async_trait]
+impl RecordSink for JsonFileSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        for record in records {
+            let bytes = self.encode(record)?;
+            self.parts.write_record(&bytes)?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.parts.end_frame()
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.parts.commit(checkpoint)
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        self.parts.last_checkpoint()
+    }
+
+    async fn resume(&mut self, _checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.parts.resume()
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.parts.reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn checkpoint(next_link: Option<&str>, committed: u64) -> ReplicationCheckpoint {
+        ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication".to_string()),
+            next_link: next_link.map(|s| s.to_string()),
+            records_committed: committed,
+        }
+    }
+
+    fn read(dir: &tempfile::TempDir, file: &str) -> String {
+        std::fs::read_to_string(dir.path().join(file)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_one_record_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+
+        sink.write_batch(&[json!({"ListingKey": "1"}), json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+
+        assert_eq!(
+            read(&dir, "property-00000.ndjson"),
+            "{\"ListingKey\":\"1\"}\n{\"ListingKey\":\"2\"}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_array_parts_are_valid_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonFileSink::json_array(dir.path(), "property").rotate_after_records(2);
+
+        let records: Vec<JsonValue> = (0..3).map(|i| json!({ "ListingKey": i })).collect();
+        sink.write_batch(&records).await.unwrap();
+        sink.commit(&checkpoint(None, 3)).await.unwrap();
+
+        let first: Vec<JsonValue> =
+            serde_json::from_str(&read(&dir, "property-00000.json")).unwrap();
+        let second: Vec<JsonValue> =
+            serde_json::from_str(&read(&dir, "property-00001.json")).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second, vec![json!({ "ListingKey": 2 })]);
+    }
+
+    #[tokio::test]
+    async fn test_commit_writes_manifest_with_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+
+        let manifest: ExportManifest =
+            serde_json::from_slice(&std::fs::read(sink.manifest_path()).unwrap()).unwrap();
+        assert!(!manifest.complete);
+        assert_eq!(manifest.total_records, 1);
+        assert_eq!(
+            manifest.checkpoint,
+            Some(checkpoint(Some("https://api.example.com/next"), 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_appends_to_open_part() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+        // Written but never committed
+        sink.write_batch(&[json!({"ListingKey": "lost"})])
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+        drop(sink);
+
+        let mut resumed = JsonFileSink::ndjson(dir.path(), "property");
+        let last = resumed.last_checkpoint().await.unwrap().unwrap();
+        assert_eq!(last.records_committed, 1);
+        resumed.resume(&last).await.unwrap();
+
+        resumed
+            .write_batch(&[json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+        resumed.commit(&checkpoint(None, 2)).await.unwrap();
+
+        assert_eq!(
+            read(&dir, "property-00000.ndjson"),
+            "{\"ListingKey\":\"1\"}\n{\"ListingKey\":\"2\"}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finish_marks_manifest_complete() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        let manifest = sink.finish().unwrap();
+
+        assert!(manifest.complete);
+        assert_eq!(manifest.parts.len(), 1);
+        assert!(manifest.parts[0].sha256.is_some());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_gzip_parts_decompress_across_frames() {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink =
+            JsonFileSink::ndjson(dir.path(), "property").with_compression(Compression::Gzip);
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+        sink.write_batch(&[json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+
+        let file = std::fs::File::open(dir.path().join("property-00000.ndjson.gz")).unwrap();
+        let mut text = String::new();
+        flate2::read::MultiGzDecoder::new(file)
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "{\"ListingKey\":\"1\"}\n{\"ListingKey\":\"2\"}\n");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_zstd_parts_decompress_across_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink =
+            JsonFileSink::ndjson(dir.path(), "property").with_compression(Compression::Zstd);
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+        sink.write_batch(&[json!({"ListingKey": "2"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+
+        let bytes = std::fs::read(dir.path().join("property-00000.ndjson.zst")).unwrap();
+        let text = zstd::stream::decode_all(&bytes[..]).unwrap();
+        assert_eq!(text, b"{\"ListingKey\":\"1\"}\n{\"ListingKey\":\"2\"}\n");
+    }
+}
@@ -0,0 +1,428 @@
+// src/sink/parquet.rs
+
+//! Parquet file sink (requires the `arrow` feature)
+
+use crate::arrow::RecordBatchConverter;
+use crate::error::{ResoError, Result};
+use crate::metadata::EntityType;
+use crate::sink::files::{
+    io_error, load_manifest_file, remove_stale_parts, sha256_file, write_manifest_file,
+    ExportManifest, ManifestPart,
+};
+use crate::sink::{RecordSink, ReplicationCheckpoint};
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::basic::Compression;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::schema::types::ColumnPath;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::fs::File;
+use std::path::PathBuf;
+
+pub use ::parquet::file::properties::WriterProperties as ParquetWriterProperties;
+
+/// Default number of records per Parquet file
+const DEFAULT_RECORDS_PER_FILE: u64 = 1_000_000;
+
+/// Sink that lands records as Parquet files
+///
+/// Records are converted with a [`RecordBatchConverter`] and written to
+/// `{prefix}-00000.parquet`, `{prefix}-00001.parquet`, ... with a
+/// `{prefix}-manifest.json` in the same format as the other file sinks.
+///
+/// A Parquet file is only readable once its footer is written, so files are
+/// the unit of durability: the manifest and its replication checkpoint advance
+/// when a file is closed, which happens after a commit once the file holds
+/// [`rotate_after_records`](Self::rotate_after_records) records, and at the
+/// end of the run. An interrupted run resumes from the last closed file and
+/// re-fetches the pages written after it.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use reso_client::{ResoClient, ReplicationQueryBuilder, ParquetSink};
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let metadata = client.fetch_parsed_metadata().await?;
+/// let property = metadata.entity_type("Property").unwrap();
+///
+/// let query = ReplicationQueryBuilder::new("Property")
+///     .top(2000)
+///     .build()?;
+///
+/// let mut sink = ParquetSink::for_entity_type("warehouse/property", "property", property)
+///     .rotate_after_records(500_000);
+/// client.replicate_into(&query, &mut sink).await?;
+/// sink.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ParquetSink {
+    dir: PathBuf,
+    prefix: String,
+    converter: RecordBatchConverter,
+    properties: WriterProperties,
+    max_records: u64,
+    parts: Vec<ManifestPart>,
+    open: Option<OpenFile>,
+    /// Checkpoint recorded in the manifest
+    durable: Option<ReplicationCheckpoint>,
+    /// Latest checkpoint, durable once the open file is closed
+    pending: Option<ReplicationCheckpoint>,
+    /// Manifest of the previous run, loaded by `last_checkpoint`
+    previous: Option<ExportManifest>,
+    /// Files of an overwritten export, deleted once the manifest drops them
+    stale: Vec<String>,
+}
+
+struct OpenFile {
+    file_name: String,
+    writer: ArrowWriter<File>,
+    records: u64,
+}
+
+impl ParquetSink {
+    /// Create a sink converting records with `converter`
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        converter: RecordBatchConverter,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.into(),
+            converter,
+            properties: WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+            max_records: DEFAULT_RECORDS_PER_FILE,
+            parts: Vec::new(),
+            open: None,
+            durable: None,
+            pending: None,
+            previous: None,
+            stale: Vec::new(),
+        }
+    }
+
+    /// Create a sink with a schema built from a `$metadata` entity type
+    ///
+    /// Use [`RecordBatchConverter::select`] with [`new`](Self::new) when the
+    /// query has a `$select` list.
+    pub fn for_entity_type(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+        entity_type: &EntityType,
+    ) -> Self {
+        Self::new(
+            dir,
+            prefix,
+            RecordBatchConverter::from_entity_type(entity_type),
+        )
+    }
+
+    /// Parquet writer properties (default: Snappy compression)
+    pub fn with_writer_properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Close a file once it holds `records` records (default 1,000,000)
+    pub fn rotate_after_records(mut self, records: u64) -> Self {
+        self.max_records = records.max(1);
+        self
+    }
+
+    /// Path of the manifest file
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join(format!("{}-manifest.json", self.prefix))
+    }
+
+    /// Close the open file and write the final manifest
+    pub fn finish(&mut self) -> Result<ExportManifest> {
+        self.close_file()?;
+        if self.pending.is_some() {
+            self.durable = self.pending.clone();
+        }
+        let complete = self
+            .durable
+            .as_ref()
+            .is_none_or(ReplicationCheckpoint::is_complete);
+        self.write_manifest(complete)
+    }
+
+    fn close_file(&mut self) -> Result<()> {
+        let Some(open) = self.open.take() else {
+            return Ok(());
+        };
+
+        let file = open
+            .writer
+            .into_inner()
+            .map_err(|e| parquet_error(&open.file_name, e))?;
+        file.sync_all().map_err(|e| io_error(&open.file_name, e))?;
+        let bytes = file
+            .metadata()
+            .map_err(|e| io_error(&open.file_name, e))?
+            .len();
+
+        let path = self.dir.join(&open.file_name);
+        let sha256 = sha256_file(&path).map_err(|e| io_error(&open.file_name, e))?;
+        self.parts.push(ManifestPart {
+            file: open.file_name,
+            records: open.records,
+            bytes,
+            uncompressed_bytes: bytes,
+            sha256: Some(sha256),
+        });
+        Ok(())
+    }
+
+    fn write_manifest(&mut self, complete: bool) -> Result<ExportManifest> {
+        let manifest = ExportManifest {
+            format: "parquet".to_string(),
+            compression: self
+                .properties
+                .compression(&ColumnPath::new(Vec::new()))
+                .to_string()
+                .to_lowercase(),
+            parts: self.parts.clone(),
+            total_records: self.parts.iter().map(|p| p.records).sum(),
+            checkpoint: self.durable.clone(),
+            complete,
+        };
+        write_manifest_file(&self.manifest_path(), &manifest)?;
+        remove_stale_parts(&self.dir, &mut self.stale, &manifest)?;
+        Ok(manifest)
+    }
+
+    fn open_file(&mut self) -> Result<&mut OpenFile> {
+        if self.open.is_none() {
+            std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir.display(), e))?;
+            let file_name = format!("{}-{:05}.parquet", self.prefix, self.parts.len());
+            let file =
+                File::create(self.dir.join(&file_name)).map_err(|e| io_error(&file_name, e))?;
+            let writer = ArrowWriter::try_new(
+                file,
+                self.converter.schema().clone(),
+                Some(self.properties.clone()),
+            )
+            .map_err(|e| parquet_error(&file_name, e))?;
+
+            self.open = Some(OpenFile {
+                file_name,
+                writer,
+                records: 0,
+            });
+        }
+        Ok(self.open.as_mut().expect("file opened above"))
+    }
+}
+
+#[async_trait]
+impl RecordSink for ParquetSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let batch = self.converter.convert(records)?;
+        let open = self.open_file()?;
+        open.writer
+            .write(&batch)
+            .map_err(|e| parquet_error(&open.file_name, e))?;
+        open.records += records.len() as u64;
+        Ok(())
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.pending = Some(checkpoint.clone());
+
+        let full = self
+            .open
+            .as_ref()
+            .is_some_and(|open| open.records >= self.max_records);
+        if full || checkpoint.is_complete() {
+            self.close_file()?;
+            self.durable = Some(checkpoint.clone());
+            self.write_manifest(checkpoint.is_complete())?;
+        }
+        Ok(())
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        // Rows written to a file that was never closed are lost with it
+        self.open = None;
+        self.pending = None;
+        self.previous = load_manifest_file(&self.manifest_path())?;
+        Ok(self
+            .previous
+            .as_ref()
+            .and_then(|manifest| manifest.checkpoint.clone()))
+    }
+
+    async fn resume(&mut self, _checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        let manifest = match self.previous.take() {
+            Some(manifest) => Some(manifest),
+            None => load_manifest_file(&self.manifest_path())?,
+        };
+        self.parts.clear();
+        if let Some(manifest) = manifest {
+            if !manifest.complete {
+                self.parts = manifest.parts;
+            }
+            self.durable = manifest.checkpoint;
+        }
+        Ok(())
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        // Files of the previous export that this run does not rewrite are
+        // deleted with its first manifest
+        let previous = match self.previous.take() {
+            Some(manifest) => Some(manifest),
+            None => load_manifest_file(&self.manifest_path())?,
+        };
+        self.stale = previous
+            .map(|manifest| manifest.parts.into_iter().map(|p| p.file).collect())
+            .unwrap_or_default();
+        self.open = None;
+        self.pending = None;
+        self.durable = None;
+        self.parts.clear();
+        Ok(())
+    }
+}
+
+fn parquet_error(file_name: &str, e: ::parquet::errors::ParquetError) -> ResoError {
+    ResoError::Sink(format!("Parquet error on {}: {}", file_name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Property;
+    use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+
+    fn entity_type() -> EntityType {
+        EntityType {
+            name: "Property".to_string(),
+            key: vec!["ListingKey".to_string()],
+            properties: vec![
+                Property {
+                    name: "ListingKey".to_string(),
+                    type_name: "Edm.String".to_string(),
+                    ..Default::default()
+                },
+                Property {
+                    name: "BedroomsTotal".to_string(),
+                    type_name: "Edm.Int32".to_string(),
+                    nullable: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn checkpoint(next_link: Option<&str>, committed: u64) -> ReplicationCheckpoint {
+        ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication".to_string()),
+            next_link: next_link.map(|s| s.to_string()),
+            records_committed: committed,
+        }
+    }
+
+    fn row_count(path: PathBuf) -> usize {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_writes_readable_parquet_with_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = ParquetSink::for_entity_type(dir.path(), "property", &entity_type());
+
+        sink.write_batch(&[
+            json!({"ListingKey": "1", "BedroomsTotal": 3}),
+            json!({"ListingKey": "2"}),
+        ])
+        .await
+        .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+        let manifest = sink.finish().unwrap();
+
+        assert!(manifest.complete);
+        assert_eq!(manifest.format, "parquet");
+        assert_eq!(manifest.compression, "snappy");
+        assert_eq!(manifest.parts.len(), 1);
+        assert_eq!(manifest.total_records, 2);
+        assert_eq!(row_count(dir.path().join("property-00000.parquet")), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rotates_after_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = ParquetSink::for_entity_type(dir.path(), "property", &entity_type())
+            .rotate_after_records(2);
+
+        for (i, next) in [(1, Some("p2")), (2, Some("p3")), (3, None)] {
+            sink.write_batch(&[json!({ "ListingKey": i.to_string() })])
+                .await
+                .unwrap();
+            sink.commit(&checkpoint(next, i)).await.unwrap();
+        }
+        let manifest = sink.finish().unwrap();
+
+        let counts: Vec<u64> = manifest.parts.iter().map(|p| p.records).collect();
+        assert_eq!(counts, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_advances_only_with_closed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = ParquetSink::for_entity_type(dir.path(), "property", &entity_type())
+            .rotate_after_records(2);
+
+        for (i, next) in [(1, "p2"), (2, "p3"), (3, "p4")] {
+            sink.write_batch(&[json!({ "ListingKey": i.to_string() })])
+                .await
+                .unwrap();
+            sink.commit(&checkpoint(Some(next), i)).await.unwrap();
+        }
+        drop(sink);
+
+        let mut resumed = ParquetSink::for_entity_type(dir.path(), "property", &entity_type())
+            .rotate_after_records(2);
+        let last = resumed.last_checkpoint().await.unwrap();
+
+        // Record 3 was in an unclosed file, so the run resumes after record 2
+        assert_eq!(last, Some(checkpoint(Some("p3"), 2)));
+        resumed.resume(&last.unwrap()).await.unwrap();
+        resumed
+            .write_batch(&[json!({"ListingKey": "3"})])
+            .await
+            .unwrap();
+        resumed.commit(&checkpoint(None, 3)).await.unwrap();
+        let manifest = resumed.finish().unwrap();
+
+        assert_eq!(manifest.total_records, 3);
+        assert_eq!(row_count(dir.path().join("property-00001.parquet")), 1);
+    }
+
+    #[tokio::test]
+    async fn test_conversion_error_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = ParquetSink::for_entity_type(dir.path(), "property", &entity_type());
+
+        let result = sink
+            .write_batch(&[json!({"ListingKey": "1", "BedroomsTotal": "three"})])
+            .await;
+        assert!(matches!(result, Err(ResoError::Parse(_))));
+    }
+}
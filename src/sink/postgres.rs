@@ -0,0 +1,687 @@
+// src/sink/postgres.rs
+
+//! PostgreSQL sink (requires the `postgres` feature)
+
+use crate::error::{ResoError, Result};
+use crate::metadata::{EntityType, Property};
+use crate::queries::ReplicationQueryBuilder;
+use crate::sink::{incremental_query, unfinished_mark, RecordSink, ReplicationCheckpoint};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::SinkExt;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tokio_postgres::{Client, NoTls};
+
+/// Table holding one replication checkpoint per resource
+const CHECKPOINT_TABLE: &str = "_reso_checkpoints";
+
+/// Table used by raw mode
+pub const RAW_TABLE: &str = "reso_records";
+
+/// Connect to PostgreSQL without TLS
+///
+/// The connection is driven on a spawned Tokio task. Use
+/// `tokio_postgres::connect` with a TLS connector and pass the client to
+/// [`PostgresSink::raw`] or [`PostgresSink::typed`] for encrypted connections.
+pub async fn connect(url: &str) -> Result<Client> {
+    let (client, connection) = tokio_postgres::connect(url, NoTls)
+        .await
+        .map_err(sql_error)?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            tracing::warn!("PostgreSQL connection closed: {}", e);
+        }
+    });
+    Ok(client)
+}
+
+/// How records are laid out in PostgreSQL
+#[derive(Debug, Clone)]
+enum Layout {
+    /// One JSONB document per record in [`RAW_TABLE`]
+    Raw,
+    /// One column per `$metadata` property
+    Typed { columns: Vec<Column> },
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    name: String,
+    sql_type: String,
+}
+
+impl Column {
+    fn is_jsonb(&self) -> bool {
+        self.sql_type == "JSONB"
+    }
+}
+
+/// Sink that mirrors a resource into PostgreSQL
+///
+/// Two layouts are available:
+///
+/// - **Raw** ([`raw`](Self::raw)): every record is stored whole as `JSONB` in
+///   the shared `reso_records` table, keyed by resource and record key.
+///   Schema changes on the server need no migration.
+/// - **Typed** ([`typed`](Self::typed)): a table named after the resource with
+///   one column per property of a parsed `$metadata` entity type. Collections
+///   are stored as `JSONB`; fields without a column are ignored.
+///
+/// Each batch is streamed into a temporary staging table with `COPY` and then
+/// upserted by key, so re-delivered pages leave one row per record. In typed
+/// mode only the columns present in the batch are updated. The replication
+/// checkpoint is written to `_reso_checkpoints` in the same transaction as the
+/// page it follows.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use reso_client::{ResoClient, PostgresSink};
+/// # use reso_client::sink::postgres;
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let metadata = client.fetch_parsed_metadata().await?;
+/// let property = metadata.entity_type("Property").unwrap();
+///
+/// let pg = postgres::connect("postgres://reso@localhost/mls").await?;
+/// let mut sink = PostgresSink::typed(pg, "Property", property).await?;
+///
+/// // Only fetch what changed since the last sync
+/// let query = sink
+///     .incremental_query("ModificationTimestamp", None)
+///     .await?
+///     .top(2000)
+///     .build()?;
+/// client.replicate_into(&query, &mut sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PostgresSink {
+    client: Client,
+    resource: String,
+    table: String,
+    key: Vec<String>,
+    layout: Layout,
+    in_transaction: bool,
+}
+
+impl PostgresSink {
+    /// Store `resource` as JSONB documents in the `reso_records` table
+    ///
+    /// Records are keyed by the values of the `key` fields.
+    pub async fn raw(client: Client, resource: &str, key: &[&str]) -> Result<Self> {
+        if key.is_empty() {
+            return Err(ResoError::Sink(format!(
+                "PostgreSQL mirror of {} needs at least one key column",
+                resource
+            )));
+        }
+
+        let sink = Self {
+            client,
+            resource: resource.to_string(),
+            table: RAW_TABLE.to_string(),
+            key: key.iter().map(|k| k.to_string()).collect(),
+            layout: Layout::Raw,
+            in_transaction: false,
+        };
+        sink.create_checkpoint_table().await?;
+        sink.client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    resource TEXT NOT NULL,
+                    record_key TEXT NOT NULL,
+                    record JSONB NOT NULL,
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                    PRIMARY KEY (resource, record_key)
+                )",
+                RAW_TABLE
+            ))
+            .await
+            .map_err(sql_error)?;
+        Ok(sink)
+    }
+
+    /// Mirror `resource` into a table of the same name built from a `$metadata` entity type
+    ///
+    /// Columns get PostgreSQL types derived from the EDM types and the entity
+    /// key becomes the primary key. An existing table is used as is.
+    pub async fn typed(client: Client, resource: &str, entity_type: &EntityType) -> Result<Self> {
+        if entity_type.key.is_empty() {
+            return Err(ResoError::Sink(format!(
+                "Entity type {} declares no key",
+                entity_type.name
+            )));
+        }
+
+        let columns: Vec<Column> = entity_type
+            .properties
+            .iter()
+            .map(|p| Column {
+                name: p.name.clone(),
+                sql_type: edm_column_type(p),
+            })
+            .collect();
+        if let Some(missing) = entity_type
+            .key
+            .iter()
+            .find(|k| !columns.iter().any(|c| &c.name == *k))
+        {
+            return Err(ResoError::Sink(format!(
+                "Key {} is not a property of {}",
+                missing, entity_type.name
+            )));
+        }
+
+        let mut definitions: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let not_null = if entity_type.key.contains(&c.name) {
+                    " NOT NULL"
+                } else {
+                    ""
+                };
+                format!("{} {}{}", quote(&c.name), c.sql_type, not_null)
+            })
+            .collect();
+        let key_list: Vec<String> = entity_type.key.iter().map(|k| quote(k)).collect();
+        definitions.push(format!("PRIMARY KEY ({})", key_list.join(", ")));
+
+        let sink = Self {
+            client,
+            resource: resource.to_string(),
+            table: resource.to_string(),
+            key: entity_type.key.clone(),
+            layout: Layout::Typed { columns },
+            in_transaction: false,
+        };
+        sink.create_checkpoint_table().await?;
+        sink.client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {} ({})",
+                quote(&sink.table),
+                definitions.join(", ")
+            ))
+            .await
+            .map_err(sql_error)?;
+        Ok(sink)
+    }
+
+    /// Underlying client, for reading the mirror
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Table the records are written to
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Number of records of this resource in the mirror
+    pub async fn row_count(&self) -> Result<u64> {
+        let row = match self.layout {
+            Layout::Raw => {
+                self.client
+                    .query_one(
+                        &format!("SELECT COUNT(*) FROM {} WHERE resource = $1", RAW_TABLE),
+                        &[&self.resource],
+                    )
+                    .await
+            }
+            Layout::Typed { .. } => {
+                self.client
+                    .query_one(&format!("SELECT COUNT(*) FROM {}", quote(&self.table)), &[])
+                    .await
+            }
+        }
+        .map_err(sql_error)?;
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    /// Greatest value of `field` in the mirror, e.g. the latest `ModificationTimestamp`
+    ///
+    /// Timestamps are returned in OData literal form (`2025-01-15T10:30:00.000000Z`).
+    /// In raw mode `field` must hold timestamps, which are compared as instants.
+    /// Returns `None` if there are no records or, in typed mode, no such column.
+    pub async fn high_water_mark(&self, field: &str) -> Result<Option<String>> {
+        let row = match &self.layout {
+            Layout::Raw => self
+                .client
+                .query_one(
+                    &format!(
+                        "SELECT to_char(MAX((record ->> $2)::timestamptz) AT TIME ZONE 'UTC', \
+                         'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"') FROM {} WHERE resource = $1",
+                        RAW_TABLE
+                    ),
+                    &[&self.resource, &field],
+                )
+                .await
+                .map_err(sql_error)?,
+            Layout::Typed { columns } => {
+                let Some(column) = columns.iter().find(|c| c.name == field) else {
+                    return Ok(None);
+                };
+                let select = if column.sql_type == "TIMESTAMPTZ" {
+                    format!(
+                        "to_char(MAX({}) AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"')",
+                        quote(field)
+                    )
+                } else {
+                    format!("MAX({})::text", quote(field))
+                };
+                self.client
+                    .query_one(
+                        &format!("SELECT {} FROM {}", select, quote(&self.table)),
+                        &[],
+                    )
+                    .await
+                    .map_err(sql_error)?
+            }
+        };
+        Ok(row.get(0))
+    }
+
+    /// Replication query that fetches records changed since the last sync
+    ///
+    /// Filters on `timestamp_field ge <high water mark>`, combined with
+    /// `filter` if given. `ge` rather than `gt` re-fetches records sharing the
+    /// latest timestamp, which the upsert absorbs. An empty mirror gets the
+    /// plain `filter`, i.e. a full replication.
+    ///
+    /// While the last run of this query is unfinished, its original mark is
+    /// kept, so the run resumes from its checkpoint instead of restarting from
+    /// a mark that skips records it has not fetched yet.
+    pub async fn incremental_query(
+        &self,
+        timestamp_field: &str,
+        filter: Option<&str>,
+    ) -> Result<ReplicationQueryBuilder> {
+        let unfinished = self
+            .stored_checkpoint()
+            .await?
+            .and_then(|checkpoint| unfinished_mark(&checkpoint, timestamp_field, filter));
+        let mark = match unfinished {
+            Some(mark) => mark,
+            None => self.high_water_mark(timestamp_field).await?,
+        };
+        Ok(incremental_query(
+            &self.resource,
+            timestamp_field,
+            mark,
+            filter,
+        ))
+    }
+
+    /// Checkpoint stored for the resource
+    async fn stored_checkpoint(&self) -> Result<Option<ReplicationCheckpoint>> {
+        let row = self
+            .client
+            .query_opt(
+                &format!(
+                    "SELECT query, next_link, records_committed FROM {} WHERE resource = $1",
+                    CHECKPOINT_TABLE
+                ),
+                &[&self.resource],
+            )
+            .await
+            .map_err(sql_error)?;
+
+        Ok(row.map(|row| ReplicationCheckpoint {
+            resource: self.resource.clone(),
+            query: row.get(0),
+            next_link: row.get(1),
+            records_committed: row.get::<_, i64>(2) as u64,
+        }))
+    }
+
+    async fn create_checkpoint_table(&self) -> Result<()> {
+        self.client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {0} (
+                    resource TEXT PRIMARY KEY,
+                    query TEXT,
+                    next_link TEXT,
+                    records_committed BIGINT NOT NULL
+                );
+                ALTER TABLE {0} ADD COLUMN IF NOT EXISTS query TEXT",
+                CHECKPOINT_TABLE
+            ))
+            .await
+            .map_err(sql_error)
+    }
+
+    async fn begin(&mut self) -> Result<()> {
+        if !self.in_transaction {
+            self.client
+                .batch_execute("BEGIN")
+                .await
+                .map_err(sql_error)?;
+            self.in_transaction = true;
+        }
+        Ok(())
+    }
+
+    /// Validate records and keep the last one per key
+    fn dedupe<'r>(&self, records: &'r [JsonValue]) -> Result<Vec<(String, &'r JsonValue)>> {
+        let mut rows: Vec<(String, &JsonValue)> = Vec::with_capacity(records.len());
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for record in records {
+            let Some(fields) = record.as_object() else {
+                return Err(ResoError::Sink(format!(
+                    "Expected a JSON object for {}, got {}",
+                    self.resource, record
+                )));
+            };
+            let key = record_key(&self.key, fields).ok_or_else(|| {
+                ResoError::Sink(format!(
+                    "{} record is missing a key field ({})",
+                    self.resource,
+                    self.key.join(", ")
+                ))
+            })?;
+
+            match index.get(&key) {
+                Some(&i) => rows[i].1 = record,
+                None => {
+                    index.insert(key.clone(), rows.len());
+                    rows.push((key, record));
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Stream `csv` into a staging table shaped like the target and upsert from it
+    async fn copy_and_upsert(
+        &self,
+        columns: &[String],
+        key: &[String],
+        updates: &[String],
+        csv: String,
+    ) -> Result<()> {
+        let stage = quote(&format!("_reso_stage_{}", self.table));
+        let column_list = columns.join(", ");
+        let key_list: Vec<String> = key.iter().map(|k| quote(k)).collect();
+
+        self.client
+            .batch_execute(&format!(
+                "CREATE TEMP TABLE IF NOT EXISTS {} (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP;
+                 TRUNCATE {}",
+                stage,
+                quote(&self.table),
+                stage
+            ))
+            .await
+            .map_err(sql_error)?;
+
+        let copy = self
+            .client
+            .copy_in::<_, Bytes>(&format!(
+                "COPY {} ({}) FROM STDIN WITH (FORMAT csv)",
+                stage, column_list
+            ))
+            .await
+            .map_err(sql_error)?;
+        futures_util::pin_mut!(copy);
+        copy.send(Bytes::from(csv)).await.map_err(sql_error)?;
+        copy.finish().await.map_err(sql_error)?;
+
+        let conflict = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+        self.client
+            .batch_execute(&format!(
+                "INSERT INTO {0} ({1}) SELECT {1} FROM {2} ON CONFLICT ({3}) {4}",
+                quote(&self.table),
+                column_list,
+                stage,
+                key_list.join(", "),
+                conflict
+            ))
+            .await
+            .map_err(sql_error)
+    }
+}
+
+#[async_trait]
+impl RecordSink for PostgresSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let rows = self.dedupe(records)?;
+        self.begin().await?;
+
+        match &self.layout {
+            Layout::Raw => {
+                let mut csv = String::new();
+                for (key, record) in &rows {
+                    csv.push_str(&csv_text(&self.resource));
+                    csv.push(',');
+                    csv.push_str(&csv_text(key));
+                    csv.push(',');
+                    csv.push_str(&csv_text(&record.to_string()));
+                    csv.push('\n');
+                }
+                let columns = ["resource", "record_key", "record"].map(String::from);
+                let key = ["resource", "record_key"].map(String::from);
+                let updates = [
+                    "record = EXCLUDED.record".to_string(),
+                    "updated_at = now()".to_string(),
+                ];
+                self.copy_and_upsert(&columns, &key, &updates, csv).await
+            }
+            Layout::Typed { columns } => {
+                // Only the columns present somewhere in the batch are written
+                let present: Vec<&Column> = columns
+                    .iter()
+                    .filter(|c| rows.iter().any(|(_, r)| r.get(&c.name).is_some()))
+                    .collect();
+
+                let mut csv = String::new();
+                for (_, record) in &rows {
+                    let values: Vec<String> = present
+                        .iter()
+                        .map(|c| csv_field(record.get(&c.name), c.is_jsonb()))
+                        .collect();
+                    csv.push_str(&values.join(","));
+                    csv.push('\n');
+                }
+                let names: Vec<String> = present.iter().map(|c| quote(&c.name)).collect();
+                let updates: Vec<String> = present
+                    .iter()
+                    .filter(|c| !self.key.contains(&c.name))
+                    .map(|c| format!("{0} = EXCLUDED.{0}", quote(&c.name)))
+                    .collect();
+                self.copy_and_upsert(&names, &self.key, &updates, csv).await
+            }
+        }
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        self.begin().await?;
+
+        self.client
+            .execute(
+                &format!(
+                    "INSERT INTO {} (resource, query, next_link, records_committed)
+                     VALUES ($1, $2, $3, $4)
+                     ON CONFLICT (resource) DO UPDATE SET
+                        query = EXCLUDED.query,
+                        next_link = EXCLUDED.next_link,
+                        records_committed = EXCLUDED.records_committed",
+                    CHECKPOINT_TABLE
+                ),
+                &[
+                    &checkpoint.resource,
+                    &checkpoint.query,
+                    &checkpoint.next_link,
+                    &(checkpoint.records_committed as i64),
+                ],
+            )
+            .await
+            .map_err(sql_error)?;
+
+        self.client
+            .batch_execute("COMMIT")
+            .await
+            .map_err(sql_error)?;
+        self.in_transaction = false;
+        Ok(())
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        // Drop anything written after the last commit
+        if self.in_transaction {
+            self.client
+                .batch_execute("ROLLBACK")
+                .await
+                .map_err(sql_error)?;
+            self.in_transaction = false;
+        }
+
+        self.stored_checkpoint().await
+    }
+}
+
+/// PostgreSQL column type for an EDM property
+fn edm_column_type(property: &Property) -> String {
+    if property.is_collection() {
+        return "JSONB".to_string();
+    }
+    match property.element_type() {
+        "Edm.Boolean" => "BOOLEAN".to_string(),
+        "Edm.Byte" | "Edm.SByte" | "Edm.Int16" => "SMALLINT".to_string(),
+        "Edm.Int32" => "INTEGER".to_string(),
+        "Edm.Int64" => "BIGINT".to_string(),
+        "Edm.Decimal" => match (property.precision, property.scale) {
+            (Some(precision), Some(scale)) if (1..=1000).contains(&precision) => {
+                format!("NUMERIC({}, {})", precision, scale.min(precision))
+            }
+            _ => "NUMERIC".to_string(),
+        },
+        "Edm.Double" => "DOUBLE PRECISION".to_string(),
+        "Edm.Single" => "REAL".to_string(),
+        "Edm.Date" => "DATE".to_string(),
+        "Edm.DateTimeOffset" => "TIMESTAMPTZ".to_string(),
+        "Edm.TimeOfDay" => "TIME".to_string(),
+        "Edm.Guid" => "UUID".to_string(),
+        _ => "TEXT".to_string(),
+    }
+}
+
+/// Text form of the record key: the value itself for a single key, a JSON
+/// array of the values for a composite key
+fn record_key(key: &[String], fields: &serde_json::Map<String, JsonValue>) -> Option<String> {
+    let values: Vec<&JsonValue> = key
+        .iter()
+        .map(|k| fields.get(k).filter(|v| !v.is_null()))
+        .collect::<Option<_>>()?;
+
+    Some(match values.as_slice() {
+        [JsonValue::String(s)] => s.clone(),
+        [value] => value.to_string(),
+        _ => JsonValue::Array(values.into_iter().cloned().collect()).to_string(),
+    })
+}
+
+/// One CSV field for `COPY ... (FORMAT csv)`; an unquoted empty field is NULL
+fn csv_field(value: Option<&JsonValue>, jsonb: bool) -> String {
+    match value {
+        None | Some(JsonValue::Null) => String::new(),
+        Some(JsonValue::Bool(b)) => b.to_string(),
+        Some(JsonValue::Number(n)) => n.to_string(),
+        Some(JsonValue::String(s)) if !jsonb => csv_text(s),
+        Some(value) => csv_text(&value.to_string()),
+    }
+}
+
+fn csv_text(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sql_error(e: tokio_postgres::Error) -> ResoError {
+    match e.as_db_error() {
+        Some(db) => ResoError::Sink(format!("PostgreSQL error: {}", db)),
+        None => ResoError::Sink(format!("PostgreSQL error: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn property(name: &str, type_name: &str) -> Property {
+        Property {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_edm_column_types() {
+        assert_eq!(
+            edm_column_type(&property("A", "Edm.DateTimeOffset")),
+            "TIMESTAMPTZ"
+        );
+        assert_eq!(
+            edm_column_type(&property("A", "Collection(Edm.String)")),
+            "JSONB"
+        );
+        assert_eq!(edm_column_type(&property("A", "Edm.Int64")), "BIGINT");
+        assert_eq!(
+            edm_column_type(&property("A", "org.reso.metadata.enums.City")),
+            "TEXT"
+        );
+
+        let price = Property {
+            precision: Some(14),
+            scale: Some(2),
+            ..property("ListPrice", "Edm.Decimal")
+        };
+        assert_eq!(edm_column_type(&price), "NUMERIC(14, 2)");
+        assert_eq!(edm_column_type(&property("A", "Edm.Decimal")), "NUMERIC");
+    }
+
+    #[test]
+    fn test_record_key() {
+        let single = vec!["ListingKey".to_string()];
+        let record = json!({"ListingKey": "A1", "Id": 7});
+        let fields = record.as_object().unwrap();
+
+        assert_eq!(record_key(&single, fields), Some("A1".to_string()));
+        assert_eq!(
+            record_key(&["Id".to_string()], fields),
+            Some("7".to_string())
+        );
+        assert_eq!(
+            record_key(&["ListingKey".to_string(), "Id".to_string()], fields),
+            Some("[\"A1\",7]".to_string())
+        );
+        assert_eq!(record_key(&["Missing".to_string()], fields), None);
+    }
+
+    #[test]
+    fn test_csv_fields() {
+        assert_eq!(csv_field(None, false), "");
+        assert_eq!(csv_field(Some(&JsonValue::Null), true), "");
+        assert_eq!(csv_field(Some(&json!("")), false), "\"\"");
+        assert_eq!(
+            csv_field(Some(&json!("say \"hi\"")), false),
+            "\"say \"\"hi\"\"\""
+        );
+        assert_eq!(csv_field(Some(&json!(true)), false), "true");
+        assert_eq!(csv_field(Some(&json!(1.5)), false), "1.5");
+        assert_eq!(csv_field(Some(&json!("x")), true), "\"\"\"x\"\"\"");
+        assert_eq!(csv_field(Some(&json!(["a"])), true), "\"[\"\"a\"\"]\"");
+    }
+}
@@ -0,0 +1,711 @@
+// src/sink/sqlite.rs
+
+//! SQLite local mirror sink (requires the `sqlite` feature)
+
+use crate::error::{ResoError, Result};
+use crate::metadata::{EntityType, Property};
+use crate::queries::ReplicationQueryBuilder;
+use crate::sink::{incremental_query, unfinished_mark, RecordSink, ReplicationCheckpoint};
+use async_trait::async_trait;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Table holding one replication checkpoint per resource
+const CHECKPOINT_TABLE: &str = "_reso_checkpoints";
+
+/// Sink that mirrors a resource into a SQLite table
+///
+/// Records are upserted by the entity key, so re-delivered pages and repeated
+/// syncs leave exactly one row per record. The replication checkpoint is
+/// stored in the `_reso_checkpoints` table and written in the same
+/// transaction as the page it follows, so the mirror and its checkpoint never
+/// disagree after a crash.
+///
+/// The table is created from a parsed `$metadata` entity type
+/// ([`for_entity_type`](Self::for_entity_type)) or from the fields observed in
+/// the records ([`open`](Self::open)). Fields that are not yet columns are
+/// added as they appear. Objects and collections are stored as JSON text.
+///
+/// SQLite calls are made directly on the calling task; the sink is intended for
+/// local mirrors, where they are fast.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use reso_client::{ResoClient, SqliteSink};
+/// # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut sink = SqliteSink::open("mirror.db", "Property", &["ListingKey"])?;
+///
+/// // Only fetch what changed since the last sync
+/// let query = sink
+///     .incremental_query("ModificationTimestamp", Some("StandardStatus eq 'Active'"))?
+///     .top(2000)
+///     .build()?;
+/// client.replicate_into(&query, &mut sink).await?;
+///
+/// println!("Mirror holds {} listings", sink.row_count()?);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteSink {
+    conn: Connection,
+    resource: String,
+    table: String,
+    key: Vec<String>,
+    columns: HashSet<String>,
+}
+
+impl SqliteSink {
+    /// Open (or create) a database file and mirror `resource` into a table of the same name
+    ///
+    /// Columns are created from the fields observed in the records, keyed by `key`.
+    pub fn open(path: impl AsRef<Path>, resource: &str, key: &[&str]) -> Result<Self> {
+        let conn = Connection::open(path).map_err(sql_error)?;
+        Self::with_connection(conn, resource, key)
+    }
+
+    /// Mirror `resource` into an existing connection
+    ///
+    /// Useful for in-memory databases and for sharing one database file
+    /// between several resources.
+    pub fn with_connection(conn: Connection, resource: &str, key: &[&str]) -> Result<Self> {
+        if key.is_empty() {
+            return Err(ResoError::Sink(format!(
+                "SQLite mirror of {} needs at least one key column",
+                resource
+            )));
+        }
+
+        let mut sink = Self {
+            conn,
+            resource: resource.to_string(),
+            table: resource.to_string(),
+            key: key.iter().map(|k| k.to_string()).collect(),
+            columns: HashSet::new(),
+        };
+        sink.create_tables(&[])?;
+        Ok(sink)
+    }
+
+    /// Open (or create) a database file with a table built from a `$metadata` entity type
+    ///
+    /// Columns get SQLite types derived from the EDM types and the entity key
+    /// becomes the primary key.
+    pub fn for_entity_type(
+        path: impl AsRef<Path>,
+        resource: &str,
+        entity_type: &EntityType,
+    ) -> Result<Self> {
+        let conn = Connection::open(path).map_err(sql_error)?;
+        Self::for_entity_type_with_connection(conn, resource, entity_type)
+    }
+
+    /// Like [`for_entity_type`](Self::for_entity_type) with an existing connection
+    pub fn for_entity_type_with_connection(
+        conn: Connection,
+        resource: &str,
+        entity_type: &EntityType,
+    ) -> Result<Self> {
+        if entity_type.key.is_empty() {
+            return Err(ResoError::Sink(format!(
+                "Entity type {} declares no key",
+                entity_type.name
+            )));
+        }
+
+        let mut sink = Self {
+            conn,
+            resource: resource.to_string(),
+            table: resource.to_string(),
+            key: entity_type.key.clone(),
+            columns: HashSet::new(),
+        };
+        sink.create_tables(&entity_type.properties)?;
+        Ok(sink)
+    }
+
+    /// Underlying connection, for reading the mirror
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Number of rows in the mirror table
+    pub fn row_count(&self) -> Result<u64> {
+        self.conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {}", quote(&self.table)),
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|n| n as u64)
+            .map_err(sql_error)
+    }
+
+    /// Greatest value of `field` in the mirror, e.g. the latest `ModificationTimestamp`
+    ///
+    /// Timestamps are compared as instants, so values with different
+    /// fractional-second precision or UTC offsets are ordered correctly.
+    /// Values that are not timestamps are compared as stored.
+    ///
+    /// Returns `None` if the table is empty or the column does not exist yet.
+    pub fn high_water_mark(&self, field: &str) -> Result<Option<String>> {
+        if !self.columns.contains(field) {
+            return Ok(None);
+        }
+
+        // julianday() is NULL for non-timestamps, which sort last
+        let column = quote(field);
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {column} FROM {} WHERE {column} IS NOT NULL \
+                     ORDER BY julianday({column}) DESC, {column} DESC LIMIT 1",
+                    quote(&self.table)
+                ),
+                [],
+                |row| row.get::<_, Option<SqlValue>>(0),
+            )
+            .optional()
+            .map_err(sql_error)
+            .map(|value| match value.flatten() {
+                Some(SqlValue::Text(s)) => Some(s),
+                Some(SqlValue::Integer(n)) => Some(n.to_string()),
+                Some(SqlValue::Real(n)) => Some(n.to_string()),
+                _ => None,
+            })
+    }
+
+    /// Replication query that fetches records changed since the last sync
+    ///
+    /// Filters on `timestamp_field ge <high water mark>`, combined with
+    /// `filter` if given. `ge` rather than `gt` re-fetches records sharing the
+    /// latest timestamp, which the upsert absorbs, instead of risking missing
+    /// records committed later with the same timestamp. An empty mirror gets
+    /// the plain `filter`, i.e. a full replication.
+    ///
+    /// While the last run of this query is unfinished, its original mark is
+    /// kept, so the run resumes from its checkpoint instead of restarting from
+    /// a mark that skips records it has not fetched yet.
+    pub fn incremental_query(
+        &self,
+        timestamp_field: &str,
+        filter: Option<&str>,
+    ) -> Result<ReplicationQueryBuilder> {
+        let unfinished = self
+            .stored_checkpoint()?
+            .and_then(|checkpoint| unfinished_mark(&checkpoint, timestamp_field, filter));
+        let mark = match unfinished {
+            Some(mark) => mark,
+            None => self.high_water_mark(timestamp_field)?,
+        };
+        Ok(incremental_query(
+            &self.resource,
+            timestamp_field,
+            mark,
+            filter,
+        ))
+    }
+
+    /// Checkpoint stored for the resource
+    fn stored_checkpoint(&self) -> Result<Option<ReplicationCheckpoint>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT query, next_link, records_committed FROM {} WHERE resource = ?1",
+                    CHECKPOINT_TABLE
+                ),
+                [&self.resource],
+                |row| {
+                    Ok(ReplicationCheckpoint {
+                        resource: self.resource.clone(),
+                        query: row.get(0)?,
+                        next_link: row.get(1)?,
+                        records_committed: row.get::<_, i64>(2)? as u64,
+                    })
+                },
+            )
+            .optional()
+            .map_err(sql_error)
+    }
+
+    fn create_tables(&mut self, properties: &[Property]) -> Result<()> {
+        self.conn
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    resource TEXT PRIMARY KEY,
+                    query TEXT,
+                    next_link TEXT,
+                    records_committed INTEGER NOT NULL
+                )",
+                CHECKPOINT_TABLE
+            ))
+            .map_err(sql_error)?;
+        // Checkpoint tables created before the query was recorded
+        if !self.table_columns(CHECKPOINT_TABLE)?.contains("query") {
+            self.conn
+                .execute_batch(&format!(
+                    "ALTER TABLE {} ADD COLUMN query TEXT",
+                    CHECKPOINT_TABLE
+                ))
+                .map_err(sql_error)?;
+        }
+
+        let mut definitions: Vec<String> = self
+            .key
+            .iter()
+            .filter(|k| !properties.iter().any(|p| &p.name == *k))
+            .map(|k| format!("{} NOT NULL", quote(k)))
+            .collect();
+        definitions.extend(properties.iter().map(|p| {
+            let not_null = if self.key.contains(&p.name) {
+                " NOT NULL"
+            } else {
+                ""
+            };
+            format!("{} {}{}", quote(&p.name), edm_column_type(p), not_null)
+        }));
+        let key_list: Vec<String> = self.key.iter().map(|k| quote(k)).collect();
+        definitions.push(format!("PRIMARY KEY ({})", key_list.join(", ")));
+
+        self.conn
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} ({})",
+                quote(&self.table),
+                definitions.join(", ")
+            ))
+            .map_err(sql_error)?;
+
+        self.load_columns()
+    }
+
+    fn load_columns(&mut self) -> Result<()> {
+        self.columns = self.table_columns(&self.table)?;
+        Ok(())
+    }
+
+    /// Column names of `table`
+    fn table_columns(&self, table: &str) -> Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("PRAGMA table_info({})", quote(table)))
+            .map_err(sql_error)?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(sql_error)?
+            .collect::<std::result::Result<_, _>>()
+            .map_err(sql_error)?;
+        Ok(columns)
+    }
+
+    /// Add columns for fields seen for the first time
+    fn ensure_columns(&mut self, record: &serde_json::Map<String, JsonValue>) -> Result<()> {
+        for (field, value) in record {
+            if is_annotation(field) || self.columns.contains(field) {
+                continue;
+            }
+            self.conn
+                .execute_batch(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    quote(&self.table),
+                    quote(field),
+                    json_column_type(value)
+                ))
+                .map_err(sql_error)?;
+            self.columns.insert(field.clone());
+        }
+        Ok(())
+    }
+
+    fn upsert(&mut self, record: &JsonValue) -> Result<()> {
+        let Some(fields) = record.as_object() else {
+            return Err(ResoError::Sink(format!(
+                "Expected a JSON object for {}, got {}",
+                self.resource, record
+            )));
+        };
+        if let Some(missing) = self.key.iter().find(|k| is_null(fields.get(*k))) {
+            return Err(ResoError::Sink(format!(
+                "{} record is missing key field {}",
+                self.resource, missing
+            )));
+        }
+        self.ensure_columns(fields)?;
+
+        let names: Vec<&String> = fields.keys().filter(|k| !is_annotation(k)).collect();
+        let columns: Vec<String> = names.iter().map(|n| quote(n)).collect();
+        let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+        let updates: Vec<String> = names
+            .iter()
+            .filter(|n| !self.key.contains(n))
+            .map(|n| format!("{0} = excluded.{0}", quote(n)))
+            .collect();
+        let key_list: Vec<String> = self.key.iter().map(|k| quote(k)).collect();
+
+        let conflict = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) {}",
+            quote(&self.table),
+            columns.join(", "),
+            placeholders.join(", "),
+            key_list.join(", "),
+            conflict
+        );
+
+        let mut stmt = self.conn.prepare_cached(&sql).map_err(sql_error)?;
+        stmt.execute(rusqlite::params_from_iter(
+            names.iter().map(|n| to_sql_value(&fields[n.as_str()])),
+        ))
+        .map_err(sql_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RecordSink for SqliteSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<()> {
+        if self.conn.is_autocommit() {
+            self.conn.execute_batch("BEGIN").map_err(sql_error)?;
+        }
+        for record in records {
+            self.upsert(record)?;
+        }
+        Ok(())
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> Result<()> {
+        if self.conn.is_autocommit() {
+            self.conn.execute_batch("BEGIN").map_err(sql_error)?;
+        }
+
+        self.conn
+            .execute(
+                &format!(
+                    "INSERT INTO {} (resource, query, next_link, records_committed)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (resource) DO UPDATE SET
+                        query = excluded.query,
+                        next_link = excluded.next_link,
+                        records_committed = excluded.records_committed",
+                    CHECKPOINT_TABLE
+                ),
+                params![
+                    checkpoint.resource,
+                    checkpoint.query,
+                    checkpoint.next_link,
+                    checkpoint.records_committed as i64
+                ],
+            )
+            .map_err(sql_error)?;
+
+        self.conn.execute_batch("COMMIT").map_err(sql_error)
+    }
+
+    async fn last_checkpoint(&mut self) -> Result<Option<ReplicationCheckpoint>> {
+        // Drop anything written after the last commit
+        if !self.conn.is_autocommit() {
+            self.conn.execute_batch("ROLLBACK").map_err(sql_error)?;
+            self.load_columns()?;
+        }
+
+        self.stored_checkpoint()
+    }
+}
+
+/// SQLite column type for an EDM property
+fn edm_column_type(property: &Property) -> &'static str {
+    if property.is_collection() {
+        return "TEXT";
+    }
+    match property.element_type() {
+        "Edm.Boolean" | "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64" => {
+            "INTEGER"
+        }
+        "Edm.Decimal" => "NUMERIC",
+        "Edm.Double" | "Edm.Single" => "REAL",
+        _ => "TEXT",
+    }
+}
+
+/// SQLite column type guessed from the first value seen for a field
+fn json_column_type(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Bool(_) => "INTEGER",
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => "INTEGER",
+        JsonValue::Number(_) => "REAL",
+        JsonValue::Null => "",
+        _ => "TEXT",
+    }
+}
+
+fn to_sql_value(value: &JsonValue) -> SqlValue {
+    match value {
+        JsonValue::Null => SqlValue::Null,
+        JsonValue::Bool(b) => SqlValue::Integer(*b as i64),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => SqlValue::Text(s.clone()),
+        JsonValue::Array(_) | JsonValue::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+/// OData control information such as `@odata.etag`
+fn is_annotation(field: &str) -> bool {
+    field.contains('@')
+}
+
+fn is_null(value: Option<&JsonValue>) -> bool {
+    value.is_none_or(JsonValue::is_null)
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sql_error(e: rusqlite::Error) -> ResoError {
+    ResoError::Sink(format!("SQLite error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sink() -> SqliteSink {
+        SqliteSink::with_connection(
+            Connection::open_in_memory().unwrap(),
+            "Property",
+            &["ListingKey"],
+        )
+        .unwrap()
+    }
+
+    fn checkpoint(next_link: Option<&str>, committed: u64) -> ReplicationCheckpoint {
+        ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication".to_string()),
+            next_link: next_link.map(|s| s.to_string()),
+            records_committed: committed,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_by_key() {
+        let mut sink = sink();
+        sink.write_batch(&[json!({"ListingKey": "1", "ListPrice": 100})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+        sink.write_batch(&[json!({"ListingKey": "1", "ListPrice": 200})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        assert_eq!(sink.row_count().unwrap(), 1);
+        let price: i64 = sink
+            .connection()
+            .query_row("SELECT ListPrice FROM Property", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(price, 200);
+    }
+
+    #[tokio::test]
+    async fn test_partial_record_keeps_other_columns() {
+        let mut sink = sink();
+        sink.write_batch(&[json!({"ListingKey": "1", "City": "Austin", "ListPrice": 100})])
+            .await
+            .unwrap();
+        sink.write_batch(&[json!({"ListingKey": "1", "ListPrice": 200})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+
+        let city: String = sink
+            .connection()
+            .query_row("SELECT City FROM Property", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(city, "Austin");
+    }
+
+    #[tokio::test]
+    async fn test_observed_fields_and_json_values() {
+        let mut sink = sink();
+        sink.write_batch(&[json!({
+            "@odata.etag": "W/\"1\"",
+            "ListingKey": "1",
+            "Appliances": ["Dishwasher"],
+            "NewConstructionYN": true
+        })])
+        .await
+        .unwrap();
+        sink.commit(&checkpoint(None, 1)).await.unwrap();
+
+        let (appliances, new): (String, i64) = sink
+            .connection()
+            .query_row(
+                "SELECT Appliances, NewConstructionYN FROM Property",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(appliances, "[\"Dishwasher\"]");
+        assert_eq!(new, 1);
+        assert!(!sink.columns.contains("@odata.etag"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_key_is_an_error() {
+        let mut sink = sink();
+        let result = sink.write_batch(&[json!({"City": "Austin"})]).await;
+        assert!(matches!(result, Err(ResoError::Sink(_))));
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_rows_rolled_back_on_resume() {
+        let mut sink = sink();
+        sink.write_batch(&[json!({"ListingKey": "1"})])
+            .await
+            .unwrap();
+        sink.commit(&checkpoint(Some("https://api.example.com/next"), 1))
+            .await
+            .unwrap();
+        sink.write_batch(&[json!({"ListingKey": "2", "NewField": "x"})])
+            .await
+            .unwrap();
+
+        let resumed = sink.last_checkpoint().await.unwrap();
+
+        assert_eq!(
+            resumed,
+            Some(checkpoint(Some("https://api.example.com/next"), 1))
+        );
+        assert_eq!(sink.row_count().unwrap(), 1);
+        // The rolled back ALTER TABLE is forgotten too
+        assert!(!sink.columns.contains("NewField"));
+    }
+
+    #[test]
+    fn test_table_from_entity_type() {
+        let entity_type = EntityType {
+            name: "Property".to_string(),
+            key: vec!["ListingKey".to_string()],
+            properties: vec![
+                Property {
+                    name: "ListingKey".to_string(),
+                    type_name: "Edm.String".to_string(),
+                    ..Default::default()
+                },
+                Property {
+                    name: "BedroomsTotal".to_string(),
+                    type_name: "Edm.Int32".to_string(),
+                    ..Default::default()
+                },
+                Property {
+                    name: "Appliances".to_string(),
+                    type_name: "Collection(Edm.String)".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let sink = SqliteSink::for_entity_type_with_connection(
+            Connection::open_in_memory().unwrap(),
+            "Property",
+            &entity_type,
+        )
+        .unwrap();
+
+        let mut stmt = sink
+            .connection()
+            .prepare("SELECT name, type, pk FROM pragma_table_info('Property') ORDER BY cid")
+            .unwrap();
+        let columns: Vec<(String, String, i64)> = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("ListingKey".to_string(), "TEXT".to_string(), 1),
+                ("BedroomsTotal".to_string(), "INTEGER".to_string(), 0),
+                ("Appliances".to_string(), "TEXT".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entity_type_without_key_is_rejected() {
+        let result = SqliteSink::for_entity_type_with_connection(
+            Connection::open_in_memory().unwrap(),
+            "Property",
+            &EntityType::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_incremental_query() {
+        let mut sink = sink();
+        let initial = sink
+            .incremental_query("ModificationTimestamp", Some("City eq 'Austin'"))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            initial.to_odata_string(),
+            "Property/replication?$filter=City%20eq%20%27Austin%27"
+        );
+
+        sink.write_batch(&[
+            json!({"ListingKey": "1", "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "2", "ModificationTimestamp": "2025-02-01T00:00:00Z"}),
+        ])
+        .await
+        .unwrap();
+        sink.commit(&checkpoint(None, 2)).await.unwrap();
+
+        assert_eq!(
+            sink.high_water_mark("ModificationTimestamp").unwrap(),
+            Some("2025-02-01T00:00:00Z".to_string())
+        );
+        let query = sink
+            .incremental_query("ModificationTimestamp", None)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            query.to_odata_string(),
+            "Property/replication?$filter=ModificationTimestamp%20ge%202025-02-01T00%3A00%3A00Z"
+        );
+    }
+    #[tokio::test]
+    async fn test_high_water_mark_compares_instants() {
+        let mut sink = sink();
+        sink.write_batch(&[
+            json!({"ListingKey": "1", "ModificationTimestamp": "2025-02-01T00:00:00Z"}),
+            json!({"ListingKey": "2", "ModificationTimestamp": "2025-02-01T00:00:00.5Z"}),
+            json!({"ListingKey": "3", "ModificationTimestamp": "2025-02-01T01:00:00.25+02:00"}),
+        ])
+        .await
+        .unwrap();
+        sink.commit(&checkpoint(None, 3)).await.unwrap();
+
+        assert_eq!(
+            sink.high_water_mark("ModificationTimestamp").unwrap(),
+            Some("2025-02-01T00:00:00.5Z".to_string())
+        );
+        assert_eq!(
+            sink.high_water_mark("ListingKey").unwrap(),
+            Some("3".to_string())
+        );
+    }
+}
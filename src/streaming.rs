@@ -0,0 +1,619 @@
+// src/streaming.rs
+
+//! Record-by-record parsing of large responses
+//!
+//! OData responses wrap their records in a `value` array. Rather than reading
+//! the whole body and building one large JSON tree, the client parses the
+//! body as it arrives and yields each record of the array on its own, so a
+//! page never sits in memory twice. The members around the array
+//! (`@odata.context`, `@odata.count`, `@odata.nextLink`, ...) are kept as
+//! annotations.
+//!
+//! [`ResoClient::stream_records`](crate::ResoClient::stream_records) streams
+//! the records of a query across all pages;
+//! [`ReplicationStream::next_record`](crate::ReplicationStream::next_record)
+//! does the same for the replication endpoint. Both can deserialize records
+//! straight into your own types.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "client")]
+//! # use reso_client::{QueryBuilder, ResoClient};
+//! # #[cfg(feature = "client")]
+//! # async fn example(client: &ResoClient) -> Result<(), Box<dyn std::error::Error>> {
+//! #[derive(serde::Deserialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Listing {
+//!     listing_key: String,
+//!     list_price: Option<f64>,
+//! }
+//!
+//! let query = QueryBuilder::new("Property")
+//!     .select(&["ListingKey", "ListPrice"])
+//!     .build()?;
+//! let mut stream = client.stream_records(&query);
+//! while let Some(listing) = stream.next_record_as::<Listing>().await? {
+//!     println!("{}: {:?}", listing.listing_key, listing.list_price);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "client")]
+use crate::client::ResoClient;
+use crate::error::{ResoError, Result};
+#[cfg(feature = "client")]
+use crate::options::RequestOptions;
+#[cfg(feature = "client")]
+use crate::queries::Query;
+#[cfg(feature = "client")]
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value as JsonValue};
+use std::ops::Range;
+
+/// Incremental parser for an OData envelope's `value` array
+#[derive(Debug, Default)]
+pub(crate) struct ValueArrayParser {
+    buf: Vec<u8>,
+    pos: usize,
+    state: State,
+    scan: Scan,
+    key: Option<String>,
+    annotations: Map<String, JsonValue>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the opening `{`
+    #[default]
+    Start,
+    /// Before the first member name or the closing `}`
+    FirstKey,
+    /// Before a member name that follows a `,`
+    Key,
+    /// Before the `:` after a member name
+    Colon,
+    /// Before a member value
+    Member,
+    /// Before the `,` or `}` after a member value
+    AfterMember,
+    /// Inside the `value` array, before the first record or `]`
+    FirstRecord,
+    /// Before a record that follows a `,`
+    Record,
+    /// Before the `,` or `]` after a record
+    AfterRecord,
+    /// After the closing `}`
+    End,
+}
+
+/// Progress through the value currently being scanned
+#[derive(Debug, Default)]
+struct Scan {
+    offset: usize,
+    depth: usize,
+    started: bool,
+    scalar: bool,
+    in_string: bool,
+    escaped: bool,
+}
+
+/// What the parser found next
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Event {
+    /// A complete record at this range of the buffer
+    Record(Range<usize>),
+    /// More input is needed
+    NeedData,
+    /// The envelope is complete
+    End,
+}
+
+impl ValueArrayParser {
+    /// Append a chunk of the body, dropping bytes already consumed
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// Bytes of a range returned with [`Event::Record`]
+    pub(crate) fn slice(&self, range: Range<usize>) -> &[u8] {
+        &self.buf[range]
+    }
+
+    /// Envelope members other than `value` seen so far
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    pub(crate) fn annotations(&self) -> &Map<String, JsonValue> {
+        &self.annotations
+    }
+
+    /// Advance to the next record; `eof` means no more input will follow
+    pub(crate) fn advance(&mut self, eof: bool) -> Result<Event> {
+        loop {
+            if self.state == State::End {
+                self.skip_whitespace();
+                return match self.buf.get(self.pos) {
+                    None => Ok(Event::End),
+                    Some(_) => Err(parse_error("trailing data after the response body")),
+                };
+            }
+
+            self.skip_whitespace();
+            let Some(&byte) = self.buf.get(self.pos) else {
+                return Self::need_data(eof);
+            };
+
+            match self.state {
+                State::Start => {
+                    self.expect(byte, b'{')?;
+                    self.state = State::FirstKey;
+                }
+                State::FirstKey | State::Key => match byte {
+                    b'}' if self.state == State::FirstKey => {
+                        self.pos += 1;
+                        self.state = State::End;
+                    }
+                    b'"' => {
+                        let Some(end) = self.scan_value(eof)? else {
+                            return Self::need_data(eof);
+                        };
+                        let key = serde_json::from_slice(&self.buf[self.pos..end])
+                            .map_err(|e| parse_error(&e.to_string()))?;
+                        self.key = Some(key);
+                        self.pos = end;
+                        self.state = State::Colon;
+                    }
+                    _ => return Err(parse_error("expected a member name")),
+                },
+                State::Colon => {
+                    self.expect(byte, b':')?;
+                    self.state = State::Member;
+                }
+                State::Member => {
+                    if byte == b'[' && self.key.as_deref() == Some("value") {
+                        self.pos += 1;
+                        self.key = None;
+                        self.state = State::FirstRecord;
+                        continue;
+                    }
+                    let Some(end) = self.scan_value(eof)? else {
+                        return Self::need_data(eof);
+                    };
+                    let value = serde_json::from_slice(&self.buf[self.pos..end])
+                        .map_err(|e| parse_error(&e.to_string()))?;
+                    self.annotations
+                        .insert(self.key.take().unwrap_or_default(), value);
+                    self.pos = end;
+                    self.state = State::AfterMember;
+                }
+                State::AfterMember => match byte {
+                    b',' => {
+                        self.pos += 1;
+                        self.state = State::Key;
+                    }
+                    b'}' => {
+                        self.pos += 1;
+                        self.state = State::End;
+                    }
+                    _ => return Err(parse_error("expected ',' or '}' after a member")),
+                },
+                State::FirstRecord | State::Record => match byte {
+                    b']' if self.state == State::FirstRecord => {
+                        self.pos += 1;
+                        self.state = State::AfterMember;
+                    }
+                    b',' | b']' => return Err(parse_error("expected a record")),
+                    _ => {
+                        let Some(end) = self.scan_value(eof)? else {
+                            return Self::need_data(eof);
+                        };
+                        let range = self.pos..end;
+                        self.pos = end;
+                        self.state = State::AfterRecord;
+                        return Ok(Event::Record(range));
+                    }
+                },
+                State::AfterRecord => match byte {
+                    b',' => {
+                        self.pos += 1;
+                        self.state = State::Record;
+                    }
+                    b']' => {
+                        self.pos += 1;
+                        self.state = State::AfterMember;
+                    }
+                    _ => return Err(parse_error("expected ',' or ']' after a record")),
+                },
+                State::End => unreachable!(),
+            }
+        }
+    }
+
+    fn need_data(eof: bool) -> Result<Event> {
+        if eof {
+            Err(parse_error("response body ended early"))
+        } else {
+            Ok(Event::NeedData)
+        }
+    }
+
+    fn expect(&mut self, byte: u8, expected: u8) -> Result<()> {
+        if byte != expected {
+            return Err(parse_error(&format!(
+                "expected '{}', found '{}'",
+                expected as char, byte as char
+            )));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .buf
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// End of the value starting at `pos`, or `None` if it is incomplete
+    ///
+    /// Scanning resumes where it stopped when more input arrives.
+    fn scan_value(&mut self, eof: bool) -> Result<Option<usize>> {
+        let scan = &mut self.scan;
+        let mut i = self.pos + scan.offset;
+        while let Some(&byte) = self.buf.get(i) {
+            if !scan.started {
+                scan.started = true;
+                match byte {
+                    b'"' => scan.in_string = true,
+                    b'{' | b'[' => scan.depth = 1,
+                    _ => scan.scalar = true,
+                }
+            } else if scan.in_string {
+                if scan.escaped {
+                    scan.escaped = false;
+                } else if byte == b'\\' {
+                    scan.escaped = true;
+                } else if byte == b'"' {
+                    scan.in_string = false;
+                    if scan.depth == 0 {
+                        self.scan = Scan::default();
+                        return Ok(Some(i + 1));
+                    }
+                }
+            } else if scan.scalar {
+                if matches!(byte, b',' | b']' | b'}') || byte.is_ascii_whitespace() {
+                    self.scan = Scan::default();
+                    return Ok(Some(i));
+                }
+            } else {
+                match byte {
+                    b'"' => scan.in_string = true,
+                    b'{' | b'[' => scan.depth += 1,
+                    b'}' | b']' => {
+                        scan.depth -= 1;
+                        if scan.depth == 0 {
+                            self.scan = Scan::default();
+                            return Ok(Some(i + 1));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            i += 1;
+        }
+
+        if eof && scan.scalar {
+            self.scan = Scan::default();
+            return Ok(Some(i));
+        }
+        scan.offset = i - self.pos;
+        Ok(None)
+    }
+}
+
+fn parse_error(message: &str) -> ResoError {
+    ResoError::Parse(format!("Failed to parse JSON: {}", message))
+}
+
+/// Records of a complete response body
+pub(crate) fn parse_records(body: &[u8]) -> Result<Vec<JsonValue>> {
+    let mut parser = ValueArrayParser::default();
+    parser.push(body);
+    let mut records = Vec::new();
+    while let Event::Record(range) = parser.advance(true)? {
+        records.push(
+            serde_json::from_slice(parser.slice(range))
+                .map_err(|e| ResoError::Parse(format!("Failed to parse record: {}", e)))?,
+        );
+    }
+    Ok(records)
+}
+
+/// Records of one response body, parsed as the body arrives
+#[cfg(feature = "client")]
+pub(crate) struct RecordReader {
+    response: reqwest::Response,
+    parser: ValueArrayParser,
+    eof: bool,
+    bytes_read: u64,
+}
+
+#[cfg(feature = "client")]
+impl RecordReader {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            parser: ValueArrayParser::default(),
+            eof: false,
+            bytes_read: 0,
+        }
+    }
+
+    /// The next record, deserialized as `T`
+    pub(crate) async fn next_as<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        loop {
+            match self.parser.advance(self.eof)? {
+                Event::Record(range) => {
+                    return serde_json::from_slice(self.parser.slice(range))
+                        .map(Some)
+                        .map_err(|e| ResoError::Parse(format!("Failed to parse record: {}", e)));
+                }
+                Event::End => return Ok(None),
+                Event::NeedData => match self
+                    .response
+                    .chunk()
+                    .await
+                    .map_err(|e| ResoError::Parse(format!("Failed to read response: {}", e)))?
+                {
+                    Some(chunk) => {
+                        self.bytes_read += chunk.len() as u64;
+                        self.parser.push(&chunk);
+                    }
+                    None => self.eof = true,
+                },
+            }
+        }
+    }
+
+    /// All remaining records
+    pub(crate) async fn collect(&mut self) -> Result<Vec<JsonValue>> {
+        let mut records = Vec::new();
+        while let Some(record) = self.next_as().await? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// `@odata.nextLink` from the body, once the parser has passed it
+    pub(crate) fn odata_next_link(&self) -> Option<String> {
+        self.parser
+            .annotations()
+            .get("@odata.nextLink")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    /// Body bytes received so far
+    pub(crate) fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// HTTP status code of the response
+    pub(crate) fn status(&self) -> u16 {
+        self.response.status().as_u16()
+    }
+
+    /// Headers of the response
+    pub(crate) fn headers(&self) -> &http::HeaderMap {
+        self.response.headers()
+    }
+}
+
+/// Records of a query across all pages, parsed as they arrive
+///
+/// Created by [`ResoClient::stream_records`]. Each page is requested when
+/// the previous one is exhausted, following `@odata.nextLink`. Responses are
+/// not cached.
+#[cfg(feature = "client")]
+pub struct RecordStream<'a> {
+    client: &'a ResoClient,
+    next_url: Option<String>,
+    reader: Option<RecordReader>,
+    pages_fetched: u64,
+    records_fetched: u64,
+    options: RequestOptions,
+}
+
+#[cfg(feature = "client")]
+impl<'a> RecordStream<'a> {
+    pub(crate) fn new(client: &'a ResoClient, query: &Query) -> Self {
+        Self {
+            client,
+            next_url: Some(client.build_url(&query.to_odata_string())),
+            reader: None,
+            pages_fetched: 0,
+            records_fetched: 0,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Apply per-call options to the stream
+    ///
+    /// Every page is requested with the headers of `options`. The timeout
+    /// applies to each `next_*` call, the deadline and token to the stream
+    /// as a whole.
+    ///
+    /// A call that times out or is cancelled discards the page it was
+    /// reading, and the next call requests that page again from its start.
+    /// Records of that page already returned are then returned again, so
+    /// callers see each record at least once.
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The next record
+    ///
+    /// Returns `Ok(None)` after the last record of the last page.
+    pub async fn next_record(&mut self) -> Result<Option<JsonValue>> {
+        self.next_record_as().await
+    }
+
+    /// The next record, deserialized as `T`
+    pub async fn next_record_as<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        let options = self.options.clone();
+        let result = options.run(self.read_record()).await;
+        if matches!(result, Err(ResoError::Timeout(_) | ResoError::Cancelled)) {
+            // `next_url` still points at the interrupted page
+            self.reader = None;
+        }
+        result
+    }
+
+    /// Read one record; `next_url` stays on the open page until it is finished
+    async fn read_record<T: DeserializeOwned>(&mut self) -> Result<Option<T>> {
+        use tracing::info;
+
+        loop {
+            if let Some(reader) = &mut self.reader {
+                if let Some(record) = reader.next_as().await? {
+                    self.records_fetched += 1;
+                    return Ok(Some(record));
+                }
+                self.next_url = reader.odata_next_link();
+                self.reader = None;
+            }
+
+            let Some(url) = &self.next_url else {
+                return Ok(None);
+            };
+            info!("Streaming query page: {}", url);
+            let response = self
+                .client
+                .send_authenticated_request(url, "application/json", &self.options)
+                .await?;
+            self.reader = Some(RecordReader::new(response));
+            self.pages_fetched += 1;
+        }
+    }
+
+    /// Pages requested so far
+    pub fn pages_fetched(&self) -> u64 {
+        self.pages_fetched
+    }
+
+    /// Records returned so far
+    pub fn records_fetched(&self) -> u64 {
+        self.records_fetched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Parse `body` fed in chunks of `size` bytes
+    fn parse(body: &str, size: usize) -> Result<(Vec<JsonValue>, Map<String, JsonValue>)> {
+        let mut parser = ValueArrayParser::default();
+        let mut chunks = body.as_bytes().chunks(size);
+        let mut records = Vec::new();
+        let mut eof = false;
+        loop {
+            match parser.advance(eof)? {
+                Event::Record(range) => {
+                    records.push(serde_json::from_slice(parser.slice(range)).unwrap())
+                }
+                Event::NeedData => match chunks.next() {
+                    Some(chunk) => parser.push(chunk),
+                    None => eof = true,
+                },
+                Event::End => return Ok((records, parser.annotations().clone())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parses_records_in_any_chunk_size() {
+        let body = r#" {
+            "@odata.context": "$metadata#Property",
+            "@odata.count": 3,
+            "value": [
+                {"ListingKey": "1", "PublicRemarks": "Quote \" and brace } and bracket ]"},
+                {"ListingKey": "2", "Media": [{"Order": 1}, {"Order": 2}], "ListPrice": 1.5e5},
+                {"ListingKey": "3", "Remarks": "back\\slash", "Flag": true, "Other": null}
+            ],
+            "@odata.nextLink": "https://api.example.com/Property?$skip=3"
+        } "#;
+        let expected: JsonValue = serde_json::from_str(body).unwrap();
+
+        for size in [1, 2, 7, 64, body.len()] {
+            let (records, annotations) = parse(body, size).unwrap();
+            assert_eq!(
+                JsonValue::Array(records),
+                expected["value"],
+                "chunk size {}",
+                size
+            );
+            assert_eq!(annotations["@odata.count"], 3);
+            assert_eq!(
+                annotations["@odata.nextLink"],
+                "https://api.example.com/Property?$skip=3"
+            );
+            assert!(!annotations.contains_key("value"));
+        }
+    }
+
+    #[test]
+    fn test_scalar_and_empty_arrays() {
+        let (records, _) = parse(r#"{"value":[1,-2.5,"x",null,[]]}"#, 3).unwrap();
+        assert_eq!(
+            records,
+            [json!(1), json!(-2.5), json!("x"), json!(null), json!([])]
+        );
+
+        let (records, annotations) = parse(r#"{"value": [], "@odata.count": 0}"#, 4).unwrap();
+        assert!(records.is_empty());
+        assert_eq!(annotations["@odata.count"], 0);
+
+        let (records, annotations) = parse(r#"{"error": {"code": "x"}}"#, 5).unwrap();
+        assert!(records.is_empty());
+        assert_eq!(annotations["error"]["code"], "x");
+    }
+
+    #[test]
+    fn test_malformed_bodies() {
+        assert!(parse(r#"{"value": [{"ListingKey": "1"}"#, 4).is_err());
+        assert!(parse(r#"[{"ListingKey": "1"}]"#, 4).is_err());
+        assert!(parse(r#"{"value": []} extra"#, 4).is_err());
+        assert!(parse("", 4).is_err());
+
+        // Separators out of place
+        for body in [
+            r#"{ , "value": [,,1,] }"#,
+            r#"{ , "value": [] }"#,
+            r#"{"value": [] , }"#,
+            r#"{"a": 1,, "value": []}"#,
+            r#"{"a": 1 "value": []}"#,
+            r#"{"value": [,{"ListingKey": "1"}]}"#,
+            r#"{"value": [{"ListingKey": "1"},,{"ListingKey": "2"}]}"#,
+            r#"{"value": [{"ListingKey": "1"},]}"#,
+            r#"{"value": [{"ListingKey": "1"} {"ListingKey": "2"}]}"#,
+            r#"{"value": [1 2]}"#,
+        ] {
+            for size in [1, 3, body.len()] {
+                assert!(parse(body, size).is_err(), "{} in chunks of {}", body, size);
+            }
+        }
+        assert_eq!(parse(r#"{}"#, 1).unwrap().0.len(), 0);
+        assert_eq!(parse(r#"{"value": [1, 2]}"#, 1).unwrap().0.len(), 2);
+    }
+}
@@ -0,0 +1,355 @@
+// src/transport.rs
+
+//! Runtime-agnostic request execution
+//!
+//! Query building, URL rendering, response parsing and the error types need
+//! neither an HTTP stack nor an async runtime. The one piece that does is
+//! sending a request, which is abstracted behind the [`Transport`] trait:
+//! given an [`HttpRequest`], return the complete [`HttpResponse`].
+//!
+//! With the default `client` feature,
+//! [`ResoClient`](crate::ResoClient) sends requests with reqwest, and
+//! [`ClientConfig::with_transport`](crate::ClientConfig::with_transport)
+//! routes them through any other `Transport` while keeping middleware,
+//! caching and streaming. Built with `default-features = false`, the crate
+//! has no reqwest or tokio dependency; [`ServiceRoot`] then executes queries
+//! through a transport directly, for example one backed by the browser's
+//! `fetch` on WASM.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use reso_client::transport::{HttpRequest, HttpResponse, ServiceRoot, Transport};
+//! use reso_client::{async_trait, QueryBuilder, Result};
+//!
+//! /// Sends requests with some other HTTP library
+//! struct MyTransport;
+//!
+//! #[async_trait]
+//! impl Transport for MyTransport {
+//!     async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+//!         // ... send `request` and collect the response body
+//! #       unimplemented!()
+//!     }
+//! }
+//!
+//! # async fn example() -> Result<()> {
+//! let root = ServiceRoot::new("https://api.mls.com/odata").with_token("your-token");
+//! let query = QueryBuilder::new("Property").top(10).build()?;
+//! let results = root.execute(&MyTransport, &query).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{ResoError, Result};
+use crate::queries::{Query, ReplicationQuery};
+use crate::replication::ReplicationResponse;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+
+/// A request as handed to a [`Transport`]
+pub type HttpRequest = http::Request<Vec<u8>>;
+
+/// A complete response as returned by a [`Transport`]
+pub type HttpResponse = http::Response<Vec<u8>>;
+
+/// Sends HTTP requests
+///
+/// Implementations send the request as given and return the response
+/// whatever its status; status handling is up to the caller. Failures to
+/// reach the server should be reported as [`ResoError::Network`].
+///
+/// On `wasm32` targets the trait requires neither `Send` nor `Sync`, so
+/// transports may hold JavaScript objects.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Transport: MaybeSendSync + 'static {
+    /// Send a request and return the complete response
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// `Send + Sync`, except on `wasm32`
+#[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSendSync: Send + Sync {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync + ?Sized> MaybeSendSync for T {}
+
+/// `Send + Sync`, except on `wasm32`
+#[doc(hidden)]
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSendSync {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSendSync for T {}
+
+/// A RESO server's service root, with the token used to reach it
+///
+/// Renders request URLs and executes queries through any [`Transport`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ServiceRoot {
+    /// Base URL, without a trailing slash
+    pub base_url: String,
+
+    /// Optional dataset ID inserted between the base URL and the resource
+    pub dataset_id: Option<String>,
+
+    /// Bearer token sent with every request
+    pub token: Option<String>,
+}
+
+impl std::fmt::Debug for ServiceRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceRoot")
+            .field("base_url", &self.base_url)
+            .field("dataset_id", &self.dataset_id)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl ServiceRoot {
+    /// Service root at `base_url`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            dataset_id: None,
+            token: None,
+        }
+    }
+
+    /// Insert a dataset ID between the base URL and the resource
+    pub fn with_dataset_id(mut self, dataset_id: impl Into<String>) -> Self {
+        self.dataset_id = Some(dataset_id.into());
+        self
+    }
+
+    /// Send a bearer token with every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Full URL for a path below the service root
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reso_client::transport::ServiceRoot;
+    /// # use reso_client::QueryBuilder;
+    /// let root = ServiceRoot::new("https://api.mls.com/odata/").with_dataset_id("actris_ref");
+    /// let query = QueryBuilder::new("Property").top(5).build()?;
+    /// assert_eq!(
+    ///     root.url(&query.to_odata_string()),
+    ///     "https://api.mls.com/odata/actris_ref/Property?$top=5"
+    /// );
+    /// # Ok::<(), reso_client::ResoError>(())
+    /// ```
+    pub fn url(&self, path: &str) -> String {
+        service_url(&self.base_url, self.dataset_id.as_deref(), path)
+    }
+
+    /// GET request for `url` accepting `accept`, with the bearer token
+    pub fn request(&self, url: &str, accept: &str) -> Result<HttpRequest> {
+        let mut builder = http::Request::get(url).header(http::header::ACCEPT, accept);
+        if let Some(token) = &self.token {
+            builder = builder.header(http::header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder
+            .body(Vec::new())
+            .map_err(|e| ResoError::Config(format!("Invalid request: {}", e)))
+    }
+
+    /// Execute a query and return raw JSON
+    ///
+    /// Works for collection queries and direct key access alike.
+    pub async fn execute<T: Transport + ?Sized>(
+        &self,
+        transport: &T,
+        query: &Query,
+    ) -> Result<JsonValue> {
+        let url = self.url(&query.to_odata_string());
+        let response = transport
+            .send(self.request(&url, "application/json")?)
+            .await?;
+        parse_json(&response)
+    }
+
+    /// Execute a count-only query
+    pub async fn execute_count<T: Transport + ?Sized>(
+        &self,
+        transport: &T,
+        query: &Query,
+    ) -> Result<u64> {
+        let url = self.url(&query.to_odata_string());
+        let response = transport.send(self.request(&url, "text/plain")?).await?;
+        parse_count(&response)
+    }
+
+    /// Fetch the `$metadata` document as XML
+    pub async fn fetch_metadata<T: Transport + ?Sized>(&self, transport: &T) -> Result<String> {
+        let url = self.url("$metadata");
+        let response = transport
+            .send(self.request(&url, "application/xml")?)
+            .await?;
+        parse_text(&response)
+    }
+
+    /// Execute a replication query and return its first page
+    pub async fn execute_replication<T: Transport + ?Sized>(
+        &self,
+        transport: &T,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse> {
+        let url = self.url(&query.to_odata_string());
+        self.execute_next_link(transport, &url).await
+    }
+
+    /// Fetch the replication page behind a `next` link
+    pub async fn execute_next_link<T: Transport + ?Sized>(
+        &self,
+        transport: &T,
+        next_link: &str,
+    ) -> Result<ReplicationResponse> {
+        let response = transport
+            .send(self.request(next_link, "application/json")?)
+            .await?;
+        parse_replication(&response)
+    }
+}
+
+/// `{base_url}/{dataset_id}/{path}`, or `{base_url}/{path}` without a dataset
+pub(crate) fn service_url(base_url: &str, dataset_id: Option<&str>, path: &str) -> String {
+    match dataset_id {
+        Some(dataset_id) => format!("{}/{}/{}", base_url, dataset_id, path),
+        None => format!("{}/{}", base_url, path),
+    }
+}
+
+/// Turn an error status into the matching [`ResoError`]
+pub fn check_status(response: &HttpResponse) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = String::from_utf8_lossy(response.body());
+        return Err(ResoError::from_status(status.as_u16(), &body));
+    }
+    Ok(())
+}
+
+/// Parse a JSON response body
+pub fn parse_json(response: &HttpResponse) -> Result<JsonValue> {
+    check_status(response)?;
+    serde_json::from_slice(response.body())
+        .map_err(|e| ResoError::Parse(format!("Failed to parse JSON: {}", e)))
+}
+
+/// Parse the body of a `/$count` response
+pub fn parse_count(response: &HttpResponse) -> Result<u64> {
+    let text = parse_text(response)?;
+    text.trim()
+        .parse::<u64>()
+        .map_err(|e| ResoError::Parse(format!("Failed to parse count '{}': {}", text, e)))
+}
+
+/// Parse a replication page: its records and the `next` link from the headers
+pub fn parse_replication(response: &HttpResponse) -> Result<ReplicationResponse> {
+    check_status(response)?;
+    let records = crate::streaming::parse_records(response.body())?;
+    Ok(ReplicationResponse::new(
+        records,
+        replication_next_link(response.headers()),
+    ))
+}
+
+fn parse_text(response: &HttpResponse) -> Result<String> {
+    check_status(response)?;
+    String::from_utf8(response.body().clone())
+        .map_err(|e| ResoError::Parse(format!("Failed to read response: {}", e)))
+}
+
+/// The replication `next` header (preferred) or `link` header
+pub(crate) fn replication_next_link(headers: &http::HeaderMap) -> Option<String> {
+    headers
+        .get("next")
+        .or_else(|| headers.get("link"))
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::{QueryBuilder, ReplicationQueryBuilder};
+    use std::sync::Mutex;
+
+    /// Answers with canned responses and records the requests it saw
+    struct Canned {
+        responses: Mutex<Vec<HttpResponse>>,
+        seen: Mutex<Vec<HttpRequest>>,
+    }
+
+    #[async_trait]
+    impl Transport for Canned {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            self.seen.lock().unwrap().push(request);
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> HttpResponse {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(body.as_bytes().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_service_root_executes_through_transport() {
+        let transport = Canned {
+            responses: Mutex::new(vec![
+                response(200, &[], r#"{"value": [{"ListingKey": "1"}]}"#),
+                response(200, &[], " 42\n"),
+                response(
+                    200,
+                    &[("next", "https://api.mls.com/odata/Property/replication?p=2")],
+                    r#"{"value": [{"ListingKey": "1"}, {"ListingKey": "2"}]}"#,
+                ),
+                response(401, &[], r#"{"error": {"message": "expired"}}"#),
+            ]),
+            seen: Mutex::default(),
+        };
+        let root = ServiceRoot::new("https://api.mls.com/odata").with_token("secret");
+
+        let query = QueryBuilder::new("Property").top(1).build().unwrap();
+        let json = root.execute(&transport, &query).await.unwrap();
+        assert_eq!(json["value"][0]["ListingKey"], "1");
+
+        let query = QueryBuilder::new("Property").count().build().unwrap();
+        assert_eq!(root.execute_count(&transport, &query).await.unwrap(), 42);
+
+        let query = ReplicationQueryBuilder::new("Property").build().unwrap();
+        let page = root.execute_replication(&transport, &query).await.unwrap();
+        assert_eq!(page.record_count, 2);
+        assert_eq!(
+            page.next_link(),
+            Some("https://api.mls.com/odata/Property/replication?p=2")
+        );
+
+        let err = root.fetch_metadata(&transport).await.unwrap_err();
+        assert!(matches!(err, ResoError::Unauthorized { .. }));
+
+        let seen = transport.seen.lock().unwrap();
+        assert_eq!(seen[0].uri(), "https://api.mls.com/odata/Property?$top=1");
+        assert_eq!(seen[0].headers()["authorization"], "Bearer secret");
+        assert_eq!(seen[1].headers()["accept"], "text/plain");
+        assert_eq!(seen[3].uri(), "https://api.mls.com/odata/$metadata");
+    }
+
+    #[test]
+    fn test_debug_redacts_token() {
+        let root = ServiceRoot::new("https://api.mls.com/odata").with_token("secret");
+        assert!(!format!("{:?}", root).contains("secret"));
+    }
+}
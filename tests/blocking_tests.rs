@@ -0,0 +1,107 @@
+// Integration tests for the blocking client
+#![cfg(feature = "blocking")]
+
+use reso_client::blocking::ResoClient;
+use reso_client::{ClientConfig, QueryBuilder, ReplicationQueryBuilder, ResoError};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Start a mock server on its own runtime, outside the calling thread
+fn mock_server(runtime: &tokio::runtime::Runtime) -> MockServer {
+    runtime.block_on(MockServer::start())
+}
+
+#[test]
+fn test_blocking_queries() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = mock_server(&runtime);
+    runtime.block_on(async {
+        Mock::given(method("GET"))
+            .and(path("/Property"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": [{"ListingKey": "1"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Property/$count"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("42"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Member"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+    });
+
+    let client = ResoClient::with_config(ClientConfig::new(server.uri(), "token")).unwrap();
+    assert_eq!(client.base_url(), server.uri());
+
+    let query = QueryBuilder::new("Property").top(1).build().unwrap();
+    assert_eq!(
+        client.execute(&query).unwrap()["value"][0]["ListingKey"],
+        "1"
+    );
+
+    let query = QueryBuilder::new("Property").count().build().unwrap();
+    assert_eq!(client.execute_count(&query).unwrap(), 42);
+
+    let query = QueryBuilder::new("Member").build().unwrap();
+    assert!(matches!(
+        client.execute(&query).unwrap_err(),
+        ResoError::Unauthorized { .. }
+    ));
+}
+
+#[test]
+fn test_blocking_replication_iterator() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = mock_server(&runtime);
+    let next = format!("{}/Property/replication?page=2", server.uri());
+    runtime.block_on(async {
+        Mock::given(method("GET"))
+            .and(path("/Property/replication"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": [{"ListingKey": "3"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Property/replication"))
+            .and(query_param("$top", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("next", next.as_str())
+                    .set_body_json(serde_json::json!({
+                        "value": [{"ListingKey": "1"}, {"ListingKey": "2"}]
+                    })),
+            )
+            .mount(&server)
+            .await;
+    });
+
+    let client = ResoClient::with_config(ClientConfig::new(server.uri(), "token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let first = client.execute_replication(&query).unwrap();
+    assert_eq!(first.record_count, 2);
+    let second = client
+        .execute_next_link(first.next_link().unwrap())
+        .unwrap();
+    assert_eq!(second.record_count, 1);
+    assert!(!second.has_more());
+
+    let mut records = client.replicate(&query);
+    let keys: Vec<_> = records
+        .by_ref()
+        .map(|record| record.unwrap()["ListingKey"].clone())
+        .collect();
+    assert_eq!(keys, ["1", "2", "3"]);
+    assert_eq!(records.progress().pages_fetched, 2);
+    assert_eq!(records.progress().records_fetched, 3);
+}
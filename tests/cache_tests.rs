@@ -0,0 +1,178 @@
+// Integration tests for the HTTP response cache
+#![cfg(feature = "client")]
+
+use reso_client::{
+    ClientConfig, HttpCache, MetadataLevel, QueryBuilder, RequestOptions, ResoClient,
+};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const METADATA: &str = "<edmx:Edmx Version=\"4.0\"/>";
+
+fn client(server: &MockServer, cache: HttpCache) -> ResoClient {
+    ResoClient::with_config(ClientConfig::new(server.uri(), "test-token").with_cache(cache))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_etag_revalidation() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Lookup"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304).insert_header("ETag", "\"v1\""))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Lookup"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(serde_json::json!({"value": [{"LookupKey": "1"}]})),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, HttpCache::in_memory());
+    let query = QueryBuilder::new("Lookup").build().unwrap();
+
+    let first = client.execute(&query).await.unwrap();
+    let second = client.execute(&query).await.unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(second["value"][0]["LookupKey"], "1");
+}
+
+#[tokio::test]
+async fn test_max_age_served_without_request() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Member"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=3600")
+                .set_body_json(serde_json::json!({"value": [{"MemberKey": "M1"}]})),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, HttpCache::in_memory());
+    let query = QueryBuilder::new("Member").build().unwrap();
+
+    client.execute(&query).await.unwrap();
+    let cached = client.execute(&query).await.unwrap();
+
+    assert_eq!(cached["value"][0]["MemberKey"], "M1");
+}
+
+#[tokio::test]
+async fn test_no_store_and_uncached_always_fetch() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "no-store")
+                .insert_header("ETag", "\"c1\"")
+                .set_body_string("42"),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Member"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=3600")
+                .set_body_json(serde_json::json!({"value": []})),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, HttpCache::in_memory());
+    let count = QueryBuilder::new("Property").count().build().unwrap();
+    assert_eq!(client.execute_count(&count).await.unwrap(), 42);
+    assert_eq!(client.execute_count(&count).await.unwrap(), 42);
+
+    let member = QueryBuilder::new("Member").build().unwrap();
+    client.uncached().execute(&member).await.unwrap();
+    client.uncached().execute(&member).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_metadata_ttl_override_on_disk() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/$metadata"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "no-cache")
+                .set_body_string(METADATA),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache = || {
+        HttpCache::on_disk(dir.path())
+            .unwrap()
+            .with_metadata_ttl(Duration::from_secs(3600))
+    };
+
+    let first = client(&mock_server, cache())
+        .fetch_metadata()
+        .await
+        .unwrap();
+    // A new client (as in the next job) reads the entry from disk
+    let second = client(&mock_server, cache())
+        .fetch_metadata()
+        .await
+        .unwrap();
+
+    assert_eq!(first, METADATA);
+    assert_eq!(second, METADATA);
+}
+
+#[tokio::test]
+async fn test_requests_with_headers_bypass_cache() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Member"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=3600")
+                .set_body_json(serde_json::json!({"value": []})),
+        )
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let client = client(&mock_server, HttpCache::in_memory());
+    let query = QueryBuilder::new("Member").build().unwrap();
+    let options = RequestOptions::new().with_metadata(MetadataLevel::Full);
+
+    client.execute(&query).await.unwrap();
+    client.execute_with(&query, &options).await.unwrap();
+    client.execute_with(&query, &options).await.unwrap();
+    // Limits alone do not change the response, so the cache still applies
+    client
+        .execute_with(
+            &query,
+            &RequestOptions::new().with_timeout(Duration::from_secs(30)),
+        )
+        .await
+        .unwrap();
+}
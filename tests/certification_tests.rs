@@ -0,0 +1,158 @@
+// Integration tests for the Web API Core certification harness
+#![cfg(feature = "mock-server")]
+
+use reso_client::{CertificationRunner, ClientConfig, MockResoServer, ResoClient, ScenarioStatus};
+use serde_json::json;
+
+const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String"/>
+        <Property Name="City" Type="Edm.String"/>
+        <Property Name="WaterfrontYN" Type="Edm.Boolean"/>
+        <Property Name="BedroomsTotal" Type="Edm.Int32"/>
+        <Property Name="ListPrice" Type="Edm.Decimal"/>
+        <Property Name="ListingContractDate" Type="Edm.Date"/>
+        <Property Name="ModificationTimestamp" Type="Edm.DateTimeOffset"/>
+        <Property Name="StandardStatus" Type="org.reso.metadata.enums.StandardStatus"/>
+        <Property Name="Appliances" Type="Collection(org.reso.metadata.enums.Appliances)"/>
+      </EntityType>
+      <EntityContainer Name="Default">
+        <EntitySet Name="Property" EntityType="org.reso.metadata.Property"/>
+      </EntityContainer>
+    </Schema>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata.enums">
+      <EnumType Name="StandardStatus">
+        <Member Name="Active"/>
+        <Member Name="Closed"/>
+      </EnumType>
+      <EnumType Name="Appliances">
+        <Member Name="Dishwasher"/>
+        <Member Name="Range"/>
+      </EnumType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+async fn server() -> MockResoServer {
+    MockResoServer::builder()
+        .resource(
+            "Property",
+            "ListingKey",
+            vec![
+                json!({
+                    "ListingKey": "1", "City": "Austin", "WaterfrontYN": true,
+                    "BedroomsTotal": 3, "ListPrice": 450000.5,
+                    "ListingContractDate": "2025-01-05",
+                    "ModificationTimestamp": "2025-01-10T10:00:00-06:00",
+                    "StandardStatus": "Active", "Appliances": ["Dishwasher", "Range"]
+                }),
+                json!({
+                    "ListingKey": "2", "City": "Dallas", "WaterfrontYN": false,
+                    "BedroomsTotal": 4, "ListPrice": 325000.0,
+                    "ListingContractDate": "2025-02-05",
+                    "ModificationTimestamp": "2025-02-10T00:00:00Z",
+                    "StandardStatus": "Closed", "Appliances": ["Range"]
+                }),
+                json!({
+                    "ListingKey": "3", "City": "Houston", "WaterfrontYN": null,
+                    "BedroomsTotal": 2, "ListPrice": 615000.0,
+                    "ListingContractDate": "2025-03-05",
+                    "ModificationTimestamp": "2025-03-10T00:00:00.000Z",
+                    "StandardStatus": "Active", "Appliances": []
+                }),
+            ],
+        )
+        .metadata(METADATA)
+        .start()
+        .await
+}
+
+#[tokio::test]
+async fn test_mock_server_passes_every_scenario() {
+    let server = server().await;
+    let client = server.client().unwrap();
+
+    let report = CertificationRunner::new().run(&client).await;
+
+    for scenario in &report.scenarios {
+        assert_eq!(
+            scenario.status,
+            ScenarioStatus::Passed,
+            "{}: {:?}",
+            scenario.id,
+            scenario.message
+        );
+    }
+    assert_eq!(report.scenarios.len(), 17);
+
+    let date = report.scenario("filter-date").unwrap();
+    assert_eq!(
+        date.requests,
+        vec![format!(
+            "{}/Property?$filter=ListingContractDate%20eq%202025-01-05&$top=20",
+            server.uri()
+        )]
+    );
+    assert_eq!(
+        report.scenario("string-functions").unwrap().requests.len(),
+        4
+    );
+    assert_eq!(report.to_json()["scenarios"][0]["id"], "metadata");
+}
+
+#[tokio::test]
+async fn test_unreachable_server_fails_and_skips() {
+    let client = ResoClient::with_config(ClientConfig::new("http://127.0.0.1:9", "token")).unwrap();
+    let report = CertificationRunner::new().run(&client).await;
+
+    assert!(!report.is_passing());
+    assert_eq!(report.count(ScenarioStatus::Failed), 2);
+    assert_eq!(report.count(ScenarioStatus::Skipped), 15);
+    assert_eq!(
+        report.scenario("metadata").unwrap().requests,
+        vec!["http://127.0.0.1:9/$metadata"]
+    );
+}
+
+#[tokio::test]
+async fn test_ignored_filter_fails_with_offending_record() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/$metadata"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(METADATA))
+        .mount(&mock_server)
+        .await;
+    // Every query returns the same two records, whatever the $filter
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "value": [
+                {"ListingKey": "1", "City": "Austin"},
+                {"ListingKey": "2", "City": "Dallas"}
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let report = CertificationRunner::new().run(&client).await;
+
+    let string = report.scenario("filter-string").unwrap();
+    assert_eq!(string.status, ScenarioStatus::Failed);
+    assert_eq!(
+        string.message.as_deref(),
+        Some("record 2 does not satisfy City eq 'Austin'")
+    );
+    assert_eq!(
+        report.scenario("filter-boolean").unwrap().status,
+        ScenarioStatus::Skipped
+    );
+}
@@ -0,0 +1,128 @@
+// Integration tests for the `reso` command-line tool
+#![cfg(all(feature = "cli", feature = "mock-server"))]
+
+use reso_client::MockResoServer;
+use serde_json::{json, Value as JsonValue};
+use std::process::Command;
+
+async fn server() -> MockResoServer {
+    MockResoServer::builder()
+        .resource(
+            "Property",
+            "ListingKey",
+            vec![
+                json!({"ListingKey": "1", "City": "Austin", "ListPrice": 450000}),
+                json!({"ListingKey": "2", "City": "Dallas", "ListPrice": null}),
+                json!({"ListingKey": "3", "City": "Austin", "ListPrice": 615000}),
+            ],
+        )
+        .start()
+        .await
+}
+
+/// Run `reso` against the server and return stdout
+async fn reso(server: &MockResoServer, args: &[&str]) -> String {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_reso"));
+    command
+        .args(args)
+        .env("RESO_BASE_URL", server.uri())
+        .env("RESO_TOKEN", "mock-token")
+        .env_remove("RESO_DATASET_ID");
+    let output = tokio::task::spawn_blocking(move || command.output())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_get_and_count() {
+    let server = server().await;
+
+    let csv = reso(
+        &server,
+        &[
+            "query",
+            "Property",
+            "--filter",
+            "City eq 'Austin'",
+            "--select",
+            "ListingKey,ListPrice",
+            "--orderby",
+            "ListPrice desc",
+            "--format",
+            "csv",
+        ],
+    )
+    .await;
+    assert_eq!(csv, "ListingKey,ListPrice\n3,615000\n1,450000\n");
+
+    let csv = reso(
+        &server,
+        &[
+            "query",
+            "Property",
+            "--select",
+            "ListingKey",
+            "--orderby",
+            "City desc, ListPrice asc",
+            "--format",
+            "csv",
+        ],
+    )
+    .await;
+    assert_eq!(csv, "ListingKey\n2\n1\n3\n");
+
+    let record: Vec<JsonValue> =
+        serde_json::from_str(&reso(&server, &["get", "Property", "2"]).await).unwrap();
+    assert_eq!(record[0]["City"], "Dallas");
+
+    let count = reso(
+        &server,
+        &["count", "Property", "--filter", "City eq 'Austin'"],
+    )
+    .await;
+    assert_eq!(count.trim(), "2");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_metadata_fields_and_replicate() {
+    let server = server().await;
+
+    let properties = reso(
+        &server,
+        &["metadata", "--resource", "Property", "-f", "ndjson"],
+    )
+    .await;
+    let rows: Vec<JsonValue> = properties
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    let key = rows.iter().find(|r| r["name"] == "ListingKey").unwrap();
+    assert_eq!(key["key"], true);
+    assert_eq!(key["type"], "Edm.String");
+
+    let table = reso(&server, &["fields", "Property", "--format", "table"]).await;
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("field"));
+    assert!(lines
+        .iter()
+        .any(|l| l.starts_with("ListPrice") && l.contains("66.7")));
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().to_str().unwrap();
+    reso(
+        &server,
+        &["replicate", "Property", "--output", output, "--top", "2"],
+    )
+    .await;
+    let exported = std::fs::read_to_string(dir.path().join("Property-00000.ndjson")).unwrap();
+    assert_eq!(exported.lines().count(), 3);
+    assert!(dir.path().join("Property-manifest.json").exists());
+}
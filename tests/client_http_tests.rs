@@ -1,6 +1,10 @@
 // Integration tests for ResoClient HTTP operations
+#![cfg(feature = "client")]
 
-use reso_client::{ClientConfig, QueryBuilder, ReplicationQueryBuilder, ResoClient, ResoError};
+use reso_client::{
+    ClientConfig, HttpClientConfig, MetadataLevel, QueryBuilder, ReplicationQueryBuilder,
+    RequestOptions, ResoClient, ResoError,
+};
 use wiremock::matchers::{header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -624,3 +628,154 @@ async fn test_authorization_header_included() {
 
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_prebuilt_http_client() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(header("User-Agent", "listing-sync/1.0"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": []
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let http_client = reqwest::Client::builder()
+        .user_agent("listing-sync/1.0")
+        .build()
+        .unwrap();
+    let config = ClientConfig::new(mock_server.uri(), "test-token").with_http_client(http_client);
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    client.execute(&query).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_requests_go_through_proxy() {
+    let proxy = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/odata/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "via-proxy"}]
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let config = ClientConfig::new("http://reso.invalid/odata", "test-token")
+        .with_http(HttpClientConfig::new().with_proxy(proxy.uri()));
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    let response = client.execute(&query).await.unwrap();
+    assert_eq!(response["value"][0]["ListingKey"], "via-proxy");
+}
+
+#[tokio::test]
+async fn test_gzip_responses_are_decompressed() {
+    // {"value":[{"ListingKey":"gz"}]}
+    const GZIPPED: &[u8] = &[
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xab, 0x56, 0x2a, 0x4b, 0xcc,
+        0x29, 0x4d, 0x55, 0xb2, 0x8a, 0xae, 0x56, 0xf2, 0xc9, 0x2c, 0x2e, 0xc9, 0xcc, 0x4b, 0xf7,
+        0x4e, 0xad, 0x54, 0xb2, 0x52, 0x4a, 0xaf, 0x52, 0xaa, 0x8d, 0xad, 0x05, 0x00, 0x84, 0x61,
+        0x10, 0x02, 0x1f, 0x00, 0x00, 0x00,
+    ];
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Encoding", "gzip")
+                .insert_header("Content-Type", "application/json")
+                .set_body_bytes(GZIPPED),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let config = ClientConfig::new(mock_server.uri(), "test-token");
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    let response = client.execute(&query).await.unwrap();
+    assert_eq!(response["value"][0]["ListingKey"], "gz");
+
+    let received = &mock_server.received_requests().await.unwrap()[0];
+    let accept_encoding = received.headers["Accept-Encoding"].to_str().unwrap();
+    assert!(accept_encoding.contains("gzip") && accept_encoding.contains("br"));
+}
+
+#[tokio::test]
+async fn test_request_options_set_headers_and_expose_response() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(header("Accept", "application/json;odata.metadata=none"))
+        .and(header("Accept-Encoding", "identity"))
+        .and(header("X-Request-Source", "tests"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Preference-Applied", "odata.maxpagesize=500")
+                .set_body_json(serde_json::json!({"value": [{"ListingKey": "1"}]})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(header("Prefer", "odata.maxpagesize=500"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", "https://api.example.com/next")
+                .set_body_json(serde_json::json!({"value": [{"ListingKey": "1"}]})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let options = RequestOptions::new()
+        .with_max_page_size(500)
+        .with_include_annotations("*")
+        .with_metadata(MetadataLevel::None)
+        .with_accept_encoding("identity")
+        .with_header("X-Request-Source", "tests");
+    let response = client.execute_response(&query, &options).await.unwrap();
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["value"][0]["ListingKey"], "1");
+    assert_eq!(response.preference_applied(), vec!["odata.maxpagesize=500"]);
+    assert_eq!(response.applied_max_page_size(), Some(500));
+
+    let received = &mock_server.received_requests().await.unwrap()[0];
+    assert_eq!(
+        received.headers["Prefer"],
+        "odata.maxpagesize=500, odata.include-annotations=\"*\""
+    );
+
+    // Without the headers the mock does not match
+    assert!(client.execute(&query).await.is_err());
+
+    let query = ReplicationQueryBuilder::new("Property").build().unwrap();
+    let options = RequestOptions::new().with_max_page_size(500);
+    let response = client
+        .execute_replication_response(&query, &options)
+        .await
+        .unwrap();
+
+    assert_eq!(response.body.record_count, 1);
+    assert_eq!(
+        response.header("next"),
+        Some("https://api.example.com/next")
+    );
+    assert_eq!(response.applied_max_page_size(), None);
+}
@@ -0,0 +1,287 @@
+// Integration tests for file export sinks
+#![cfg(feature = "client")]
+
+use reso_client::{
+    ClientConfig, CsvFileSink, JsonFileSink, MemorySink, QueryBuilder, ReplicationQueryBuilder,
+    ResoClient,
+};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn read_lines(path: &std::path::Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_replicate_into_ndjson_with_rotation() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("$top", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{"ListingKey": "1"}, {"ListingKey": "2"}]
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut sink = JsonFileSink::ndjson(dir.path(), "property").rotate_after_records(2);
+    client.replicate_into(&query, &mut sink).await.unwrap();
+    let manifest = sink.finish().unwrap();
+
+    assert!(manifest.complete);
+    assert_eq!(manifest.total_records, 3);
+    assert_eq!(manifest.parts.len(), 2);
+    assert_eq!(manifest.parts[0].file, "property-00000.ndjson");
+    assert_eq!(manifest.parts[1].records, 1);
+    assert!(manifest.parts.iter().all(|p| p.sha256.is_some()));
+
+    let records = read_lines(&dir.path().join("property-00000.ndjson"));
+    assert_eq!(
+        records,
+        vec![
+            serde_json::json!({"ListingKey": "1"}),
+            serde_json::json!({"ListingKey": "2"})
+        ]
+    );
+
+    // The manifest on disk matches the one returned
+    let on_disk: reso_client::ExportManifest =
+        serde_json::from_slice(&std::fs::read(sink.manifest_path()).unwrap()).unwrap();
+    assert_eq!(on_disk, manifest);
+}
+
+#[tokio::test]
+async fn test_execute_into_follows_odata_next_link() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property?$skip=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$top", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "1"}, {"ListingKey": "2"}],
+            "@odata.nextLink": next
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = QueryBuilder::new("Property").top(2).build().unwrap();
+
+    let mut sink = MemorySink::new();
+    let count = client.execute_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(count, 3);
+    assert_eq!(sink.records().len(), 3);
+    assert!(sink.checkpoint().unwrap().is_complete());
+}
+
+#[tokio::test]
+async fn test_execute_into_resumes_unfinished_export() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property?$skip=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$top", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "1"}, {"ListingKey": "2"}],
+            "@odata.nextLink": next
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = QueryBuilder::new("Property").top(2).build().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+    assert!(client.execute_into(&query, &mut sink).await.is_err());
+    drop(sink);
+
+    // A new sink on the same directory picks up from the manifest
+    let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+    let count = client.execute_into(&query, &mut sink).await.unwrap();
+    let manifest = sink.finish().unwrap();
+
+    assert_eq!(count, 3);
+    assert_eq!(manifest.total_records, 3);
+    assert_eq!(
+        read_lines(&dir.path().join("property-00000.ndjson")).len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_fresh_run_does_not_append_to_export_of_other_query() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property?$skip=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$filter", "City eq 'Austin'"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "A1"}, {"ListingKey": "A2"}],
+            "@odata.nextLink": next
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$filter", "City eq 'Waco'"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "B1"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let austin = QueryBuilder::new("Property")
+        .filter("City eq 'Austin'")
+        .build()
+        .unwrap();
+    let waco = QueryBuilder::new("Property")
+        .filter("City eq 'Waco'")
+        .build()
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    // Query A is interrupted after its first page
+    let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+    assert!(client.execute_into(&austin, &mut sink).await.is_err());
+    drop(sink);
+
+    // Query B starts afresh in the same directory
+    let mut sink = JsonFileSink::ndjson(dir.path(), "property");
+    let count = client.execute_into(&waco, &mut sink).await.unwrap();
+    let manifest = sink.finish().unwrap();
+
+    assert_eq!(count, 1);
+    assert!(manifest.complete);
+    assert_eq!(manifest.total_records, 1);
+    assert_eq!(manifest.parts.len(), 1);
+    assert_eq!(
+        read_lines(&dir.path().join("property-00000.ndjson")),
+        vec![serde_json::json!({"ListingKey": "B1"})]
+    );
+}
+
+#[tokio::test]
+async fn test_csv_columns_from_parsed_metadata() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/$metadata"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String" Nullable="false"/>
+        <Property Name="City" Type="Edm.String"/>
+        <Property Name="Appliances" Type="Collection(Edm.String)"/>
+      </EntityType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#,
+        ))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "1", "City": "Austin", "Appliances": ["Dishwasher", "Range"]},
+                {"ListingKey": "2", "City": null, "Appliances": []}
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let metadata = client.fetch_parsed_metadata().await.unwrap();
+    let entity_type = metadata.entity_type_for_resource("Property").unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut sink = CsvFileSink::for_entity_type(dir.path(), "property", entity_type)
+        .collection_delimiter("|")
+        .null_value("NULL");
+    let query = QueryBuilder::new("Property").build().unwrap();
+    client.execute_into(&query, &mut sink).await.unwrap();
+    let manifest = sink.finish().unwrap();
+
+    assert_eq!(manifest.total_records, 2);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("property-00000.csv")).unwrap(),
+        "ListingKey,City,Appliances\n1,Austin,Dishwasher|Range\n2,NULL,\n"
+    );
+}
@@ -0,0 +1,163 @@
+// Integration tests for record-and-replay fixtures
+#![cfg(feature = "client")]
+
+use reso_client::{
+    ClientConfig, QueryBuilder, ReplicationQueryBuilder, RequestOptions, ResoClient, ResoError,
+};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_record_then_replay_offline() {
+    let mock_server = MockServer::start().await;
+    let base_url = mock_server.uri();
+    let next = format!("{}/Property/replication?page=2", base_url);
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "1", "ListPrice": 100000}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("7"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({"value": [{"ListingKey": "2"}]})),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let query = QueryBuilder::new("Property").top(1).build().unwrap();
+    let count = QueryBuilder::new("Property").count().build().unwrap();
+    let replication = ReplicationQueryBuilder::new("Property").build().unwrap();
+
+    let recorder = ResoClient::with_config(
+        ClientConfig::new(&base_url, "secret-token").record_fixtures(dir.path()),
+    )
+    .unwrap();
+    let recorded = recorder.execute(&query).await.unwrap();
+    recorder.execute_count(&count).await.unwrap();
+    let first_page = recorder.execute_replication(&replication).await.unwrap();
+    recorder
+        .execute_next_link(first_page.next_link.as_deref().unwrap())
+        .await
+        .unwrap();
+
+    // No token in any fixture
+    for entry in std::fs::read_dir(dir.path()).unwrap() {
+        let contents = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(!contents.contains("secret-token"));
+    }
+
+    // The server is gone; everything comes from the fixtures
+    drop(mock_server);
+    let replayer =
+        ResoClient::with_config(ClientConfig::new(&base_url, "unused").replay_fixtures(dir.path()))
+            .unwrap();
+
+    assert_eq!(replayer.execute(&query).await.unwrap(), recorded);
+    assert_eq!(replayer.execute_count(&count).await.unwrap(), 7);
+    let replayed = replayer.execute_replication(&replication).await.unwrap();
+    assert_eq!(replayed.next_link, Some(next.clone()));
+    let second_page = replayer.execute_next_link(&next).await.unwrap();
+    assert_eq!(second_page.records[0]["ListingKey"], "3");
+}
+
+#[tokio::test]
+async fn test_replay_unrecorded_request_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = ResoClient::with_config(
+        ClientConfig::new("https://api.example.com/odata", "unused").replay_fixtures(dir.path()),
+    )
+    .unwrap();
+
+    let query = QueryBuilder::new("Member").build().unwrap();
+    let err = client.execute(&query).await.unwrap_err();
+
+    assert!(matches!(err, ResoError::FixtureNotFound { .. }));
+    assert!(err
+        .to_string()
+        .contains("https://api.example.com/odata/Member"));
+}
+
+#[tokio::test]
+async fn test_recorded_error_responses_replay_as_errors() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": "NotFound", "message": "No such resource"}
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let recorder = ResoClient::with_config(
+        ClientConfig::new(mock_server.uri(), "token").record_fixtures(dir.path()),
+    )
+    .unwrap();
+    assert!(recorder.execute(&query).await.is_err());
+
+    let replayer = ResoClient::with_config(
+        ClientConfig::new(mock_server.uri(), "token").replay_fixtures(dir.path()),
+    )
+    .unwrap();
+    assert!(matches!(
+        replayer.execute(&query).await,
+        Err(ResoError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_fixtures_are_keyed_by_request_headers() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"{"value": [{"ListingKey": "1", "ListPrice": 100000.00}]}"#),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let paged = RequestOptions::new().with_max_page_size(10);
+    let recorder = ResoClient::with_config(
+        ClientConfig::new(mock_server.uri(), "token").record_fixtures(dir.path()),
+    )
+    .unwrap();
+    recorder.execute_with(&query, &paged).await.unwrap();
+
+    let replayer = ResoClient::with_config(
+        ClientConfig::new(mock_server.uri(), "token").replay_fixtures(dir.path()),
+    )
+    .unwrap();
+    let replayed = replayer.execute_with(&query, &paged).await.unwrap();
+    assert_eq!(replayed["value"][0]["ListPrice"], 100000.0);
+    assert!(matches!(
+        replayer.execute(&query).await,
+        Err(ResoError::FixtureNotFound { .. })
+    ));
+}
@@ -0,0 +1,100 @@
+// Integration tests for the Lookup resource cache
+#![cfg(feature = "client")]
+
+use reso_client::{ClientConfig, QueryBuilder, ResoClient};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String"/>
+        <Property Name="StandardStatus" Type="Edm.String">
+          <Annotation Term="RESO.OData.Metadata.LookupName" String="org.reso.metadata.enums.StandardStatus"/>
+        </Property>
+      </EntityType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+#[tokio::test]
+async fn test_lookups_load_once_across_pages() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/$metadata"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(METADATA))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Lookup"))
+        .and(query_param("$skiptoken", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "LookupKey": "3",
+                "LookupName": "org.reso.metadata.enums.StandardStatus",
+                "LookupValue": "Closed",
+                "LegacyODataValue": "Closed"
+            }]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Lookup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {
+                    "LookupKey": "1",
+                    "LookupName": "org.reso.metadata.enums.StandardStatus",
+                    "LookupValue": "Active Under Contract",
+                    "StandardLookupValue": "Active Under Contract",
+                    "LegacyODataValue": "ActiveUnderContract"
+                },
+                {
+                    "LookupKey": "2",
+                    "LookupName": "org.reso.metadata.enums.StandardStatus",
+                    "LookupValue": "Coming Soon",
+                    "LegacyODataValue": "ComingSoon"
+                }
+            ],
+            "@odata.nextLink": format!("{}/Lookup?$skiptoken=2", mock_server.uri())
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$filter", "StandardStatus eq 'Closed'"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "L1", "StandardStatus": "Closed"}]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let lookups = client.lookups().await.unwrap();
+    assert_eq!(lookups.len(), 3);
+
+    // Loaded once; uncached clients share the same cache
+    let again = client.uncached().lookups().await.unwrap();
+    assert!(std::sync::Arc::ptr_eq(&lookups, &again));
+
+    assert_eq!(
+        lookups.resolve("StandardStatus", "ActiveUnderContract"),
+        Some("Active Under Contract")
+    );
+
+    let query = QueryBuilder::new("Property")
+        .filter(lookups.filter_eq("StandardStatus", "closed").unwrap())
+        .build()
+        .unwrap();
+    let results = client.execute(&query).await.unwrap();
+    assert_eq!(results["value"][0]["ListingKey"], "L1");
+}
@@ -0,0 +1,244 @@
+// Integration tests for media queries and downloads
+#![cfg(feature = "client")]
+
+use reso_client::{ClientConfig, MediaDownloader, MediaItem, MediaStatus, ResoClient, ResoError};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn item(server: &MockServer, key: &str, order: i64, timestamp: &str) -> MediaItem {
+    MediaItem::from_record(&serde_json::json!({
+        "MediaKey": key,
+        "ResourceRecordKey": "L1",
+        "Order": order,
+        "MediaURL": format!("{}/photos/{}.jpg", server.uri(), key),
+        "MediaModificationTimestamp": timestamp
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_fetch_media_orders_items() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Media"))
+        .and(query_param(
+            "$filter",
+            "ResourceName eq 'Property' and ResourceRecordKey eq 'L1'",
+        ))
+        .and(query_param("$orderby", "Order asc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"MediaKey": "m2", "Order": 2, "MediaURL": "https://cdn.example.com/2.jpg"},
+                {"MediaKey": "m1", "Order": 1, "MediaURL": "https://cdn.example.com/1.jpg"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let items = client.fetch_media("L1").await.unwrap();
+
+    let keys: Vec<&str> = items.iter().map(|i| i.media_key.as_str()).collect();
+    assert_eq!(keys, vec!["m1", "m2"]);
+}
+
+#[tokio::test]
+async fn test_fetch_media_follows_next_link() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Media?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Media"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"MediaKey": "m3", "Order": 3, "MediaURL": "https://cdn.example.com/3.jpg"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Media"))
+        .and(query_param("$orderby", "Order asc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"MediaKey": "m1", "Order": 1, "MediaURL": "https://cdn.example.com/1.jpg"},
+                {"MediaKey": "m2", "Order": 2, "MediaURL": "https://cdn.example.com/2.jpg"}
+            ],
+            "@odata.nextLink": next
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let items = client.fetch_media("L1").await.unwrap();
+
+    let keys: Vec<&str> = items.iter().map(|i| i.media_key.as_str()).collect();
+    assert_eq!(keys, vec!["m1", "m2", "m3"]);
+}
+
+#[tokio::test]
+async fn test_download_into_store_skips_unchanged() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/photos/m1.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/jpeg")
+                .set_body_bytes(vec![0xff, 0xd8, 0xff]),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/photos/m2.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/png")
+                .set_body_bytes(vec![0x89, 0x50]),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let downloader = MediaDownloader::new().store_in(dir.path());
+    let first = vec![
+        item(&mock_server, "m1", 1, "2025-01-01T00:00:00Z"),
+        item(&mock_server, "m2", 2, "2025-01-01T00:00:00Z"),
+    ];
+
+    let downloads = downloader.download(&first).await.unwrap();
+    let media = downloads[1].result.as_ref().unwrap();
+    assert_eq!(media.status, MediaStatus::Downloaded);
+    assert_eq!(media.size, 2);
+    assert_eq!(
+        media.path.as_deref(),
+        Some(dir.path().join("L1/m2.png").as_path())
+    );
+    assert_eq!(
+        std::fs::read(dir.path().join("L1/m1.jpg")).unwrap(),
+        vec![0xff, 0xd8, 0xff]
+    );
+    assert!(dir.path().join("media-manifest.json").exists());
+
+    // m1 changed since the last run; m2 did not
+    let second = vec![
+        item(&mock_server, "m1", 1, "2025-02-01T00:00:00Z"),
+        item(&mock_server, "m2", 2, "2025-01-01T00:00:00Z"),
+    ];
+    let downloads = downloader.download(&second).await.unwrap();
+    assert_eq!(
+        downloads[0].result.as_ref().unwrap().status,
+        MediaStatus::Downloaded
+    );
+    assert_eq!(
+        downloads[1].result.as_ref().unwrap().status,
+        MediaStatus::Unchanged
+    );
+}
+
+#[tokio::test]
+async fn test_download_limits_and_retries() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/photos/big.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/jpeg")
+                .set_body_bytes(vec![0u8; 64]),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/photos/page.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "text/html")
+                .set_body_string("<html/>"),
+        )
+        .mount(&mock_server)
+        .await;
+    // First attempt fails with 503, the retry succeeds
+    Mock::given(method("GET"))
+        .and(path("/photos/flaky.jpg"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/photos/flaky.jpg"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/jpeg")
+                .set_body_bytes(vec![1u8; 8]),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let items = vec![
+        item(&mock_server, "big", 1, "t"),
+        item(&mock_server, "page", 2, "t"),
+        item(&mock_server, "flaky", 3, "t"),
+        item(&mock_server, "missing", 4, "t"),
+    ];
+    let downloads = MediaDownloader::new()
+        .max_bytes(32)
+        .retry_delay(Duration::from_millis(10))
+        .download(&items)
+        .await
+        .unwrap();
+
+    assert!(matches!(downloads[0].result, Err(ResoError::Media(_))));
+    assert!(matches!(downloads[1].result, Err(ResoError::Media(_))));
+    let flaky = downloads[2].result.as_ref().unwrap();
+    assert_eq!(flaky.data.as_deref(), Some(&[1u8; 8][..]));
+    assert!(matches!(
+        downloads[3].result,
+        Err(ResoError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_media_downloader_uses_client_http_settings() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/photos/m1.jpg"))
+        .and(header("user-agent", "listing-sync/1.0"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/jpeg")
+                .set_body_bytes(vec![7u8; 4]),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let http_client = reqwest::Client::builder()
+        .user_agent("listing-sync/1.0")
+        .build()
+        .unwrap();
+    let client = ResoClient::with_config(
+        ClientConfig::new(mock_server.uri(), "token").with_http_client(http_client),
+    )
+    .unwrap();
+    let downloads = client
+        .media_downloader()
+        .retries(0)
+        .download(&[item(&mock_server, "m1", 1, "t")])
+        .await
+        .unwrap();
+
+    let media = downloads[0].result.as_ref().unwrap();
+    assert_eq!(media.data.as_deref(), Some(&[7u8; 4][..]));
+}
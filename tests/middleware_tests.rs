@@ -0,0 +1,123 @@
+// Integration tests for the request middleware chain
+#![cfg(feature = "client")]
+
+use reso_client::middleware::{Middleware, Next, Request, Response};
+use reso_client::{async_trait, ClientConfig, QueryBuilder, ResoClient, Result, RetryPolicy};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Adds a header and records the order middlewares ran in
+struct Tag {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Middleware for Tag {
+    async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response> {
+        let auth = request
+            .headers()
+            .get("Authorization")
+            .map(|v| v.to_str().unwrap().to_string());
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} {:?}", self.name, auth));
+        request
+            .headers_mut()
+            .append("X-Tag", self.name.parse().unwrap());
+        next.run(request).await
+    }
+}
+
+/// Answers the first `failures` requests with 503 without sending them
+struct FaultInjection {
+    failures: u32,
+    seen: AtomicU32,
+}
+
+#[async_trait]
+impl Middleware for FaultInjection {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response> {
+        if self.seen.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Ok(Response::from(
+                http::Response::builder()
+                    .status(503)
+                    .body("injected")
+                    .unwrap(),
+            ));
+        }
+        next.run(request).await
+    }
+}
+
+#[tokio::test]
+async fn test_custom_middlewares_run_in_order_after_auth() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(header("Authorization", "Bearer test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": []})))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let config = ClientConfig::new(mock_server.uri(), "test-token")
+        .with_middleware(Tag {
+            name: "first",
+            log: log.clone(),
+        })
+        .with_middleware(Tag {
+            name: "second",
+            log: log.clone(),
+        });
+    let client = ResoClient::with_config(config).unwrap();
+
+    client
+        .execute(&QueryBuilder::new("Property").build().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "first Some(\"Bearer test-token\")",
+            "second Some(\"Bearer test-token\")"
+        ]
+    );
+    let received = &mock_server.received_requests().await.unwrap()[0];
+    let tags: Vec<&str> = received
+        .headers
+        .get_all("X-Tag")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect();
+    assert_eq!(tags, ["first", "second"]);
+}
+
+#[tokio::test]
+async fn test_injected_faults_are_retried() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": []})))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let faults = FaultInjection {
+        failures: 2,
+        seen: AtomicU32::new(0),
+    };
+    let config = ClientConfig::new(mock_server.uri(), "test-token")
+        .with_retry(RetryPolicy::new(2).with_initial_backoff(Duration::from_millis(1)))
+        .with_middleware(faults);
+    let client = ResoClient::with_config(config).unwrap();
+
+    let query = QueryBuilder::new("Property").build().unwrap();
+    client.execute(&query).await.unwrap();
+}
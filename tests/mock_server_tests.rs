@@ -0,0 +1,161 @@
+// Integration tests for the in-process mock RESO server
+#![cfg(feature = "mock-server")]
+
+use reso_client::{
+    MemorySink, MockResoServer, QueryBuilder, ReplicationQueryBuilder, ResoClient, ResoError,
+};
+use serde_json::{json, Value as JsonValue};
+
+fn listings() -> Vec<JsonValue> {
+    vec![
+        json!({"ListingKey": "1", "City": "Austin", "ListPrice": 450000, "ModificationTimestamp": "2025-01-10T00:00:00Z"}),
+        json!({"ListingKey": "2", "City": "Dallas", "ListPrice": 325000, "ModificationTimestamp": "2025-02-10T00:00:00Z"}),
+        json!({"ListingKey": "3", "City": "Austin", "ListPrice": 615000, "ModificationTimestamp": "2025-03-10T00:00:00Z"}),
+        json!({"ListingKey": "4", "City": "Houston", "ListPrice": null, "ModificationTimestamp": "2025-04-10T00:00:00Z"}),
+        json!({"ListingKey": "5", "City": "Austin", "ListPrice": 299000, "ModificationTimestamp": "2025-05-10T00:00:00Z"}),
+    ]
+}
+
+async fn server() -> MockResoServer {
+    MockResoServer::builder()
+        .resource("Property", "ListingKey", listings())
+        .start()
+        .await
+}
+
+fn keys(results: &JsonValue) -> Vec<&str> {
+    results["value"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["ListingKey"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_query_options() {
+    let server = server().await;
+    let client = server.client().unwrap();
+
+    let query = QueryBuilder::new("Property")
+        .filter("City eq 'Austin' and ListPrice gt 300000")
+        .select(&["ListingKey", "ListPrice"])
+        .order_by("ListPrice", "desc")
+        .with_count()
+        .build()
+        .unwrap();
+    let results = client.execute(&query).await.unwrap();
+
+    assert_eq!(keys(&results), vec!["3", "1"]);
+    assert_eq!(results["@odata.count"], 2);
+    assert_eq!(
+        results["value"][0],
+        json!({"ListingKey": "3", "ListPrice": 615000})
+    );
+
+    let query = QueryBuilder::new("Property")
+        .order_by("ListingKey", "asc")
+        .skip(1)
+        .top(2)
+        .build()
+        .unwrap();
+    assert_eq!(keys(&client.execute(&query).await.unwrap()), vec!["2", "3"]);
+}
+
+#[tokio::test]
+async fn test_count_key_access_and_metadata() {
+    let server = server().await;
+    let client = server.client().unwrap();
+
+    let count = QueryBuilder::new("Property")
+        .filter("ModificationTimestamp ge 2025-03-01T00:00:00Z")
+        .count()
+        .build()
+        .unwrap();
+    assert_eq!(client.execute_count(&count).await.unwrap(), 3);
+
+    let by_key = QueryBuilder::by_key("Property", "2").build().unwrap();
+    let record = client.execute_by_key(&by_key).await.unwrap();
+    assert_eq!(record["City"], "Dallas");
+
+    let missing = QueryBuilder::by_key("Property", "99").build().unwrap();
+    assert!(matches!(
+        client.execute_by_key(&missing).await,
+        Err(ResoError::NotFound { .. })
+    ));
+
+    let metadata = client.fetch_parsed_metadata().await.unwrap();
+    let property = metadata.entity_type("Property").unwrap();
+    assert_eq!(property.key, vec!["ListingKey".to_string()]);
+    assert_eq!(
+        property.property("ListPrice").unwrap().type_name,
+        "Edm.Int64"
+    );
+}
+
+#[tokio::test]
+async fn test_replication_follows_next_headers() {
+    let server = server().await;
+    let client = server.client().unwrap();
+
+    let query = ReplicationQueryBuilder::new("Property")
+        .filter("City eq 'Austin'")
+        .top(2)
+        .build()
+        .unwrap();
+    let mut sink = MemorySink::new();
+    let progress = client.replicate_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(progress.records_fetched, 3);
+    assert_eq!(progress.pages_fetched, 2);
+    let fetched: Vec<&str> = sink
+        .records()
+        .iter()
+        .map(|r| r["ListingKey"].as_str().unwrap())
+        .collect();
+    assert_eq!(fetched, vec!["1", "3", "5"]);
+    assert_eq!(
+        server
+            .received_requests()
+            .await
+            .iter()
+            .filter(|r| r.starts_with("/Property/replication"))
+            .count(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_paging_auth_and_errors() {
+    let server = MockResoServer::builder()
+        .resource("Property", "ListingKey", listings())
+        .max_page_size(2)
+        .require_token("secret")
+        .dataset_id("test_ds")
+        .start()
+        .await;
+    let client = server.client().unwrap();
+
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let mut sink = MemorySink::new();
+    assert_eq!(client.execute_into(&query, &mut sink).await.unwrap(), 5);
+
+    let wrong_token =
+        ResoClient::with_config(reso_client::ClientConfig::new(server.uri(), "nope")).unwrap();
+    assert!(matches!(
+        wrong_token.execute(&query).await,
+        Err(ResoError::Unauthorized { .. })
+    ));
+
+    let bad_filter = QueryBuilder::new("Property")
+        .filter("City eq 'Austin")
+        .build()
+        .unwrap();
+    assert!(client.execute(&bad_filter).await.is_err());
+
+    let unknown = QueryBuilder::new("Member").build().unwrap();
+    assert!(matches!(
+        client.execute(&unknown).await,
+        Err(ResoError::NotFound { .. })
+    ));
+}
@@ -0,0 +1,127 @@
+// Integration tests for the Parquet sink
+#![cfg(all(feature = "arrow", feature = "client"))]
+
+use arrow_array::{Array, Decimal128Array, ListArray, StringArray, TimestampMicrosecondArray};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use reso_client::{ClientConfig, ParquetSink, ReplicationQueryBuilder, ResoClient};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String" Nullable="false"/>
+        <Property Name="ListPrice" Type="Edm.Decimal" Precision="14" Scale="2"/>
+        <Property Name="ModificationTimestamp" Type="Edm.DateTimeOffset"/>
+        <Property Name="Appliances" Type="Collection(Edm.String)"/>
+      </EntityType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+#[tokio::test]
+async fn test_replicate_into_parquet_from_metadata() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/$metadata"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(METADATA))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "2", "ListPrice": null, "ModificationTimestamp": null, "Appliances": []}
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("$top", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{
+                        "ListingKey": "1",
+                        "ListPrice": 425000.5,
+                        "ModificationTimestamp": "2025-01-15T10:30:00Z",
+                        "Appliances": ["Dishwasher", "Range"]
+                    }]
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let metadata = client.fetch_parsed_metadata().await.unwrap();
+    let entity_type = metadata.entity_type_for_resource("Property").unwrap();
+
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(1)
+        .build()
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let mut sink = ParquetSink::for_entity_type(dir.path(), "property", entity_type);
+    client.replicate_into(&query, &mut sink).await.unwrap();
+    let manifest = sink.finish().unwrap();
+
+    assert!(manifest.complete);
+    assert_eq!(manifest.total_records, 2);
+    assert_eq!(manifest.parts[0].file, "property-00000.parquet");
+
+    let file = std::fs::File::open(dir.path().join("property-00000.parquet")).unwrap();
+    let batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|batch| batch.unwrap())
+        .collect();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+
+    let keys = batch
+        .column_by_name("ListingKey")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(keys.value(0), "1");
+    assert_eq!(keys.value(1), "2");
+
+    let prices = batch
+        .column_by_name("ListPrice")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Decimal128Array>()
+        .unwrap();
+    assert_eq!(prices.value(0), 42500050);
+    assert!(prices.is_null(1));
+
+    let timestamps = batch
+        .column_by_name("ModificationTimestamp")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<TimestampMicrosecondArray>()
+        .unwrap();
+    assert_eq!(timestamps.value(0), 1_736_937_000_000_000);
+
+    let appliances = batch
+        .column_by_name("Appliances")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    assert_eq!(appliances.value_length(0), 2);
+    assert_eq!(appliances.value_length(1), 0);
+}
@@ -0,0 +1,195 @@
+// Integration tests for the PostgreSQL sink
+//
+// These run against a local database named by `RESO_TEST_POSTGRES_URL`, e.g.
+// `postgres://postgres@localhost/reso_test`, and are ignored by default. Run
+// them with `cargo test --all-features --test postgres_sink_tests -- --ignored`.
+// Tables are dropped and recreated, so do not point this at real data.
+#![cfg(all(feature = "postgres", feature = "client"))]
+
+use reso_client::sink::postgres;
+use reso_client::{
+    ClientConfig, EntityType, PostgresSink, RecordSink, ReplicationCheckpoint, ResoClient,
+};
+use serial_test::serial;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn database() -> tokio_postgres::Client {
+    let url = std::env::var("RESO_TEST_POSTGRES_URL").expect("RESO_TEST_POSTGRES_URL must be set");
+    let client = postgres::connect(&url).await.unwrap();
+    client
+        .batch_execute("DROP TABLE IF EXISTS \"PgProperty\", reso_records, _reso_checkpoints")
+        .await
+        .unwrap();
+    client
+}
+
+fn entity_type() -> EntityType {
+    reso_client::Metadata::parse(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+  <edmx:DataServices>
+    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="org.reso.metadata">
+      <EntityType Name="PgProperty">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String" Nullable="false"/>
+        <Property Name="City" Type="Edm.String"/>
+        <Property Name="ListPrice" Type="Edm.Decimal" Precision="14" Scale="2"/>
+        <Property Name="ModificationTimestamp" Type="Edm.DateTimeOffset"/>
+        <Property Name="Appliances" Type="Collection(Edm.String)"/>
+      </EntityType>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#,
+    )
+    .unwrap()
+    .entity_type("PgProperty")
+    .unwrap()
+    .clone()
+}
+
+fn checkpoint(next_link: Option<&str>, committed: u64) -> ReplicationCheckpoint {
+    ReplicationCheckpoint {
+        resource: "PgProperty".to_string(),
+        query: Some("PgProperty/replication".to_string()),
+        next_link: next_link.map(|s| s.to_string()),
+        records_committed: committed,
+    }
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires RESO_TEST_POSTGRES_URL"]
+async fn test_typed_full_then_incremental_sync() {
+    let db = database().await;
+    let mock_server = MockServer::start().await;
+
+    // Incremental run: only the changed listing comes back
+    Mock::given(method("GET"))
+        .and(path("/PgProperty/replication"))
+        .and(query_param(
+            "$filter",
+            "ModificationTimestamp ge 2025-02-01T00:00:00.000000Z",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "2", "ListPrice": 250000, "ModificationTimestamp": "2025-03-01T00:00:00Z"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Initial full run
+    Mock::given(method("GET"))
+        .and(path("/PgProperty/replication"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "1", "City": "Austin", "ListPrice": 100000.5, "ModificationTimestamp": "2025-01-01T00:00:00Z", "Appliances": ["Range"]},
+                {"ListingKey": "2", "City": "Waco", "ListPrice": 200000, "ModificationTimestamp": "2025-02-01T00:00:00Z", "Appliances": []}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let mut sink = PostgresSink::typed(db, "PgProperty", &entity_type())
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let query = sink
+            .incremental_query("ModificationTimestamp", None)
+            .await
+            .unwrap()
+            .build()
+            .unwrap();
+        client.replicate_into(&query, &mut sink).await.unwrap();
+    }
+
+    assert_eq!(sink.row_count().await.unwrap(), 2);
+    let row = sink
+        .client()
+        .query_one(
+            "SELECT \"ListPrice\"::text, \"City\", \"Appliances\"::text FROM \"PgProperty\" WHERE \"ListingKey\" = '2'",
+            &[],
+        )
+        .await
+        .unwrap();
+    let (price, city, appliances): (String, String, String) = (row.get(0), row.get(1), row.get(2));
+    assert_eq!(price, "250000.00");
+    // Columns missing from the incremental page keep their values
+    assert_eq!(city, "Waco");
+    assert_eq!(appliances, "[]");
+    assert_eq!(
+        sink.high_water_mark("ModificationTimestamp").await.unwrap(),
+        Some("2025-03-01T00:00:00.000000Z".to_string())
+    );
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires RESO_TEST_POSTGRES_URL"]
+async fn test_raw_mode_upserts_and_rolls_back_uncommitted() {
+    let db = database().await;
+    let mut sink = PostgresSink::raw(db, "PgProperty", &["ListingKey"])
+        .await
+        .unwrap();
+
+    sink.write_batch(&[
+        serde_json::json!({"ListingKey": "1", "ListPrice": 100}),
+        serde_json::json!({"ListingKey": "1", "ListPrice": 150}),
+    ])
+    .await
+    .unwrap();
+    sink.commit(&checkpoint(Some("https://api.example.com/next"), 2))
+        .await
+        .unwrap();
+    sink.write_batch(&[serde_json::json!({"ListingKey": "2", "ListPrice": 200})])
+        .await
+        .unwrap();
+
+    let resumed = sink.last_checkpoint().await.unwrap();
+
+    assert_eq!(
+        resumed,
+        Some(checkpoint(Some("https://api.example.com/next"), 2))
+    );
+    assert_eq!(sink.row_count().await.unwrap(), 1);
+    let record: String = sink
+        .client()
+        .query_one(
+            "SELECT record::text FROM reso_records WHERE resource = 'PgProperty' AND record_key = '1'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(record, r#"{"ListPrice": 150, "ListingKey": "1"}"#);
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "requires RESO_TEST_POSTGRES_URL"]
+async fn test_raw_mode_high_water_mark_compares_instants() {
+    let db = database().await;
+    let mut sink = PostgresSink::raw(db, "PgProperty", &["ListingKey"])
+        .await
+        .unwrap();
+
+    sink.write_batch(&[
+        serde_json::json!({"ListingKey": "1", "ModificationTimestamp": "2025-02-01T00:00:00Z"}),
+        serde_json::json!({"ListingKey": "2", "ModificationTimestamp": "2025-02-01T00:00:00.5Z"}),
+        serde_json::json!({"ListingKey": "3", "ModificationTimestamp": "2025-02-01T01:00:00.25+02:00"}),
+    ])
+    .await
+    .unwrap();
+    sink.commit(&checkpoint(None, 3)).await.unwrap();
+
+    assert_eq!(
+        sink.high_water_mark("ModificationTimestamp").await.unwrap(),
+        Some("2025-02-01T00:00:00.500000Z".to_string())
+    );
+}
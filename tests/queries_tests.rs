@@ -50,6 +50,17 @@ fn test_query_with_orderby() {
     assert!(url.contains("$orderby=ListPrice%20desc"));
 }
 
+#[test]
+fn test_query_with_orderby_expression() {
+    let query = QueryBuilder::new("Property")
+        .order_by_expression("ListPrice desc, City asc")
+        .build()
+        .unwrap();
+
+    let url = query.to_odata_string();
+    assert!(url.contains("$orderby=ListPrice%20desc%2C%20City%20asc"));
+}
+
 #[test]
 fn test_query_with_skip() {
     let query = QueryBuilder::new("Property").skip(20).build().unwrap();
@@ -0,0 +1,120 @@
+// Integration tests for FeedRegistry fan-out queries
+#![cfg(feature = "client")]
+
+use reso_client::{ClientConfig, FeedRegistry, Profiles, QueryBuilder, ResoClient, ResoError};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn feed(records: serde_json::Value) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": records
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn client(server: &MockServer) -> ResoClient {
+    ResoClient::with_config(ClientConfig::new(server.uri(), "token")).unwrap()
+}
+
+#[tokio::test]
+async fn test_execute_tags_records_and_collects_errors() {
+    let north = feed(serde_json::json!([{"ListingKey": "N1"}, {"ListingKey": "N2"}])).await;
+    let south = feed(serde_json::json!([{"ListingKey": "S1"}])).await;
+    let broken = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&broken)
+        .await;
+
+    let registry = FeedRegistry::new()
+        .with_feed("south", client(&south))
+        .with_feed("north", client(&north))
+        .with_feed("broken", client(&broken));
+    assert_eq!(
+        registry.names().collect::<Vec<_>>(),
+        ["broken", "north", "south"]
+    );
+
+    let query = QueryBuilder::new("Property").top(10).build().unwrap();
+    let result = registry.execute(&query).await;
+
+    let tagged: Vec<(&str, &str)> = result
+        .records
+        .iter()
+        .map(|r| (r.feed.as_str(), r.record["ListingKey"].as_str().unwrap()))
+        .collect();
+    assert_eq!(tagged, [("north", "N1"), ("north", "N2"), ("south", "S1")]);
+    assert_eq!(result.records_from("south").count(), 1);
+
+    assert!(!result.is_complete());
+    assert_eq!(result.errors.len(), 1);
+    assert!(matches!(
+        result.errors["broken"],
+        ResoError::Unauthorized { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_execute_all_follows_next_links() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "2"}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "1"}],
+            "@odata.nextLink": format!("{}/Property?$skip=1", server.uri())
+        })))
+        .mount(&server)
+        .await;
+
+    let registry = FeedRegistry::new().with_feed("only", client(&server));
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    assert_eq!(registry.execute(&query).await.records.len(), 1);
+
+    let result = registry.execute_all(&query).await;
+    assert!(result.is_complete());
+    let keys: Vec<&str> = result
+        .records_from("only")
+        .map(|r| r["ListingKey"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn test_registry_from_profiles() {
+    let server = feed(serde_json::json!([{"ListingKey": "1"}])).await;
+    std::env::set_var("RESO_REGISTRY_TEST_TOKEN", "token");
+    let profiles = Profiles::from_toml(&format!(
+        r#"
+        [feeds.a]
+        base_url = "{0}"
+        auth = {{ method = "bearer", token = {{ env = "RESO_REGISTRY_TEST_TOKEN" }} }}
+
+        [feeds.b]
+        base_url = "{0}"
+        auth = {{ method = "bearer", token = {{ env = "RESO_REGISTRY_TEST_TOKEN" }} }}
+        "#,
+        server.uri()
+    ))
+    .unwrap();
+
+    let registry = FeedRegistry::from_profiles(&profiles).unwrap();
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.get("a").unwrap().base_url(), server.uri());
+
+    let query = QueryBuilder::new("Property").build().unwrap();
+    assert_eq!(registry.execute(&query).await.records.len(), 2);
+}
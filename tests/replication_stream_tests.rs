@@ -0,0 +1,638 @@
+// Integration tests for replication streaming, progress reporting and sinks
+#![cfg(feature = "client")]
+
+use reso_client::{
+    async_trait, CancellationToken, ClientConfig, MemorySink, ProgressObserver, RecordSink,
+    ReplicationCheckpoint, ReplicationProgress, ReplicationQueryBuilder, RequestOptions,
+    ResoClient, ResoError,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Observer that records every callback for later inspection
+#[derive(Clone, Default)]
+struct RecordingObserver {
+    events: Arc<Mutex<Vec<(&'static str, ReplicationProgress)>>>,
+}
+
+impl ProgressObserver for RecordingObserver {
+    fn on_start(&self, progress: &ReplicationProgress) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(("start", progress.clone()));
+    }
+
+    fn on_page(&self, progress: &ReplicationProgress) {
+        self.events.lock().unwrap().push(("page", progress.clone()));
+    }
+
+    fn on_complete(&self, progress: &ReplicationProgress) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(("complete", progress.clone()));
+    }
+}
+
+async fn mount_two_pages(mock_server: &MockServer) {
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3"}]
+        })))
+        .mount(mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("$top", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{"ListingKey": "1"}, {"ListingKey": "2"}]
+                })),
+        )
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_replicate_follows_next_links() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut stream = client.replicate(&query);
+    let mut keys = Vec::new();
+    while let Some(page) = stream.next_page().await.unwrap() {
+        keys.extend(page.records.iter().map(|r| r["ListingKey"].clone()));
+    }
+
+    assert_eq!(keys, vec!["1", "2", "3"]);
+    assert_eq!(stream.progress().pages_fetched, 2);
+    assert_eq!(stream.progress().records_fetched, 3);
+    assert!(stream.progress().bytes_downloaded > 0);
+    // Without an observer no count request is made
+    assert_eq!(stream.progress().total_expected, None);
+
+    // Exhausted streams stay exhausted
+    assert!(stream.next_page().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_replicate_streams_records_across_pages() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut stream = client.replicate(&query);
+    let first = stream.next_record().await.unwrap().unwrap();
+    assert_eq!(first["ListingKey"], "1");
+    // Progress moves when a page has been read to its end
+    assert_eq!(stream.progress().pages_fetched, 0);
+
+    // A page requested mid-way holds the rest of the open page
+    let rest = stream.next_page().await.unwrap().unwrap();
+    assert_eq!(rest.records, vec![serde_json::json!({"ListingKey": "2"})]);
+    assert!(rest.has_more());
+
+    #[derive(serde::Deserialize)]
+    struct Listing {
+        #[serde(rename = "ListingKey")]
+        key: String,
+    }
+    let last: Listing = stream.next_record_as().await.unwrap().unwrap();
+    assert_eq!(last.key, "3");
+    assert!(stream.next_record().await.unwrap().is_none());
+
+    assert_eq!(stream.progress().pages_fetched, 2);
+    assert_eq!(stream.progress().records_fetched, 3);
+    assert!(stream.progress().bytes_downloaded > 0);
+}
+
+#[tokio::test]
+async fn test_replicate_reports_progress_with_total() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .and(query_param("$filter", "City eq 'Austin'"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("3"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .filter("City eq 'Austin'")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let observer = RecordingObserver::default();
+    let mut stream = client.replicate(&query).with_progress(observer.clone());
+    while stream.next_page().await.unwrap().is_some() {}
+
+    let events = observer.events.lock().unwrap();
+    let kinds: Vec<_> = events.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(kinds, vec!["start", "page", "page", "complete"]);
+
+    let (_, first_page) = &events[1];
+    assert_eq!(first_page.total_expected, Some(3));
+    assert_eq!(first_page.records_fetched, 2);
+    assert_eq!(first_page.pages_fetched, 1);
+
+    let (_, complete) = &events[3];
+    assert_eq!(complete.records_fetched, 3);
+    assert_eq!(complete.percent_complete(), Some(100.0));
+}
+
+#[tokio::test]
+async fn test_replicate_progress_count_uses_stream_options() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .and(header("X-Feed-Key", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("3"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let observer = RecordingObserver::default();
+    let mut stream = client
+        .replicate(&query)
+        .with_progress(observer.clone())
+        .with_options(RequestOptions::new().with_header("X-Feed-Key", "secret"));
+    while stream.next_page().await.unwrap().is_some() {}
+
+    assert_eq!(stream.progress().total_expected, Some(3));
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|r| r.headers["X-Feed-Key"] == "secret"));
+}
+
+#[tokio::test]
+async fn test_replicate_count_failure_is_not_fatal() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .respond_with(ResponseTemplate::new(400))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut stream = client.replicate(&query).with_progress(tx);
+    while stream.next_page().await.unwrap().is_some() {}
+    drop(stream);
+
+    let updates: Vec<ReplicationProgress> = rx.iter().collect();
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[1].total_expected, None);
+    assert_eq!(updates[1].records_fetched, 3);
+}
+
+#[tokio::test]
+async fn test_replicate_propagates_page_errors() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("boom"))
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property").build().unwrap();
+
+    let mut stream = client.replicate(&query);
+    assert!(stream.next_page().await.is_err());
+}
+
+/// Sink that records every call so the driver's sequencing can be checked
+#[derive(Default)]
+struct CallLogSink {
+    calls: Vec<String>,
+    resume_from: Option<ReplicationCheckpoint>,
+    batch_size: Option<usize>,
+}
+
+#[async_trait]
+impl RecordSink for CallLogSink {
+    async fn write_batch(&mut self, records: &[serde_json::Value]) -> reso_client::Result<()> {
+        self.calls.push(format!("write:{}", records.len()));
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> reso_client::Result<()> {
+        self.calls.push("flush".to_string());
+        Ok(())
+    }
+
+    async fn commit(&mut self, checkpoint: &ReplicationCheckpoint) -> reso_client::Result<()> {
+        self.calls.push(format!(
+            "commit:{}:{}",
+            checkpoint.records_committed,
+            checkpoint.next_link.is_some()
+        ));
+        Ok(())
+    }
+
+    async fn last_checkpoint(&mut self) -> reso_client::Result<Option<ReplicationCheckpoint>> {
+        Ok(self.resume_from.clone())
+    }
+
+    async fn resume(&mut self, checkpoint: &ReplicationCheckpoint) -> reso_client::Result<()> {
+        self.calls
+            .push(format!("resume:{}", checkpoint.records_committed));
+        Ok(())
+    }
+
+    async fn reset(&mut self) -> reso_client::Result<()> {
+        self.calls.push("reset".to_string());
+        Ok(())
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+}
+
+#[tokio::test]
+async fn test_replicate_into_commits_after_each_page() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut sink = CallLogSink {
+        batch_size: Some(1),
+        ..Default::default()
+    };
+    let progress = client.replicate_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(progress.records_fetched, 3);
+    assert_eq!(
+        sink.calls,
+        vec![
+            "reset",
+            "write:1",
+            "write:1",
+            "flush",
+            "commit:2:true",
+            "write:1",
+            "flush",
+            "commit:3:false"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_replicate_into_memory_sink() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut sink = MemorySink::new();
+    client.replicate_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(sink.records().len(), 3);
+    let checkpoint = sink.checkpoint().unwrap();
+    assert_eq!(checkpoint.resource, "Property");
+    assert_eq!(checkpoint.records_committed, 3);
+    assert!(checkpoint.is_complete());
+}
+
+#[tokio::test]
+async fn test_replicate_into_resumes_from_checkpoint() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut sink = CallLogSink {
+        resume_from: Some(ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication?$top=2".to_string()),
+            next_link: Some(format!("{}/Property/replication?page=2", mock_server.uri())),
+            records_committed: 2,
+        }),
+        ..Default::default()
+    };
+    let progress = client.replicate_into(&query, &mut sink).await.unwrap();
+
+    // Only the second page is fetched, and the committed count continues
+    assert_eq!(progress.pages_fetched, 1);
+    assert_eq!(progress.records_resumed, 2);
+    assert_eq!(progress.records_fetched, 1);
+    assert_eq!(progress.records_done(), 3);
+    assert_eq!(
+        sink.calls,
+        vec!["resume:2", "write:1", "flush", "commit:3:false"]
+    );
+}
+
+#[tokio::test]
+async fn test_replicate_into_ignores_checkpoint_of_other_query() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    // An interrupted incremental run on the same resource
+    let mut sink = CallLogSink {
+        resume_from: Some(ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some(
+                "Property/replication?$filter=ModificationTimestamp%20ge%202024-01-01T00%3A00%3A00Z&$top=2"
+                    .to_string(),
+            ),
+            next_link: Some(format!("{}/Property/replication?page=2", mock_server.uri())),
+            records_committed: 2,
+        }),
+        ..Default::default()
+    };
+    let progress = client.replicate_into(&query, &mut sink).await.unwrap();
+
+    // Both pages are fetched and the count starts from zero
+    assert_eq!(progress.pages_fetched, 2);
+    assert_eq!(progress.records_fetched, 3);
+    assert_eq!(sink.calls.first().unwrap(), "reset");
+    assert_eq!(sink.calls.last().unwrap(), "commit:3:false");
+}
+
+#[tokio::test]
+async fn test_replicate_into_restarts_after_completed_checkpoint() {
+    let mock_server = MockServer::start().await;
+    mount_two_pages(&mock_server).await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut sink = CallLogSink {
+        resume_from: Some(ReplicationCheckpoint {
+            resource: "Property".to_string(),
+            query: Some("Property/replication?$top=2".to_string()),
+            next_link: None,
+            records_committed: 3,
+        }),
+        ..Default::default()
+    };
+    let progress = client.replicate_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(progress.pages_fetched, 2);
+    assert_eq!(sink.calls.last().unwrap(), "commit:3:false");
+}
+
+#[tokio::test]
+async fn test_replicate_into_does_not_commit_failed_page() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("$top", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{"ListingKey": "1"}, {"ListingKey": "2"}]
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let mut sink = MemorySink::new();
+    let result = client.replicate_into(&query, &mut sink).await;
+
+    assert!(result.is_err());
+    assert_eq!(sink.records().len(), 2);
+    assert_eq!(sink.checkpoint().unwrap().next_link, Some(next));
+}
+
+#[tokio::test]
+async fn test_cancelled_drain_keeps_last_complete_page() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"value": [{"ListingKey": "3"}]}))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("$top", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{"ListingKey": "1"}, {"ListingKey": "2"}]
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+
+    let token = CancellationToken::new();
+    let canceller = {
+        let token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            token.cancel();
+        })
+    };
+
+    let mut sink = MemorySink::new();
+    let result = client
+        .replicate(&query)
+        .with_options(RequestOptions::new().with_cancellation(token))
+        .drain_into(&mut sink)
+        .await;
+    canceller.await.unwrap();
+
+    assert!(matches!(result, Err(ResoError::Cancelled)));
+    assert_eq!(sink.records().len(), 2);
+    let checkpoint = sink.checkpoint().unwrap();
+    assert_eq!(checkpoint.next_link, Some(next));
+    assert_eq!(checkpoint.records_committed, 2);
+}
+
+/// Server whose odd-numbered responses stall halfway through the body
+///
+/// Serves one replication page with two records and no next link. wiremock
+/// can only delay a whole response, so this writes the chunks by hand.
+fn start_stalling_server() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let uri = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for (n, stream) in listener.incoming().enumerate() {
+            if let Ok(stream) = stream {
+                std::thread::spawn(move || serve_page(stream, n % 2 == 0));
+            }
+        }
+    });
+
+    uri
+}
+
+fn serve_page(mut stream: std::net::TcpStream, stall: bool) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+        line.clear();
+    }
+
+    let chunk = |data: &str| format!("{:x}\r\n{}\r\n", data.len(), data);
+    let head = concat!(
+        "HTTP/1.1 200 OK\r\n",
+        "Content-Type: application/json\r\n",
+        "Transfer-Encoding: chunked\r\n",
+        "Connection: close\r\n\r\n"
+    );
+    let _ = write!(
+        stream,
+        "{}{}",
+        head,
+        chunk(r#"{"value": [{"ListingKey": "1"},"#)
+    );
+    let _ = stream.flush();
+    if stall {
+        std::thread::sleep(Duration::from_secs(2));
+    }
+    let _ = write!(stream, "{}0\r\n\r\n", chunk(r#"{"ListingKey": "2"}]}"#));
+}
+
+#[tokio::test]
+async fn test_interrupted_page_is_read_again_from_its_start() {
+    let uri = start_stalling_server();
+    let client = ResoClient::with_config(ClientConfig::new(uri, "test-token")).unwrap();
+    let query = ReplicationQueryBuilder::new("Property")
+        .top(2)
+        .build()
+        .unwrap();
+    let options = RequestOptions::new().with_timeout(Duration::from_millis(300));
+    let key = |record: Option<serde_json::Value>| record.unwrap()["ListingKey"].clone();
+
+    // next_record, then a next_page that times out partway through the page
+    let mut stream = client.replicate(&query).with_options(options.clone());
+    assert_eq!(key(stream.next_record().await.unwrap()), "1");
+    assert!(matches!(
+        stream.next_page().await,
+        Err(ResoError::Timeout(_))
+    ));
+    let page = stream.next_page().await.unwrap().unwrap();
+    let keys: Vec<_> = page
+        .records
+        .iter()
+        .map(|r| r["ListingKey"].clone())
+        .collect();
+    assert_eq!(keys, vec!["1", "2"]);
+    assert!(stream.next_page().await.unwrap().is_none());
+
+    // next_record timing out behaves the same way
+    let mut stream = client.replicate(&query).with_options(options);
+    assert_eq!(key(stream.next_record().await.unwrap()), "1");
+    assert!(matches!(
+        stream.next_record().await,
+        Err(ResoError::Timeout(_))
+    ));
+    assert_eq!(key(stream.next_record().await.unwrap()), "1");
+    assert_eq!(key(stream.next_record().await.unwrap()), "2");
+    assert!(stream.next_record().await.unwrap().is_none());
+    assert_eq!(stream.progress().pages_fetched, 1);
+    assert_eq!(stream.progress().records_fetched, 2);
+}
@@ -0,0 +1,213 @@
+// Integration tests for retries, rate limiting, per-call timeouts and OAuth client credentials
+#![cfg(feature = "client")]
+
+use reso_client::{
+    ClientConfig, ClientCredentials, QueryBuilder, RateLimiter, RequestOptions, ResoClient,
+    ResoError, RetryPolicy,
+};
+use std::time::{Duration, Instant};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn fast_retries(max_retries: u32) -> RetryPolicy {
+    RetryPolicy::new(max_retries).with_initial_backoff(Duration::from_millis(1))
+}
+
+fn ok_body() -> serde_json::Value {
+    serde_json::json!({"value": [{"ListingKey": "1"}]})
+}
+
+#[tokio::test]
+async fn test_retries_transient_failures() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(ok_body()))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = ClientConfig::new(mock_server.uri(), "test-token").with_retry(fast_retries(2));
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    let response = client.execute(&query).await.unwrap();
+    assert_eq!(response["value"][0]["ListingKey"], "1");
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("boom"))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Member"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = ClientConfig::new(mock_server.uri(), "test-token").with_retry(fast_retries(2));
+    let client = ResoClient::with_config(config).unwrap();
+
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let err = client.execute(&query).await.unwrap_err();
+    assert!(matches!(err, ResoError::ServerError { .. }));
+
+    // Non-transient errors are not retried
+    let query = QueryBuilder::new("Member").build().unwrap();
+    let err = client.execute(&query).await.unwrap_err();
+    assert!(matches!(err, ResoError::NotFound { .. }));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_spaces_requests() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(ok_body()))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let config = ClientConfig::new(mock_server.uri(), "test-token")
+        .with_rate_limiter(RateLimiter::with_interval(Duration::from_millis(50)));
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        client.execute(&query).await.unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_client_credentials_fetch_and_renew_token() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("client_id=my-client"))
+        .and(body_string_contains("scope=api"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "stale-token",
+            "token_type": "Bearer",
+            "expires_in": 3600
+        })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "access_token": "fresh-token",
+            "expires_in": 3600
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(header("Authorization", "Bearer stale-token"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(header("Authorization", "Bearer fresh-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(ok_body()))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let credentials = ClientCredentials::new(
+        format!("{}/token", mock_server.uri()),
+        "my-client",
+        "my-secret",
+    )
+    .with_scope("api");
+    let config = ClientConfig::new(mock_server.uri(), "").with_client_credentials(credentials);
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    client.execute(&query).await.unwrap();
+    // The renewed token is cached for later requests
+    client.execute(&query).await.unwrap();
+
+    let token_requests = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/token")
+        .count();
+    assert_eq!(token_requests, 2);
+}
+
+#[tokio::test]
+async fn test_token_endpoint_errors_are_surfaced() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("invalid_client"))
+        .mount(&mock_server)
+        .await;
+
+    let credentials = ClientCredentials::new(format!("{}/token", mock_server.uri()), "id", "wrong");
+    let config = ClientConfig::new(mock_server.uri(), "").with_client_credentials(credentials);
+    let client = ResoClient::with_config(config).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+
+    let err = client.execute(&query).await.unwrap_err();
+    assert!(matches!(err, ResoError::Unauthorized { .. }));
+}
+
+#[tokio::test]
+async fn test_per_call_timeout_overrides_client_timeout() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/Property/$count"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("42")
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let query = QueryBuilder::new("Property").count().build().unwrap();
+
+    let options = RequestOptions::new().with_timeout(Duration::from_millis(100));
+    let start = Instant::now();
+    let result = client.execute_count_with(&query, &options).await;
+
+    assert!(matches!(result, Err(ResoError::Timeout(_))));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
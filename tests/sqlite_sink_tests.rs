@@ -0,0 +1,167 @@
+// Integration tests for the SQLite mirror sink
+#![cfg(all(feature = "sqlite", feature = "client"))]
+
+use reso_client::{ClientConfig, RecordSink, ReplicationCheckpoint, ResoClient, SqliteSink};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_full_then_incremental_sync() {
+    let mock_server = MockServer::start().await;
+
+    // Incremental run: only the changed listing comes back
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param(
+            "$filter",
+            "ModificationTimestamp ge 2025-02-01T00:00:00Z",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "2", "ListPrice": 250000, "ModificationTimestamp": "2025-03-01T00:00:00Z"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Initial full run
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [
+                {"ListingKey": "1", "ListPrice": 100000, "ModificationTimestamp": "2025-01-01T00:00:00Z"},
+                {"ListingKey": "2", "ListPrice": 200000, "ModificationTimestamp": "2025-02-01T00:00:00Z"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("mirror.db");
+
+    let mut sink = SqliteSink::open(&db, "Property", &["ListingKey"]).unwrap();
+    let query = sink
+        .incremental_query("ModificationTimestamp", None)
+        .unwrap()
+        .build()
+        .unwrap();
+    client.replicate_into(&query, &mut sink).await.unwrap();
+    assert_eq!(sink.row_count().unwrap(), 2);
+    drop(sink);
+
+    // Reopen the same file and pull only what changed
+    let mut sink = SqliteSink::open(&db, "Property", &["ListingKey"]).unwrap();
+    let query = sink
+        .incremental_query("ModificationTimestamp", None)
+        .unwrap()
+        .build()
+        .unwrap();
+    client.replicate_into(&query, &mut sink).await.unwrap();
+
+    assert_eq!(sink.row_count().unwrap(), 2);
+    let price: i64 = sink
+        .connection()
+        .query_row(
+            "SELECT ListPrice FROM Property WHERE ListingKey = '2'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(price, 250000);
+    assert_eq!(
+        sink.high_water_mark("ModificationTimestamp").unwrap(),
+        Some("2025-03-01T00:00:00Z".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_interrupted_incremental_sync_resumes_from_its_mark() {
+    let mock_server = MockServer::start().await;
+    let next = format!("{}/Property/replication?page=2", mock_server.uri());
+
+    // The second page fails once, then holds the listing changed in between
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "2", "ModificationTimestamp": "2025-02-01T00:00:00Z"}]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Records are not returned in timestamp order
+    Mock::given(method("GET"))
+        .and(path("/Property/replication"))
+        .and(query_param(
+            "$filter",
+            "ModificationTimestamp ge 2025-01-01T00:00:00Z",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("next", next.as_str())
+                .set_body_json(serde_json::json!({
+                    "value": [{"ListingKey": "3", "ModificationTimestamp": "2025-03-01T00:00:00Z"}]
+                })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client =
+        ResoClient::with_config(ClientConfig::new(mock_server.uri(), "test-token")).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("mirror.db");
+
+    // A finished earlier sync left one listing in the mirror
+    let mut sink = SqliteSink::open(&db, "Property", &["ListingKey"]).unwrap();
+    sink.write_batch(&[serde_json::json!({
+        "ListingKey": "1",
+        "ModificationTimestamp": "2025-01-01T00:00:00Z"
+    })])
+    .await
+    .unwrap();
+    sink.commit(&ReplicationCheckpoint {
+        resource: "Property".to_string(),
+        query: Some("Property/replication".to_string()),
+        next_link: None,
+        records_committed: 1,
+    })
+    .await
+    .unwrap();
+
+    let query = sink
+        .incremental_query("ModificationTimestamp", None)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(client.replicate_into(&query, &mut sink).await.is_err());
+    drop(sink);
+
+    // The mirror now holds a later timestamp, but the run keeps its mark
+    let mut sink = SqliteSink::open(&db, "Property", &["ListingKey"]).unwrap();
+    let resumed = sink
+        .incremental_query("ModificationTimestamp", None)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(resumed.to_odata_string(), query.to_odata_string());
+    client.replicate_into(&resumed, &mut sink).await.unwrap();
+
+    assert_eq!(sink.row_count().unwrap(), 3);
+    assert_eq!(
+        sink.high_water_mark("ModificationTimestamp").unwrap(),
+        Some("2025-03-01T00:00:00Z".to_string())
+    );
+}
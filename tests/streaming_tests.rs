@@ -0,0 +1,165 @@
+// Integration tests for record-by-record streaming of query results
+#![cfg(feature = "client")]
+
+use reso_client::{ClientConfig, QueryBuilder, RequestOptions, ResoClient, ResoError};
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer) -> ResoClient {
+    ResoClient::with_config(ClientConfig::new(server.uri(), "token")).unwrap()
+}
+
+#[tokio::test]
+async fn test_stream_records_follows_next_links() {
+    let server = MockServer::start().await;
+    let next = format!("{}/Property?$skip=2", server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$skip", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{"ListingKey": "3", "ListPrice": 300000}]
+        })))
+        .mount(&server)
+        .await;
+
+    // The next link follows the records, as many servers send it
+    let first_page = format!(
+        r#"{{"@odata.context": "$metadata#Property", "value": [
+            {{"ListingKey": "1", "ListPrice": 100000, "PublicRemarks": "Has \"quotes\" and ]"}},
+            {{"ListingKey": "2", "ListPrice": null}}
+        ], "@odata.nextLink": "{}"}}"#,
+        next
+    );
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .and(query_param("$top", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(first_page, "application/json"))
+        .mount(&server)
+        .await;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Listing {
+        listing_key: String,
+        list_price: Option<u64>,
+    }
+
+    let client = client(&server);
+    let query = QueryBuilder::new("Property").top(2).build().unwrap();
+    let mut stream = client.stream_records(&query);
+
+    let first = stream.next_record().await.unwrap().unwrap();
+    assert_eq!(first["PublicRemarks"], "Has \"quotes\" and ]");
+
+    let mut listings = Vec::new();
+    while let Some(listing) = stream.next_record_as::<Listing>().await.unwrap() {
+        listings.push(listing);
+    }
+    assert_eq!(
+        listings,
+        [
+            Listing {
+                listing_key: "2".into(),
+                list_price: None
+            },
+            Listing {
+                listing_key: "3".into(),
+                list_price: Some(300000)
+            },
+        ]
+    );
+    assert_eq!(stream.pages_fetched(), 2);
+    assert_eq!(stream.records_fetched(), 3);
+    assert!(stream.next_record().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_stream_records_reports_truncated_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/Property"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"{"value": [{"ListingKey": "1"}, {"ListingKey": "#,
+            "application/json",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let mut stream = client.stream_records(&query);
+
+    assert_eq!(
+        stream.next_record().await.unwrap().unwrap()["ListingKey"],
+        "1"
+    );
+    assert!(matches!(
+        stream.next_record().await.unwrap_err(),
+        ResoError::Parse(_)
+    ));
+}
+
+/// Server whose first response stalls halfway through a two-record page
+///
+/// wiremock can only delay a whole response, so this writes the chunks by hand.
+fn start_stalling_server() -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let uri = format!("http://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for (n, stream) in listener.incoming().enumerate() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            std::thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+
+                let chunk = |data: &str| format!("{:x}\r\n{}\r\n", data.len(), data);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n{}",
+                    chunk(r#"{"value": [{"ListingKey": "1"},"#)
+                );
+                let _ = stream.flush();
+                if n == 0 {
+                    std::thread::sleep(Duration::from_secs(2));
+                }
+                let _ = write!(stream, "{}0\r\n\r\n", chunk(r#"{"ListingKey": "2"}]}"#));
+            });
+        }
+    });
+
+    uri
+}
+
+#[tokio::test]
+async fn test_stream_records_reads_interrupted_page_again() {
+    let client =
+        ResoClient::with_config(ClientConfig::new(start_stalling_server(), "token")).unwrap();
+    let query = QueryBuilder::new("Property").build().unwrap();
+    let mut stream = client
+        .stream_records(&query)
+        .with_options(RequestOptions::new().with_timeout(Duration::from_millis(300)));
+    let key = |record: Option<serde_json::Value>| record.unwrap()["ListingKey"].clone();
+
+    assert_eq!(key(stream.next_record().await.unwrap()), "1");
+    assert!(matches!(
+        stream.next_record().await,
+        Err(ResoError::Timeout(_))
+    ));
+
+    // The page is requested again and read from its start
+    assert_eq!(key(stream.next_record().await.unwrap()), "1");
+    assert_eq!(key(stream.next_record().await.unwrap()), "2");
+    assert!(stream.next_record().await.unwrap().is_none());
+    assert_eq!(stream.pages_fetched(), 2);
+}
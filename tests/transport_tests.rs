@@ -0,0 +1,73 @@
+// Integration tests for custom transports
+#![cfg(feature = "client")]
+
+use reso_client::transport::{HttpRequest, HttpResponse};
+use reso_client::{
+    async_trait, ClientConfig, QueryBuilder, ReplicationQueryBuilder, ResoClient, ResoError,
+    Result, Transport,
+};
+use std::sync::{Arc, Mutex};
+
+/// Serves a fixed body per path and records the requests it saw
+#[derive(Clone, Default)]
+struct InMemory {
+    seen: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+#[async_trait]
+impl Transport for InMemory {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let (status, next, body) = match request.uri().path() {
+            "/odata/Property" => (200, None, r#"{"value": [{"ListingKey": "1"}]}"#),
+            "/odata/Property/replication" if request.uri().query() == Some("page=2") => {
+                (200, None, r#"{"value": [{"ListingKey": "3"}]}"#)
+            }
+            "/odata/Property/replication" => (
+                200,
+                Some("http://mls.test/odata/Property/replication?page=2"),
+                r#"{"value": [{"ListingKey": "1"}, {"ListingKey": "2"}]}"#,
+            ),
+            _ => (404, None, r#"{"error": {"message": "no such resource"}}"#),
+        };
+        self.seen.lock().unwrap().push(request);
+
+        let mut response = http::Response::builder().status(status);
+        if let Some(next) = next {
+            response = response.header("next", next);
+        }
+        Ok(response.body(body.as_bytes().to_vec()).unwrap())
+    }
+}
+
+#[tokio::test]
+async fn test_client_sends_through_custom_transport() {
+    let transport = InMemory::default();
+    let config =
+        ClientConfig::new("http://mls.test/odata", "secret").with_transport(transport.clone());
+    let client = ResoClient::with_config(config).unwrap();
+
+    let query = QueryBuilder::new("Property").top(1).build().unwrap();
+    let json = client.execute(&query).await.unwrap();
+    assert_eq!(json["value"][0]["ListingKey"], "1");
+
+    let query = ReplicationQueryBuilder::new("Property").build().unwrap();
+    let mut stream = client.replicate(&query);
+    let mut keys = Vec::new();
+    while let Some(record) = stream.next_record().await.unwrap() {
+        keys.push(record["ListingKey"].as_str().unwrap().to_string());
+    }
+    assert_eq!(keys, ["1", "2", "3"]);
+
+    let query = QueryBuilder::new("Member").build().unwrap();
+    assert!(matches!(
+        client.execute(&query).await.unwrap_err(),
+        ResoError::NotFound { .. }
+    ));
+
+    // Requests went through the middleware chain, including authentication
+    let seen = transport.seen.lock().unwrap();
+    assert_eq!(seen.len(), 4);
+    assert!(seen
+        .iter()
+        .all(|request| request.headers()["authorization"] == "Bearer secret"));
+}